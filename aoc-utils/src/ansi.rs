@@ -0,0 +1,63 @@
+//! Draw grids in the terminal as blocks of color
+//!
+//! Each character holds two cells, one above the other: an upper half block, drawn in the top
+//! cell's color on a background of the bottom cell's color. Terminal characters are about
+//! twice as tall as they're wide, so the cells come out square. The colors are 24-bit ANSI
+//! escapes, which most terminals understand.
+
+use crate::image::Rgb;
+use crate::Grid;
+use std::fmt::Write;
+
+/// Draw the grid, coloring each cell with `color`
+///
+/// Every line ends by resetting the colors, then a newline.
+pub fn render_blocks<T>(grid: &Grid<T>, mut color: impl FnMut(&T) -> Rgb) -> String {
+    let rows: Vec<Vec<Rgb>> = grid
+        .rows()
+        .map(|row| row.iter().map(&mut color).collect())
+        .collect();
+
+    let mut out = String::new();
+    for pair in rows.chunks(2) {
+        // Only change the colors when they're different from the last character's
+        let mut last = None;
+        for (x, &top) in pair[0].iter().enumerate() {
+            let bottom = pair.get(1).map(|row| row[x]);
+            if last != Some((top, bottom)) {
+                let [r, g, b] = top;
+                write!(out, "\x1b[38;2;{};{};{}m", r, g, b).unwrap();
+                match bottom {
+                    Some([r, g, b]) => write!(out, "\x1b[48;2;{};{};{}m", r, g, b).unwrap(),
+                    // An odd row out at the bottom sits on the terminal's own background
+                    None => out.push_str("\x1b[49m"),
+                }
+                last = Some((top, bottom));
+            }
+            out.push('▀');
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_blocks() {
+        let grid = Grid::from_vec(2, 3, vec![0, 0, 1, 0, 1, 1]).unwrap();
+        let palette = [[0, 0, 0], [255, 255, 255]];
+        let drawn = render_blocks(&grid, |&cell| palette[cell]);
+        assert_eq!(
+            drawn,
+            concat!(
+                "\x1b[38;2;0;0;0m\x1b[48;2;255;255;255m▀",
+                "\x1b[38;2;0;0;0m\x1b[48;2;0;0;0m▀\x1b[0m\n",
+                "\x1b[38;2;255;255;255m\x1b[49m▀▀\x1b[0m\n",
+            )
+        );
+        assert_eq!(render_blocks(&Grid::new(3, 0, 0), |_| [0, 0, 0]), "");
+    }
+}
@@ -0,0 +1,81 @@
+//! Finding where repeatedly applying a function starts going around in circles
+
+/// Where a sequence of states starts repeating
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T> {
+    /// The number of steps before the first state that's part of the cycle
+    pub start: usize,
+    /// The number of steps to go around the cycle once
+    pub length: usize,
+    /// The first state that's part of the cycle, which is also the first to appear twice
+    pub state: T,
+}
+
+/// Find the cycle in the states reached by applying `step` over and over, starting at `start`
+///
+/// This uses Brent's algorithm, so no states are kept around and they don't need to be
+/// hashable, at the cost of calling `step` a few more times than the cycle is long. It never
+/// returns if the states don't repeat.
+pub fn find_cycle<T: Clone + Eq>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle<T> {
+    // Find the length: the hare runs ahead in stretches that double each time, with the
+    // tortoise waiting where the stretch started, until the hare comes back to it
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Find the start: with the hare one cycle ahead, they meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut offset = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+    }
+
+    Cycle {
+        start: offset,
+        length,
+        state: tortoise,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 1, 3, 9, 27, 81, 43, 29, 87, 61, 83, 49, 47, 41, 23, 69, 7, 21, 63, 89, 67, 1, ...
+        let cycle = find_cycle(1, |&n| n * 3 % 100);
+        assert_eq!(cycle.start, 0);
+        assert_eq!(cycle.length, 20);
+
+        // 0, 10, 20, 30, 40, 20, ...
+        let cycle = find_cycle(0, |&n| if n == 40 { 20 } else { n + 10 });
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 2,
+                length: 3,
+                state: 20
+            }
+        );
+
+        let cycle = find_cycle(7, |&n| n);
+        assert_eq!((cycle.start, cycle.length), (0, 1));
+    }
+}
@@ -0,0 +1,367 @@
+//! Points and directions on a 2D grid, and vectors in 3D space
+//!
+//! The 2D types follow the same coordinates as [`Grid`](crate::Grid): `y` grows downwards, so
+//! [`Direction::Up`] decreases it.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub};
+
+/// A position on a 2D grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    /// The column, growing to the right
+    pub x: i64,
+    /// The row, growing downwards
+    pub y: i64,
+}
+
+/// The difference between two points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2 {
+    /// The change in columns
+    pub x: i64,
+    /// The change in rows
+    pub y: i64,
+}
+
+impl Point {
+    /// The point at `(0, 0)`
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    /// Create a point
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// The point one step away in a direction
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.vec()
+    }
+
+    /// The number of steps between two points, moving only up, down, left, and right
+    pub fn manhattan_distance(self, other: Point) -> i64 {
+        (self - other).manhattan_length()
+    }
+
+    /// The points one step away, up, down, left, and right
+    pub fn neighbors(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .iter()
+            .map(move |&direction| self.step(direction))
+    }
+}
+
+impl Vec2 {
+    /// Create a vector
+    pub const fn new(x: i64, y: i64) -> Self {
+        Vec2 { x, y }
+    }
+
+    /// The number of steps the vector covers, moving only up, down, left, and right
+    pub fn manhattan_length(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl From<Point> for (i64, i64) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Point;
+
+    fn add(self, other: Vec2) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+
+    fn sub(self, other: Point) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, factor: i64) -> Vec2 {
+        Vec2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+/// One of the four ways to move on a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// Towards smaller `y`
+    Up,
+    /// Towards larger `y`
+    Down,
+    /// Towards smaller `x`
+    Left,
+    /// Towards larger `x`
+    Right,
+}
+
+impl Direction {
+    /// Every direction, in the order up, down, left, right
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// The direction after a quarter turn to the left
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// The direction after a quarter turn to the right
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// The opposite direction
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// One step in this direction
+    pub fn vec(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(0, -1),
+            Direction::Down => Vec2::new(0, 1),
+            Direction::Left => Vec2::new(-1, 0),
+            Direction::Right => Vec2::new(1, 0),
+        }
+    }
+
+    /// The direction for `U`, `D`, `L`, or `R`
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The letter for the direction, `U`, `D`, `L`, or `R`
+    pub fn letter(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+/// A position or velocity in 3D space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3 {
+    /// The first coordinate
+    pub x: i64,
+    /// The second coordinate
+    pub y: i64,
+    /// The third coordinate
+    pub z: i64,
+}
+
+impl Vec3 {
+    /// Create a vector
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    /// The sum of the absolute values of the coordinates
+    pub fn manhattan_length(self) -> i64 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    /// The sign of each coordinate: -1, 0, or 1
+    pub fn signum(self) -> Self {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// The pull towards another position, one unit along each axis where they differ
+    pub fn gravity_towards(self, other: Vec3) -> Self {
+        (other - self).signum()
+    }
+}
+
+/// The energy of a moving body: the length of its position times the length of its velocity
+pub fn energy(position: Vec3, velocity: Vec3) -> i64 {
+    position.manhattan_length() * velocity.manhattan_length()
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, factor: i64) -> Vec3 {
+        Vec3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Sum for Vec3 {
+    fn sum<I: Iterator<Item = Vec3>>(iter: I) -> Vec3 {
+        iter.fold(Vec3::default(), Add::add)
+    }
+}
+
+impl Index<usize> for Vec3 {
+    type Output = i64;
+
+    /// # Panics
+    ///
+    /// Panics if the axis isn't 0, 1, or 2.
+    fn index(&self, axis: usize) -> &i64 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("There's no axis {}", axis),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        match axis {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("There's no axis {}", axis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let point = Point::new(3, -4);
+        assert_eq!(point.manhattan_distance(Point::ORIGIN), 7);
+        assert_eq!(point.step(Direction::Up), Point::new(3, -5));
+        assert_eq!(point + Direction::Left.vec() * 3, Point::new(0, -4));
+        assert_eq!(point - Point::new(1, 1), Vec2::new(2, -5));
+        assert_eq!(point.neighbors().count(), 4);
+        assert_eq!(<(i64, i64)>::from(point), (3, -4));
+    }
+
+    #[test]
+    fn test_direction() {
+        for &direction in &Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+            assert_eq!(direction.vec() + direction.reverse().vec(), Vec2::default());
+            assert_eq!(Direction::from_letter(direction.letter()), Some(direction));
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::from_letter('X'), None);
+    }
+
+    #[test]
+    fn test_vec3() {
+        let position = Vec3::new(3, -5, 0);
+        assert_eq!(
+            position.gravity_towards(Vec3::new(1, -5, 2)),
+            Vec3::new(-1, 0, 1)
+        );
+        assert_eq!(position + Vec3::new(1, 1, 1) * 2, Vec3::new(5, -3, 2));
+        assert_eq!(position[1], -5);
+        assert_eq!(energy(position, Vec3::new(-1, 0, 1)), 16);
+
+        let total: Vec3 = vec![position, -position, Vec3::new(0, 0, 1)]
+            .into_iter()
+            .sum();
+        assert_eq!(total, Vec3::new(0, 0, 1));
+    }
+}
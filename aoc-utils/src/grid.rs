@@ -0,0 +1,265 @@
+//! A rectangle of cells, like a map drawn in ASCII or the pixels of an image
+//!
+//! Cells are addressed by `(x, y)`, with `(0, 0)` at the top left and `y` growing downwards.
+//! Coordinates are signed, so stepping off an edge is just a lookup that finds nothing.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// The four cells next to a cell, up, down, left, and right
+const ORTHOGONAL: [(i64, i64); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+/// Why some text couldn't be parsed as a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// A line isn't as wide as the first one
+    Ragged {
+        /// The line, counting from 0
+        y: usize,
+    },
+    /// A character the cells can't be made from
+    UnexpectedChar {
+        /// The character
+        c: char,
+        /// Where it is
+        position: (i64, i64),
+    },
+    /// The number of cells doesn't match the width and height
+    WrongSize,
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Ragged { y } => write!(f, "Line {} is a different width", y),
+            GridError::UnexpectedChar { c, position } => {
+                write!(f, "Unexpected character {:?} at {:?}", c, position)
+            }
+            GridError::WrongSize => write!(f, "The cells don't fill the grid"),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// A rectangle of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid with every cell set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Create a grid from its cells, row by row from the top left
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if cells.len() != width * height {
+            return Err(GridError::WrongSize);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parse a grid drawn in ASCII, one line per row, turning each character into a cell
+    ///
+    /// Every line has to be the same width. `cell` returns `None` for characters that aren't
+    /// allowed.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, GridError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in input.lines().enumerate() {
+            let start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let position = (x as i64, y as i64);
+                cells.push(cell(c).ok_or(GridError::UnexpectedChar { c, position })?);
+            }
+            let line_width = cells.len() - start;
+            if *width.get_or_insert(line_width) != line_width {
+                return Err(GridError::Ragged { y });
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// Get the number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (x, y): (i64, i64)) -> Option<usize> {
+        if self.contains((x, y)) {
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a position is inside the grid
+    pub fn contains(&self, (x, y): (i64, i64)) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// Get the cell at a position, or `None` if it's outside the grid
+    pub fn get(&self, position: (i64, i64)) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    /// Get the cell at a position to change it, or `None` if it's outside the grid
+    pub fn get_mut(&mut self, position: (i64, i64)) -> Option<&mut T> {
+        self.index_of(position)
+            .map(move |index| &mut self.cells[index])
+    }
+
+    /// Every position in the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (i64, i64)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x as i64, y as i64)))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Every cell, row by row, without its position
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Every row, from the top
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// The positions up, down, left, and right of a position that are inside the grid
+    pub fn neighbors(&self, (x, y): (i64, i64)) -> impl Iterator<Item = (i64, i64)> + '_ {
+        ORTHOGONAL
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |&position| self.contains(position))
+    }
+
+    /// The position of the first cell, row by row, that matches
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(i64, i64)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// Make a grid of the same size by transforming every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(i64, i64)> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the position is outside the grid.
+    fn index(&self, position: (i64, i64)) -> &T {
+        match self.get(position) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside the grid", position),
+        }
+    }
+}
+
+impl<T> IndexMut<(i64, i64)> for Grid<T> {
+    fn index_mut(&mut self, position: (i64, i64)) -> &mut T {
+        match self.index_of(position) {
+            Some(index) => &mut self.cells[index],
+            None => panic!("{:?} is outside the grid", position),
+        }
+    }
+}
+
+/// Draw the grid with each cell's `Display`, one line per row
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::parse("#..\n.#.\n", |c| Some(c == '#')).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((1, 1)), Some(&true));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, -1)), None);
+        assert_eq!(grid.position(|&cell| cell), Some((0, 0)));
+
+        grid[(2, 1)] = true;
+        let mut neighbors: Vec<_> = grid.neighbors((2, 1)).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(1, 1), (2, 0)]);
+        assert_eq!(
+            grid.iter().filter(|(_, &cell)| cell).count(),
+            grid.cells().iter().filter(|&&cell| cell).count()
+        );
+
+        let drawn = grid.map(|&cell| if cell { '#' } else { '.' });
+        assert_eq!(drawn.to_string(), "#..\n.##\n");
+        assert_eq!(drawn.rows().count(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Grid::parse("##\n#\n", Some),
+            Err(GridError::Ragged { y: 1 })
+        );
+        assert_eq!(
+            Grid::parse("#x\n", |c| if c == '#' { Some(1) } else { None }),
+            Err(GridError::UnexpectedChar {
+                c: 'x',
+                position: (1, 0)
+            })
+        );
+        assert_eq!(Grid::from_vec(2, 2, vec![0; 3]), Err(GridError::WrongSize));
+        assert_eq!(Grid::new(0, 3, 0u8).rows().count(), 3);
+    }
+}
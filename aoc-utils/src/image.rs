@@ -0,0 +1,238 @@
+//! Save grids as image files, with each cell drawn as a square of pixels
+//!
+//! PPM needs nothing extra. PNG needs the `png` feature, and animated GIF needs the `gif`
+//! feature.
+
+use crate::Grid;
+use std::io::{self, Write};
+
+/// A color, as red, green, and blue
+pub type Rgb = [u8; 3];
+
+/// Parse a color written in hex, like `f9e2af` or `#f9e2af`
+pub fn parse_color(input: &str) -> Option<Rgb> {
+    let hex = input.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+/// Parse a list of colors written in hex, separated by commas
+pub fn parse_palette(input: &str) -> Result<Vec<Rgb>, String> {
+    input
+        .split(',')
+        .map(|color| parse_color(color).ok_or_else(|| format!("Invalid color {:?}", color)))
+        .collect()
+}
+
+/// The cells turned into pixels with `f`, row by row, with each cell blown up to `scale` by
+/// `scale` pixels
+fn scaled<T, U: Copy>(grid: &Grid<T>, scale: usize, mut f: impl FnMut(&T) -> U) -> Vec<U> {
+    let mut pixels = Vec::with_capacity(grid.cells().len() * scale * scale);
+    for row in grid.rows() {
+        let row: Vec<U> = row.iter().map(&mut f).collect();
+        for _ in 0..scale {
+            for &pixel in &row {
+                for _ in 0..scale {
+                    pixels.push(pixel);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// The pixels of the image as bytes, red, green, and blue for each
+fn pixels<T>(grid: &Grid<T>, scale: usize, color: impl FnMut(&T) -> Rgb) -> Vec<u8> {
+    scaled(grid, scale, color).concat()
+}
+
+/// Write the grid as a binary PPM, coloring each cell with `color`
+pub fn write_ppm<T>(
+    mut out: impl Write,
+    grid: &Grid<T>,
+    scale: usize,
+    color: impl FnMut(&T) -> Rgb,
+) -> io::Result<()> {
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    out.write_all(&pixels(grid, scale, color))?;
+    out.flush()
+}
+
+/// Write the grid as a PNG, coloring each cell with `color`
+#[cfg(feature = "png")]
+pub fn write_png<T>(
+    out: impl Write,
+    grid: &Grid<T>,
+    scale: usize,
+    color: impl FnMut(&T) -> Rgb,
+) -> io::Result<()> {
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(&pixels(grid, scale, color))
+        .map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Write the frames as an animated GIF that loops forever, coloring each cell with `color`
+///
+/// `delay` is how long each frame shows for, in hundredths of a second. Every frame has to be
+/// the same size, and there can be at most 256 colors between them.
+#[cfg(feature = "gif")]
+pub fn write_gif<T>(
+    out: impl Write,
+    frames: &[Grid<T>],
+    scale: usize,
+    delay: u16,
+    mut color: impl FnMut(&T) -> Rgb,
+) -> io::Result<()> {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, message);
+    let (width, height) = match frames.first() {
+        Some(frame) => (frame.width(), frame.height()),
+        None => return Err(invalid("There are no frames")),
+    };
+    if frames
+        .iter()
+        .any(|frame| (frame.width(), frame.height()) != (width, height))
+    {
+        return Err(invalid("The frames are different sizes"));
+    }
+    let scaled_size = |size: usize| {
+        size.checked_mul(scale)
+            .and_then(|size| u16::try_from(size).ok())
+            .ok_or_else(|| invalid("The scaled image is too big for a GIF"))
+    };
+    let (width, height) = (scaled_size(width)?, scaled_size(height)?);
+
+    // Every color gets an index in a palette shared by all the frames
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut indexes = HashMap::new();
+    let index_frames: Vec<Grid<usize>> = frames
+        .iter()
+        .map(|frame| {
+            frame.map(|cell| {
+                let rgb = color(cell);
+                *indexes.entry(rgb).or_insert_with(|| {
+                    palette.push(rgb);
+                    palette.len() - 1
+                })
+            })
+        })
+        .collect();
+    if palette.len() > 256 {
+        return Err(invalid("There are more than 256 colors"));
+    }
+
+    let mut encoder =
+        gif::Encoder::new(out, width, height, &palette.concat()).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for index_frame in &index_frames {
+        let frame = gif::Frame {
+            width,
+            height,
+            delay,
+            buffer: Cow::Owned(scaled(index_frame, scale, |&index| index as u8)),
+            ..gif::Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn color(&lit: &bool) -> Rgb {
+        if lit {
+            [255, 255, 255]
+        } else {
+            [0, 0, 0]
+        }
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        assert_eq!(parse_color("#1E1E2e"), Some([0x1e, 0x1e, 0x2e]));
+        assert_eq!(parse_color("fff"), None);
+        assert_eq!(parse_color("+fffff"), None);
+        assert_eq!(
+            parse_palette("000000, ffffff"),
+            Ok(vec![[0, 0, 0], [255, 255, 255]])
+        );
+        assert!(parse_palette("000000,white").is_err());
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let grid = Grid::from_vec(2, 1, vec![true, false]).unwrap();
+        let mut out = Vec::new();
+        write_ppm(&mut out, &grid, 2, color).unwrap();
+
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        for _ in 0..2 {
+            expected.extend_from_slice(&[255; 6]);
+            expected.extend_from_slice(&[0; 6]);
+        }
+        assert_eq!(out, expected);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        let grid = Grid::from_vec(2, 1, vec![true, false]).unwrap();
+        let mut out = Vec::new();
+        write_png(&mut out, &grid, 3, color).unwrap();
+
+        let decoder = png::Decoder::new(out.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (6, 3));
+        assert_eq!(&pixels[..9], &[255; 9]);
+        assert_eq!(&pixels[9..18], &[0; 9]);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_write_gif() {
+        let frames = vec![
+            Grid::from_vec(2, 1, vec![true, false]).unwrap(),
+            Grid::from_vec(2, 1, vec![false, true]).unwrap(),
+        ];
+        let mut out = Vec::new();
+        write_gif(&mut out, &frames, 2, 10, color).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(out.as_slice()).unwrap();
+        let mut decoded = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height, frame.delay), (4, 2, 10));
+            decoded.push(frame.buffer[..4].to_vec());
+        }
+        assert_eq!(decoded, vec![vec![255, 255, 255, 255], vec![0, 0, 0, 255]]);
+
+        let uneven = vec![Grid::new(1, 1, true), Grid::new(2, 1, true)];
+        assert!(write_gif(Vec::new(), &uneven, 1, 10, color).is_err());
+
+        // GIFs are at most 65535 pixels wide
+        let wide = vec![Grid::new(2, 1, true)];
+        let error = write_gif(Vec::new(), &wide, 40000, 10, color).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(write_gif(Vec::new(), &wide, usize::MAX, 10, color).is_err());
+    }
+}
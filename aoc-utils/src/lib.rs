@@ -0,0 +1,21 @@
+//! Small utilities shared between the solutions for multiple days
+#![deny(missing_docs)]
+
+pub mod ansi;
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod image;
+pub mod math;
+pub mod ocr;
+pub mod pathfinding;
+pub mod permutations;
+pub mod puzzle;
+pub mod render;
+
+pub use cycle::find_cycle;
+pub use geometry::{Direction, Point, Vec2, Vec3};
+pub use grid::Grid;
+pub use permutations::permutations;
+pub use puzzle::Puzzle;
+pub use render::render_points;
@@ -0,0 +1,139 @@
+//! Number theory for puzzles whose numbers are too big to work with directly
+//!
+//! The modular arithmetic works on `i128`, so products of two numbers below 2^63 can't
+//! overflow. Its results are always in the range `0..modulus`, even for negative inputs.
+
+/// The greatest common divisor of two numbers, which is never negative
+///
+/// `gcd(0, 0)` is 0.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The least common multiple of two numbers, which is never negative
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// `base` to the power of `exponent`, modulo `modulus`, by repeated squaring
+pub fn mod_pow(base: i128, exponent: u128, modulus: i128) -> i128 {
+    let mut result = 1 % modulus;
+    let mut base = base.rem_euclid(modulus);
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result
+}
+
+/// The greatest common divisor `g` of `a` and `b`, with `x` and `y` where `a*x + b*y = g`, by
+/// the extended Euclidean algorithm
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+    (old_r, old_s, old_t)
+}
+
+/// The number that gives 1 when multiplied by `value`, modulo `modulus`, if there is one
+///
+/// There is one exactly when `value` and `modulus` have no common factor. This uses the
+/// extended Euclidean algorithm, so the modulus doesn't need to be prime.
+pub fn mod_inv(value: i128, modulus: i128) -> Option<i128> {
+    let (gcd, x, _) = extended_gcd(value.rem_euclid(modulus), modulus);
+    if gcd == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+/// The smallest number that leaves each remainder for its modulus, and the modulus it repeats
+/// with, by the Chinese remainder theorem
+///
+/// Each pair is `(remainder, modulus)`. The moduli don't need to be coprime, but if they share
+/// a factor the remainders have to agree on it, or there's no answer.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut result = (0, 1);
+    for &(remainder, modulus) in congruences {
+        let (r1, m1) = result;
+        let r2 = remainder.rem_euclid(modulus);
+        let (gcd, p, _) = extended_gcd(m1, modulus);
+        if (r2 - r1) % gcd != 0 {
+            return None;
+        }
+        // Step r1 along by multiples of m1 until it also leaves r2 modulo the new modulus
+        let step = modulus / gcd;
+        let t = ((r2 - r1) / gcd).rem_euclid(step) * p.rem_euclid(step) % step;
+        let lcm = m1 * step;
+        result = ((r1 + m1 * t).rem_euclid(lcm), lcm);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-4, 6), 2);
+        assert_eq!(gcd(0, -5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-3, 5), 15);
+        assert_eq!(lcm(0, 5), 0);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(3, 4, 7), 81 % 7);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        // Fermat's little theorem, with a big prime
+        let p = 119_315_717_514_047;
+        assert_eq!(mod_pow(123_456_789, p as u128 - 1, p), 1);
+    }
+
+    #[test]
+    fn test_mod_inv() {
+        assert_eq!(mod_inv(3, 10), Some(7));
+        assert_eq!(mod_inv(-3, 10), Some(3));
+        assert_eq!(mod_inv(4, 10), None);
+        let p = 119_315_717_514_047;
+        let inverse = mod_inv(987_654_321, p).unwrap();
+        assert_eq!(987_654_321 * inverse % p, 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 4), (3, 6)]), Some((3, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        // Moduli big enough that their product needs more than 64 bits
+        let (p, q) = (119_315_717_514_047, 101_741_582_076_661);
+        let (value, modulus) = crt(&[(5, p), (7, q)]).unwrap();
+        assert_eq!(modulus, p * q);
+        assert_eq!((value % p, value % q), (5, 7));
+    }
+}
@@ -0,0 +1,119 @@
+//! Read the block capitals that some puzzles spell their answers out in
+//!
+//! The letters are 6 pixels tall and 4 wide (`Y` is 5), with a column of space after each one.
+
+use crate::render::bounds;
+use crate::Grid;
+
+/// The letters that have turned up in answers, drawn with `#` for lit pixels
+const GLYPHS: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The width of each letter and the space after it
+const CELL_WIDTH: i64 = 5;
+
+/// Read the letters in a picture, where `true` is a lit pixel
+///
+/// The letters can be anywhere in the picture, as long as they're in a single row. Returns
+/// `None` if anything lit isn't part of a letter this knows.
+pub fn recognize(pixels: &Grid<bool>) -> Option<String> {
+    let lit: Vec<(i64, i64)> = pixels
+        .iter()
+        .filter(|&(_, &lit)| lit)
+        .map(|(position, _)| position)
+        .collect();
+    let (min_x, max_x, min_y, max_y) = bounds(&lit)?;
+    if max_y - min_y + 1 != 6 {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut x = min_x;
+    while x <= max_x {
+        let matches = |rows: &[&str; 6]| {
+            (0..6).all(|dy| {
+                (0..CELL_WIDTH).all(|dx| {
+                    let expected = rows[dy as usize].as_bytes().get(dx as usize) == Some(&b'#');
+                    let actual = pixels.get((x + dx, min_y + dy)) == Some(&true);
+                    expected == actual
+                })
+            })
+        };
+        let &(letter, _) = GLYPHS.iter().find(|(_, rows)| matches(rows))?;
+        text.push(letter);
+        x += CELL_WIDTH;
+    }
+    Some(text)
+}
+
+/// Read the letters drawn by a set of lit points, with `y` increasing down the page
+pub fn recognize_points(points: impl IntoIterator<Item = (i64, i64)>) -> Option<String> {
+    let points: Vec<(i64, i64)> = points.into_iter().collect();
+    let (min_x, max_x, min_y, max_y) = bounds(&points)?;
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut pixels = Grid::new(width, height, false);
+    for (x, y) in points {
+        pixels[(x - min_x, y - min_y)] = true;
+    }
+    recognize(&pixels)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Draw letters from the glyph table side by side, with a margin around them
+    fn draw(letters: &str) -> Grid<bool> {
+        let mut pixels = Grid::new(letters.len() * 5 + 2, 8, false);
+        for (i, letter) in letters.chars().enumerate() {
+            let (_, rows) = GLYPHS.iter().find(|(c, _)| *c == letter).unwrap();
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    pixels[((i * 5 + x + 1) as i64, (y + 1) as i64)] = c == '#';
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_recognize() {
+        let alphabet: String = GLYPHS.iter().map(|(letter, _)| *letter).collect();
+        assert_eq!(recognize(&draw(&alphabet)), Some(alphabet));
+        assert_eq!(recognize(&draw("YZ")), Some(String::from("YZ")));
+
+        let mut smudged = draw("AB");
+        smudged[(3, 3)] = true;
+        assert_eq!(recognize(&smudged), None);
+        assert_eq!(recognize(&Grid::new(5, 6, false)), None);
+    }
+
+    #[test]
+    fn test_recognize_points() {
+        let pixels = draw("HL");
+        let points = pixels
+            .iter()
+            .filter(|&(_, &lit)| lit)
+            .map(|((x, y), _)| (x - 10, y + 20));
+        assert_eq!(recognize_points(points), Some(String::from("HL")));
+        assert_eq!(recognize_points(Vec::new()), None);
+    }
+}
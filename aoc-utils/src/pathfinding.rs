@@ -0,0 +1,269 @@
+//! Search for shortest paths through graphs that are described by a neighbor function
+//!
+//! There's no graph to build first: `neighbors` lists the nodes one step away from a node, and
+//! for the weighted searches, [`dijkstra`] and [`astar`], what each step costs. Nodes can be
+//! anything that can be hashed, like positions on a map or whole states of a puzzle.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The fewest steps from `start` to a node where `goal` holds, by breadth-first search
+///
+/// `neighbors` lists the nodes one step away from a node. Returns `None` if no goal can be
+/// reached.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(start.clone());
+    queue.push_back((start, 0));
+
+    while let Some((node, distance)) = queue.pop_front() {
+        if goal(&node) {
+            return Some(distance);
+        }
+        for next in neighbors(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    None
+}
+
+/// The shortest path from `start` to a node where `goal` holds, by breadth-first search
+///
+/// The path starts with `start` and ends with the goal, so it's one node longer than the
+/// number of steps.
+pub fn bfs_path<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+    parents.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            return Some(reconstruct(&parents, node));
+        }
+        for next in neighbors(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Follow the parents back from a node to the start, which has no parent
+fn reconstruct<N: Clone + Eq + Hash>(parents: &HashMap<N, Option<N>>, node: N) -> Vec<N> {
+    let mut path = vec![node];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// The shortest paths from one node to every node that can be reached from it
+#[derive(Debug, Clone)]
+pub struct Paths<N: Eq + Hash> {
+    distances: HashMap<N, usize>,
+    parents: HashMap<N, Option<N>>,
+}
+
+impl<N: Clone + Eq + Hash> Paths<N> {
+    /// Get the fewest steps to a node, or `None` if it can't be reached
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// Get the distance to every node that can be reached
+    pub fn distances(&self) -> &HashMap<N, usize> {
+        &self.distances
+    }
+
+    /// Get a shortest path to a node, from the start to the node itself
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if self.parents.contains_key(node) {
+            Some(reconstruct(&self.parents, node.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Find the shortest paths from `start` to every node that can be reached, by breadth-first
+/// search
+pub fn distances<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths {
+        distances: HashMap::new(),
+        parents: HashMap::new(),
+    };
+    let mut queue = VecDeque::new();
+    paths.distances.insert(start.clone(), 0);
+    paths.parents.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = paths.distances[&node];
+        for next in neighbors(&node) {
+            if !paths.distances.contains_key(&next) {
+                paths.distances.insert(next.clone(), distance + 1);
+                paths.parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// The cheapest total cost from `start` to a node where `goal` holds, by Dijkstra's algorithm
+///
+/// `neighbors` lists the nodes one step away from a node, with the cost of that step.
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<usize>
+where
+    N: Clone + Ord + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    search(start, neighbors, |_| 0, goal, false).map(|(cost, _)| cost)
+}
+
+/// The cheapest path from `start` to a node where `goal` holds, and its cost, by A* search
+///
+/// `heuristic` estimates the cost from a node to the nearest goal. As long as it never
+/// overestimates, the path is the cheapest one.
+pub fn astar<N, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> usize,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Ord + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    search(start, neighbors, heuristic, goal, true)
+}
+
+/// A* search, keeping track of the way back only if the path is wanted
+fn search<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut goal: impl FnMut(&N) -> bool,
+    with_path: bool,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Ord + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut best = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert(start.clone(), 0);
+    if with_path {
+        parents.insert(start.clone(), None);
+    }
+    queue.push(Reverse((heuristic(&start), 0, start)));
+
+    while let Some(Reverse((_, cost, node))) = queue.pop() {
+        if best.get(&node) < Some(&cost) {
+            continue;
+        }
+        if goal(&node) {
+            let path = if with_path {
+                reconstruct(&parents, node)
+            } else {
+                Vec::new()
+            };
+            return Some((cost, path));
+        }
+
+        for (next, step) in neighbors(&node) {
+            let cost = cost + step;
+            if best.get(&next).is_none_or(|&best| cost < best) {
+                best.insert(next.clone(), cost);
+                if with_path {
+                    parents.insert(next.clone(), Some(node.clone()));
+                }
+                queue.push(Reverse((cost + heuristic(&next), cost, next)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bfs() {
+        // Reach 10 from 1 by doubling or adding one
+        let steps = bfs(1, |&n: &u32| vec![n * 2, n + 1], |&n| n == 10);
+        assert_eq!(steps, Some(4));
+        let path = bfs_path(1, |&n: &u32| vec![n * 2, n + 1], |&n| n == 10);
+        assert_eq!(path.map(|path| path.len()), Some(5));
+
+        let steps = bfs(1, |&n: &u32| (n < 5).then(|| n + 1), |&n| n == 10);
+        assert_eq!(steps, None);
+
+        let paths = distances(1, |&n: &u32| (n < 5).then(|| n + 1));
+        assert_eq!(paths.distances().len(), 5);
+        assert_eq!(paths.distance(&4), Some(3));
+        assert_eq!(paths.path_to(&3), Some(vec![1, 2, 3]));
+        assert_eq!(paths.path_to(&10), None);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // Going around the long way is cheaper than the direct step from a to d
+        let edges = |node: &char| match node {
+            'a' => vec![('b', 1), ('d', 10)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 3)],
+            _ => vec![],
+        };
+        assert_eq!(dijkstra('a', edges, |&n| n == 'd'), Some(6));
+        assert_eq!(dijkstra('d', edges, |&n| n == 'a'), None);
+
+        // On a grid, with the Manhattan distance to the corner as the heuristic
+        let neighbors = |&(x, y): &(i32, i32)| {
+            vec![(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x <= 3 && y <= 3 && (x, y) != (1, 1))
+                .map(|next| (next, 1))
+        };
+        let heuristic = |&(x, y): &(i32, i32)| (6 - x - y) as usize;
+        let (cost, path) = astar((0, 0), neighbors, heuristic, |&n| n == (3, 3)).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert_eq!((path[0], path[6]), ((0, 0), (3, 3)));
+        assert!(!path.contains(&(1, 1)));
+    }
+}
@@ -0,0 +1,90 @@
+//! Iterate over every ordering of a list of items
+
+/// Iterator over every permutation of a list of items, created by [`permutations`]
+pub struct Permutations<T> {
+    items: Vec<T>,
+    indices: Vec<usize>,
+    done: bool,
+}
+
+/// Iterate over every permutation of `items`
+///
+/// Permutations are produced in lexicographic order of the items' positions, so for a sorted
+/// list they come out sorted: `[0, 1, 2]`, `[0, 2, 1]`, `[1, 0, 2]`, and so on.
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<T> {
+    Permutations {
+        items: items.to_vec(),
+        indices: (0..items.len()).collect(),
+        done: false,
+    }
+}
+
+impl<T: Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let permutation = self
+            .indices
+            .iter()
+            .map(|&index| self.items[index].clone())
+            .collect();
+        self.done = !next_permutation(&mut self.indices);
+
+        Some(permutation)
+    }
+}
+
+/// Rearrange the indices into the next permutation in lexicographic order
+///
+/// Returns false if they were already the last permutation.
+fn next_permutation(indices: &mut [usize]) -> bool {
+    let pivot = match (1..indices.len())
+        .rev()
+        .find(|&i| indices[i - 1] < indices[i])
+    {
+        Some(i) => i - 1,
+        None => return false,
+    };
+
+    let successor = (pivot + 1..indices.len())
+        .rev()
+        .find(|&i| indices[i] > indices[pivot])
+        .unwrap();
+    indices.swap(pivot, successor);
+    indices[pivot + 1..].reverse();
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_permutations() {
+        let all: Vec<_> = permutations(&['a', 'b', 'c']).collect();
+
+        assert_eq!(
+            all,
+            vec![
+                vec!['a', 'b', 'c'],
+                vec!['a', 'c', 'b'],
+                vec!['b', 'a', 'c'],
+                vec!['b', 'c', 'a'],
+                vec!['c', 'a', 'b'],
+                vec!['c', 'b', 'a'],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_permutations_count() {
+        assert_eq!(permutations(&[0, 1, 2, 3, 4]).count(), 120);
+        assert_eq!(permutations(&[7]).collect::<Vec<_>>(), vec![vec![7]]);
+        assert_eq!(permutations::<i64>(&[]).collect::<Vec<_>>(), vec![vec![]]);
+    }
+}
@@ -0,0 +1,95 @@
+//! A common shape for every day's solution, so they can all be run the same way
+//!
+//! Each day's crate is a library with a type that implements [`Puzzle`], and a small `main`
+//! that hands it to [`run`].
+
+use std::io::{self, Read};
+
+/// A day's puzzle: how to read its input, and how to solve both parts
+pub trait Puzzle {
+    /// The puzzle input, parsed into whatever the solutions work with
+    type Input;
+
+    /// Parse the puzzle input
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input isn't valid, since there's nothing to solve.
+    fn parse(input: &str) -> Self::Input;
+
+    /// Solve the first part of the puzzle
+    fn part1(input: &Self::Input) -> String;
+
+    /// Solve the second part of the puzzle
+    fn part2(input: &Self::Input) -> String;
+}
+
+/// Read the whole puzzle input from stdin
+pub fn read_stdin() -> String {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    input
+}
+
+/// Label an answer, for printing
+///
+/// Answers that span several lines, like pictures, start on the line after their label.
+pub fn format_answer(label: &str, answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("{}:\n{}", label, answer.trim_end())
+    } else {
+        format!("{}={}", label, answer)
+    }
+}
+
+/// Solve both parts, and print the answers
+pub fn print_answers<P: Puzzle>(input: &P::Input) {
+    println!("{}", format_answer("part1", &P::part1(input)));
+    println!("{}", format_answer("part2", &P::part2(input)));
+}
+
+/// Read the puzzle input from stdin, and print the answers to both parts
+pub fn run<P: Puzzle>() {
+    let input = P::parse(&read_stdin());
+    print_answers::<P>(&input);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Sum the numbers, and multiply them
+    struct Numbers;
+
+    impl Puzzle for Numbers {
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Self::Input {
+            input
+                .split_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> String {
+            input.iter().sum::<u32>().to_string()
+        }
+
+        fn part2(input: &Self::Input) -> String {
+            input.iter().product::<u32>().to_string()
+        }
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let input = Numbers::parse("2 3 4\n");
+        assert_eq!(Numbers::part1(&input), "9");
+        assert_eq!(Numbers::part2(&input), "24");
+    }
+
+    #[test]
+    fn test_format_answer() {
+        assert_eq!(format_answer("part1", "24"), "part1=24");
+        assert_eq!(format_answer("part2", "#.\n.#\n"), "part2:\n#.\n.#");
+    }
+}
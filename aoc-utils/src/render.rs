@@ -0,0 +1,50 @@
+//! Draw sets of points as text, for puzzles whose answer is spelled out in a picture
+
+/// Draw the points as `#` on a background of spaces
+///
+/// The picture covers the smallest rectangle that holds every point, with `y` increasing down
+/// the page. Every row, including the last, ends with a newline.
+pub fn render_points(points: impl IntoIterator<Item = (i64, i64)>) -> String {
+    let points: Vec<(i64, i64)> = points.into_iter().collect();
+    let (min_x, max_x, min_y, max_y) = match bounds(&points) {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut rows = vec![vec![' '; width]; height];
+    for (x, y) in points {
+        rows[(y - min_y) as usize][(x - min_x) as usize] = '#';
+    }
+
+    let mut picture = String::with_capacity((width + 1) * height);
+    for row in rows {
+        picture.extend(row);
+        picture.push('\n');
+    }
+    picture
+}
+
+/// The smallest and largest `x` and `y`, or `None` if there are no points
+pub(crate) fn bounds(points: &[(i64, i64)]) -> Option<(i64, i64, i64, i64)> {
+    let (&(x, y), rest) = points.split_first()?;
+    Some(
+        rest.iter()
+            .fold((x, x, y, y), |(min_x, max_x, min_y, max_y), &(x, y)| {
+                (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+            }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_points() {
+        let points = vec![(-1, 5), (1, 5), (0, 6)];
+        assert_eq!(render_points(points), "# #\n # \n");
+        assert_eq!(render_points(Vec::new()), "");
+    }
+}
@@ -0,0 +1,22 @@
+//! Benchmark every day that has a cached input, with `cargo bench -p aoc --features criterion`
+
+use aoc::days::SOLUTIONS;
+use aoc::inputs::Inputs;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn days(c: &mut Criterion) {
+    let inputs = Inputs::workspace();
+    let mut group = c.benchmark_group("days");
+    group.sample_size(10);
+    for solution in SOLUTIONS {
+        if let Some(input) = inputs.cached(solution.day) {
+            group.bench_function(format!("day-{:02}", solution.day), |b| {
+                b.iter(|| solution.solve(&input))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+//! A table of how long every day takes, to keep an eye on performance while refactoring
+
+use crate::days::Timings;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Format a duration to a sensible precision, like `1.5ms`
+fn duration(duration: Duration) -> String {
+    format!("{:.1?}", duration)
+}
+
+/// Lay out the timings for each day as a table, with the total for every day at the bottom
+pub fn table(rows: &[(u32, Timings)]) -> String {
+    let mut table = String::new();
+    writeln!(
+        table,
+        "{:>5} {:>10} {:>10} {:>10} {:>10}",
+        "day", "parse", "part1", "part2", "total"
+    )
+    .unwrap();
+    for (day, timings) in rows {
+        writeln!(
+            table,
+            "{:>5} {:>10} {:>10} {:>10} {:>10}",
+            day,
+            duration(timings.parse),
+            duration(timings.part1),
+            duration(timings.part2),
+            duration(timings.total())
+        )
+        .unwrap();
+    }
+    let total: Duration = rows.iter().map(|(_, timings)| timings.total()).sum();
+    writeln!(table, "{:>5} {:>43}", "all", duration(total)).unwrap();
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let timings = Timings {
+            parse: Duration::from_micros(15),
+            part1: Duration::from_millis(2),
+            part2: Duration::from_secs(1),
+        };
+        assert_eq!(
+            table(&[(1, timings), (12, timings)]),
+            concat!(
+                "  day      parse      part1      part2      total\n",
+                "    1     15.0µs      2.0ms       1.0s       1.0s\n",
+                "   12     15.0µs      2.0ms       1.0s       1.0s\n",
+                "  all                                        2.0s\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Every day's solution, behind one signature so the runner can pick them by number
+
+use aoc_utils::Puzzle;
+use serde::{Serialize, Serializer};
+use std::time::{Duration, Instant};
+
+/// The answers to both parts of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Answers {
+    pub part1: String,
+    pub part2: String,
+}
+
+/// How long each step of solving a puzzle took
+///
+/// Serialized as fractional milliseconds, like `{"parse_ms": 0.2, ...}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Timings {
+    #[serde(rename = "parse_ms", serialize_with = "millis")]
+    pub parse: Duration,
+    #[serde(rename = "part1_ms", serialize_with = "millis")]
+    pub part1: Duration,
+    #[serde(rename = "part2_ms", serialize_with = "millis")]
+    pub part2: Duration,
+}
+
+fn millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+impl Timings {
+    /// The time for all three steps together
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// A day's solution, with the type of its parsed input hidden
+pub struct Solution {
+    pub day: u32,
+    run: fn(&str) -> (Answers, Timings),
+}
+
+impl Solution {
+    /// Parse the puzzle input, and solve both parts
+    pub fn solve(&self, input: &str) -> Answers {
+        self.run(input).0
+    }
+
+    /// Parse the puzzle input and solve both parts, timing each step
+    pub fn run(&self, input: &str) -> (Answers, Timings) {
+        (self.run)(input)
+    }
+}
+
+fn run<P: Puzzle>(input: &str) -> (Answers, Timings) {
+    let start = Instant::now();
+    let input = P::parse(input);
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let part1 = P::part1(&input);
+    let part1_time = start.elapsed();
+
+    let start = Instant::now();
+    let part2 = P::part2(&input);
+    let part2_time = start.elapsed();
+
+    (
+        Answers { part1, part2 },
+        Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        },
+    )
+}
+
+/// Build the list of solutions from lines like `1 => day_01::Day01`
+macro_rules! solutions {
+    ($($day:literal => $puzzle:ty,)*) => {
+        /// Every day that's been solved, in order
+        pub const SOLUTIONS: &[Solution] = &[$(Solution {
+            day: $day,
+            run: run::<$puzzle>,
+        }),*];
+    };
+}
+
+solutions! {
+    1 => day_01::Day01,
+    2 => day_02::Day02,
+    3 => day_03::Day03,
+    4 => day_04::Day04,
+    5 => day_05::Day05,
+    6 => day_06::Day06,
+    7 => day_07::Day07,
+    8 => day_08::Day08,
+    9 => day_09::Day09,
+    10 => day_10::Day10,
+    11 => day_11::Day11,
+    12 => day_12::Day12,
+    13 => day_13::Day13,
+    14 => day_14::Day14,
+    15 => day_15::Day15,
+    17 => day_17::Day17,
+    18 => day_18::Day18,
+    19 => day_19::Day19,
+    20 => day_20::Day20,
+    21 => day_21::Day21,
+    22 => day_22::Day22,
+    23 => day_23::Day23,
+    24 => day_24::Day24,
+    25 => day_25::Day25,
+}
+
+/// Get the solution for a day, if it's been solved
+pub fn solution(day: u32) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|solution| solution.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solution() {
+        assert!(solution(16).is_none());
+        let answers = solution(1).unwrap().solve("12\n1969\n");
+        assert_eq!(answers.part1, "656");
+        assert_eq!(answers.part2, "968");
+
+        let (_, timings) = solution(1).unwrap().run("12\n1969\n");
+        assert_eq!(
+            timings.total(),
+            timings.parse + timings.part1 + timings.part2
+        );
+    }
+}
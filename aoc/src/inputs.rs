@@ -0,0 +1,171 @@
+//! Puzzle inputs, downloaded from adventofcode.com once and cached after that
+//!
+//! Inputs are different for everyone, so downloading them needs the session cookie of a
+//! logged-in browser. It's read from the `AOC_SESSION` environment variable, or from the
+//! `aoc/session` file in the config directory (`$XDG_CONFIG_HOME`, or `~/.config`).
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const USER_AGENT: &str = "github.com/bryanburgers/advent-of-code-2019 by bryan@burgers.io";
+
+/// Why an input couldn't be fetched
+#[derive(Debug)]
+pub enum FetchError {
+    /// There's no session cookie in the environment or the config file
+    NoSession,
+    /// The request failed, or adventofcode.com refused it
+    Http(Box<ureq::Error>),
+    /// The input couldn't be read or cached
+    Io(io::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session cookie: set AOC_SESSION, or put it in ~/.config/aoc/session"
+            ),
+            FetchError::Http(e) => write!(f, "couldn't download the input: {}", e),
+            FetchError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<ureq::Error> for FetchError {
+    fn from(e: ureq::Error) -> Self {
+        FetchError::Http(Box::new(e))
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::Io(e)
+    }
+}
+
+/// The session cookie, from the environment or the config file
+pub fn session() -> Result<String, FetchError> {
+    if let Ok(session) = env::var("AOC_SESSION") {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .ok_or(FetchError::NoSession)?;
+    match fs::read_to_string(config.join("aoc").join("session")) {
+        Ok(session) if !session.trim().is_empty() => Ok(session.trim().to_string()),
+        _ => Err(FetchError::NoSession),
+    }
+}
+
+/// The cache of puzzle inputs: a directory with a file for every day
+///
+/// Inputs that are committed to the repository, as `day-NN/input.txt`, are used when a day
+/// isn't cached.
+pub struct Inputs {
+    dir: PathBuf,
+    committed: Option<PathBuf>,
+}
+
+impl Inputs {
+    /// Cache inputs in the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Inputs {
+            dir: dir.into(),
+            committed: None,
+        }
+    }
+
+    /// Also read the inputs committed as `day-NN/input.txt` under the given directory
+    pub fn with_committed(mut self, root: impl Into<PathBuf>) -> Self {
+        self.committed = Some(root.into());
+        self
+    }
+
+    /// Cache inputs in the `inputs` directory at the root of the workspace, falling back to
+    /// the inputs committed next to each day
+    pub fn workspace() -> Self {
+        let root = crate::workspace_root();
+        Inputs::new(root.join("inputs")).with_committed(root)
+    }
+
+    /// Where a day's input is cached
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("day-{:02}.txt", day))
+    }
+
+    /// Get a day's committed input, if there is one
+    fn committed(&self, day: u32) -> Option<String> {
+        let root = self.committed.as_ref()?;
+        fs::read_to_string(root.join(format!("day-{:02}", day)).join("input.txt")).ok()
+    }
+
+    /// Get a day's input, if it's cached or committed
+    pub fn cached(&self, day: u32) -> Option<String> {
+        fs::read_to_string(self.path(day))
+            .ok()
+            .or_else(|| self.committed(day))
+    }
+
+    /// Get a day's input, downloading it if it isn't cached or committed yet
+    pub fn get(&self, day: u32) -> Result<String, FetchError> {
+        match fs::read_to_string(self.path(day)) {
+            Ok(input) => Ok(input),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => match self.committed(day) {
+                Some(input) => Ok(input),
+                None => self.fetch(day, &session()?),
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Download a day's input, and cache it
+    pub fn fetch(&self, day: u32, session: &str) -> Result<String, FetchError> {
+        let url = format!("https://adventofcode.com/2019/day/{}/input", day);
+        let input = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .call()?
+            .into_string()?;
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(day), &input)?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cached() {
+        let dir = env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        let inputs = Inputs::new(&dir);
+        assert_eq!(inputs.path(7), dir.join("day-07.txt"));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(inputs.path(7), "3,0,4,0,99\n").unwrap();
+        assert_eq!(inputs.get(7).unwrap(), "3,0,4,0,99\n");
+        assert_eq!(inputs.cached(7).as_deref(), Some("3,0,4,0,99\n"));
+        assert_eq!(inputs.cached(8), None);
+
+        fs::create_dir_all(dir.join("day-08")).unwrap();
+        fs::write(dir.join("day-08").join("input.txt"), "0212\n").unwrap();
+        let inputs = inputs.with_committed(&dir);
+        assert_eq!(inputs.cached(8).as_deref(), Some("0212\n"));
+        assert_eq!(inputs.get(8).unwrap(), "0212\n");
+        assert_eq!(inputs.cached(7).as_deref(), Some("3,0,4,0,99\n"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
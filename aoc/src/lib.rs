@@ -0,0 +1,18 @@
+//! Run any day's solution, against puzzle inputs fetched from adventofcode.com and cached
+
+pub mod bench;
+pub mod days;
+pub mod inputs;
+pub mod report;
+pub mod scaffold;
+pub mod verify;
+
+use std::path::{Path, PathBuf};
+
+/// The root of the workspace, with a crate for every day
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
@@ -0,0 +1,172 @@
+use aoc::bench::table;
+use aoc::days::{solution, SOLUTIONS};
+use aoc::inputs::{session, Inputs};
+use aoc::report::Report;
+use aoc::scaffold::scaffold;
+use aoc::verify::{verify, AnswerKey, Status};
+use aoc_utils::puzzle::format_answer;
+use std::env;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "Usage: aoc run --day <N> [<input>], aoc fetch --day <N>, aoc bench, \
+                     aoc verify, or aoc new --day <N>, where run, bench, and verify take \
+                     --format text|json";
+
+/// How answers, timings, and errors are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Take `--format text|json` out of the arguments, wherever it is
+fn format(args: &mut Vec<String>) -> Format {
+    let position = match args.iter().position(|arg| arg == "--format") {
+        Some(position) => position,
+        None => return Format::Text,
+    };
+    let format = match args.get(position + 1).map(String::as_str) {
+        Some("text") => Format::Text,
+        Some("json") => Format::Json,
+        _ => panic!("{}", USAGE),
+    };
+    args.drain(position..position + 2);
+    format
+}
+
+/// Parse `--day N`
+fn day(args: &mut impl Iterator<Item = String>) -> u32 {
+    match (args.next().as_deref(), args.next()) {
+        (Some("--day"), Some(day)) => day.parse().expect(USAGE),
+        _ => panic!("{}", USAGE),
+    }
+}
+
+/// Run a day, against the given input file or its cached or committed input
+fn run(day: u32, path: Option<String>, inputs: &Inputs) -> Report {
+    let solution = match solution(day) {
+        Some(solution) => solution,
+        None => return Report::error(day, format!("Day {} isn't solved yet", day)),
+    };
+    let input = match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| e.to_string()),
+        None => inputs.get(day).map_err(|e| e.to_string()),
+    };
+    match input {
+        Ok(input) => Report::run(solution, &input),
+        Err(e) => Report::error(day, e),
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let format = format(&mut args);
+
+    let mut args = args.into_iter();
+    let inputs = Inputs::workspace();
+    match args.next().as_deref() {
+        Some("run") => {
+            let day = day(&mut args);
+            let report = run(day, args.next(), &inputs);
+            match (format, &report.answers) {
+                (Format::Json, _) => println!("{}", serde_json::to_string(&report).unwrap()),
+                (Format::Text, Some(answers)) => {
+                    println!("{}", format_answer("part1", &answers.part1));
+                    println!("{}", format_answer("part2", &answers.part2));
+                }
+                (Format::Text, None) => {}
+            }
+            if let Some(error) = report.error {
+                if format == Format::Text {
+                    eprintln!("{}", error);
+                }
+                process::exit(1);
+            }
+        }
+        Some("fetch") => {
+            let day = day(&mut args);
+            let result = session().and_then(|session| inputs.fetch(day, &session));
+            match result {
+                Ok(_) => println!("{}", inputs.path(day).display()),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Some("bench") => {
+            let reports: Vec<Report> = SOLUTIONS
+                .iter()
+                .map(|solution| match inputs.cached(solution.day) {
+                    Some(input) => Report::run(solution, &input),
+                    None => {
+                        Report::error(solution.day, "no cached or committed input (see aoc fetch)")
+                    }
+                })
+                .collect();
+
+            match format {
+                Format::Json => println!("{}", serde_json::to_string(&reports).unwrap()),
+                Format::Text => {
+                    let mut rows = Vec::new();
+                    for report in reports {
+                        match (report.timings, report.error) {
+                            (Some(timings), _) => rows.push((report.day, timings)),
+                            (None, Some(error)) => {
+                                eprintln!("Skipping day {}: {}", report.day, error)
+                            }
+                            (None, None) => {}
+                        }
+                    }
+                    print!("{}", table(&rows));
+                }
+            }
+        }
+        Some("verify") => {
+            let key = AnswerKey::workspace().unwrap_or_else(|e| {
+                eprintln!("answers.toml: {}", e);
+                process::exit(1);
+            });
+            let verifications = verify(&key, &inputs);
+
+            if format == Format::Json {
+                println!("{}", serde_json::to_string(&verifications).unwrap());
+            }
+            let mut failed = false;
+            for verification in verifications {
+                let day = verification.day;
+                match verification.status {
+                    Status::Ok if format == Format::Text => println!("day {:>2}: ok", day),
+                    Status::Skipped { reason } if format == Format::Text => {
+                        println!("day {:>2}: skipped, {}", day, reason)
+                    }
+                    Status::Failed { problems } => {
+                        failed = true;
+                        if format == Format::Text {
+                            println!("day {:>2}: FAILED", day);
+                            for problem in problems {
+                                println!("    {}", problem);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+        Some("new") => {
+            let day = day(&mut args);
+            match scaffold(&aoc::workspace_root(), day) {
+                Ok(dir) => println!("{}", dir.display()),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        _ => panic!("{}", USAGE),
+    }
+}
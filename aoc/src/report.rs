@@ -0,0 +1,112 @@
+//! What happened when running a day, in a shape that's as easy to serialize as to print
+
+use crate::days::{Answers, Solution, Timings};
+use serde::Serialize;
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    /// Whether panics on this thread are being caught by [`Report::run`]
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Install, once for the whole process, a panic hook that stays quiet for caught panics
+///
+/// Swapping the hook in and out around every run would race when days run on several threads
+/// at once, like they do in tests, and could leave the quiet hook installed for good.
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCED.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// The answers and timings from running a day, or why it couldn't be run
+///
+/// As JSON, a day that ran looks like
+/// `{"day":1,"part1":"656","part2":"968","timings":{"parse_ms":0.01,...}}`, and one that
+/// didn't looks like `{"day":16,"error":"..."}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub day: u32,
+    #[serde(flatten)]
+    pub answers: Option<Answers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Report {
+    /// Report that a day couldn't be run
+    pub fn error(day: u32, error: impl ToString) -> Self {
+        Report {
+            day,
+            answers: None,
+            timings: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Run a day's solution, reporting a panic, like one from a bad input, as an error
+    ///
+    /// The panic is only reported here, so the message isn't printed while it's caught.
+    pub fn run(solution: &Solution, input: &str) -> Self {
+        install_hook();
+        SILENCED.with(|silenced| silenced.set(true));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solution.run(input)));
+        SILENCED.with(|silenced| silenced.set(false));
+
+        match result {
+            Ok((answers, timings)) => Report {
+                day: solution.day,
+                answers: Some(answers),
+                timings: Some(timings),
+                error: None,
+            },
+            Err(payload) => Report::error(solution.day, panic_message(payload)),
+        }
+    }
+}
+
+/// The message a panic was raised with
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => String::from("panicked"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::days::solution;
+
+    #[test]
+    fn test_report() {
+        let report = Report::run(solution(1).unwrap(), "12\n1969\n");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["day"], 1);
+        assert_eq!(json["part1"], "656");
+        assert_eq!(json["part2"], "968");
+        assert!(json["timings"]["parse_ms"].is_f64());
+        assert!(json.get("error").is_none());
+
+        let report = Report::run(solution(1).unwrap(), "twelve\n");
+        assert!(report.error.unwrap().contains("InvalidDigit"));
+        assert!(!SILENCED.with(Cell::get));
+
+        let json = serde_json::to_string(&Report::error(16, "not solved")).unwrap();
+        assert_eq!(json, r#"{"day":16,"error":"not solved"}"#);
+    }
+}
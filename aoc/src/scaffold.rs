@@ -0,0 +1,221 @@
+//! Start a new day: a crate from a template, registered with the workspace and the runner
+//!
+//! The new crate has a `Puzzle` implementation to fill in, and a test that runs it against
+//! `example.txt`, for the example from the puzzle description.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_RS: &str = include_str!("../templates/lib.rs.tmpl");
+const MAIN_RS: &str = include_str!("../templates/main.rs.tmpl");
+
+/// Why a new day couldn't be created
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// There's already a crate for the day
+    Exists(PathBuf),
+    /// A file that lists the days doesn't look the way it's expected to
+    Unregistered(PathBuf),
+    Io(io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScaffoldError::Exists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Unregistered(path) => {
+                write!(
+                    f,
+                    "Couldn't find where to add the day in {}",
+                    path.display()
+                )
+            }
+            ScaffoldError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(e: io::Error) -> Self {
+        ScaffoldError::Io(e)
+    }
+}
+
+/// Fill in a template for a day: `{{day}}` is the day's number, and `{{dd}}` is it padded
+fn render(template: &str, day: u32) -> String {
+    template
+        .replace("{{day}}", &day.to_string())
+        .replace("{{dd}}", &format!("{:02}", day))
+}
+
+/// Add a line to a list of lines, one per day, keeping the days in order
+///
+/// `day_of` picks out the day on the lines that are part of the list. If the day is already
+/// there, or there's no list at all, there's nothing to add it to.
+fn insert_in_order(
+    text: &str,
+    line: &str,
+    day: u32,
+    day_of: impl Fn(&str) -> Option<u32>,
+) -> Option<String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let days: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| day_of(line.trim()).map(|day| (index, day)))
+        .collect();
+    if days.iter().any(|&(_, existing)| existing == day) {
+        return None;
+    }
+
+    let index = match days.iter().rev().find(|&&(_, existing)| existing < day) {
+        Some(&(index, _)) => index + 1,
+        None => days.first()?.0,
+    };
+    lines.insert(index, line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Add the day to a file, with [`insert_in_order`]
+fn register(
+    path: &Path,
+    line: &str,
+    day: u32,
+    day_of: impl Fn(&str) -> Option<u32>,
+) -> Result<(), ScaffoldError> {
+    let text = fs::read_to_string(path)?;
+    let text = insert_in_order(&text, line, day, day_of)
+        .ok_or_else(|| ScaffoldError::Unregistered(path.to_path_buf()))?;
+    fs::write(path, text)?;
+    Ok(())
+}
+
+/// Create the crate for a day in the workspace at `root`, and register it
+///
+/// The day is added to the workspace members, and to the runner's dependencies and
+/// solutions. Returns the new crate's directory.
+pub fn scaffold(root: &Path, day: u32) -> Result<PathBuf, ScaffoldError> {
+    let name = format!("day-{:02}", day);
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(ScaffoldError::Exists(dir));
+    }
+
+    // Check every file can be registered in, before changing anything
+    let registrations = [
+        (
+            root.join("Cargo.toml"),
+            format!("    \"{}\",", name),
+            Box::new(|line: &str| {
+                line.strip_prefix("\"day-")?
+                    .strip_suffix("\",")?
+                    .parse()
+                    .ok()
+            }) as Box<dyn Fn(&str) -> Option<u32>>,
+        ),
+        (
+            root.join("aoc").join("Cargo.toml"),
+            format!(
+                "{} = {{ version = \"0.1.0\", path = \"../{}\" }}",
+                name, name
+            ),
+            Box::new(|line: &str| line.strip_prefix("day-")?.split(' ').next()?.parse().ok()),
+        ),
+        (
+            root.join("aoc").join("src").join("days.rs"),
+            format!("    {} => day_{:02}::Day{:02},", day, day, day),
+            Box::new(|line: &str| line.split(" => day_").next()?.parse().ok()),
+        ),
+    ];
+    for (path, line, day_of) in registrations.iter() {
+        let text = fs::read_to_string(path)?;
+        if insert_in_order(&text, line, day, day_of).is_none() {
+            return Err(ScaffoldError::Unregistered(path.clone()));
+        }
+    }
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), render(CARGO_TOML, day))?;
+    fs::write(dir.join("src").join("lib.rs"), render(LIB_RS, day))?;
+    fs::write(dir.join("src").join("main.rs"), render(MAIN_RS, day))?;
+    fs::write(dir.join("example.txt"), "")?;
+
+    for (path, line, day_of) in registrations.iter() {
+        register(path, line, day, day_of)?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_insert_in_order() {
+        let day_of = |line: &str| line.strip_prefix("day ")?.parse().ok();
+        let text = "days = [\nday 1\nday 3\n]\n";
+        assert_eq!(
+            insert_in_order(text, "day 2", 2, day_of).unwrap(),
+            "days = [\nday 1\nday 2\nday 3\n]\n"
+        );
+        assert_eq!(
+            insert_in_order(text, "day 9", 9, day_of).unwrap(),
+            "days = [\nday 1\nday 3\nday 9\n]\n"
+        );
+        assert_eq!(
+            insert_in_order(text, "day 0", 0, day_of).unwrap(),
+            "days = [\nday 0\nday 1\nday 3\n]\n"
+        );
+        assert_eq!(insert_in_order(text, "day 3", 3, day_of), None);
+        assert_eq!(insert_in_order("days = []\n", "day 3", 3, day_of), None);
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("aoc").join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day-01\",\n]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc").join("Cargo.toml"),
+            "[dependencies]\nday-01 = { version = \"0.1.0\", path = \"../day-01\" }\nureq = \"2\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc").join("src").join("days.rs"),
+            "solutions! {\n    1 => day_01::Day01,\n}\n",
+        )
+        .unwrap();
+
+        let dir = scaffold(&root, 2).unwrap();
+        assert_eq!(dir, root.join("day-02"));
+        let lib = fs::read_to_string(dir.join("src").join("lib.rs")).unwrap();
+        assert!(lib.contains("impl Puzzle for Day02 {"));
+        assert!(fs::read_to_string(dir.join("Cargo.toml"))
+            .unwrap()
+            .contains("name = \"day-02\""));
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day-01\",\n    \"day-02\",\n]\n"
+        );
+        assert!(fs::read_to_string(root.join("aoc").join("Cargo.toml"))
+            .unwrap()
+            .contains("day-02 = { version = \"0.1.0\", path = \"../day-02\" }\nureq"));
+        assert_eq!(
+            fs::read_to_string(root.join("aoc").join("src").join("days.rs")).unwrap(),
+            "solutions! {\n    1 => day_01::Day01,\n    2 => day_02::Day02,\n}\n"
+        );
+
+        assert!(matches!(scaffold(&root, 2), Err(ScaffoldError::Exists(_))));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
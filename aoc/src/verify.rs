@@ -0,0 +1,208 @@
+//! Check the answers every day computes against the correct answers recorded in `answers.toml`
+//!
+//! Refactoring shared code, like the intcode computer, can break days that were solved long
+//! ago. Recording their answers once means every later change can be checked against them.
+
+use crate::days::solution;
+use crate::inputs::Inputs;
+use crate::report::Report;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The correct answers for a day, where either part might not be recorded
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Why the recorded answers couldn't be read
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    /// A table that isn't named like `day-01`
+    BadDay(String),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io(e) => write!(f, "{}", e),
+            AnswersError::Toml(e) => write!(f, "{}", e),
+            AnswersError::BadDay(name) => write!(f, "Expected a day like day-01, got {}", name),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+/// The recorded answers for every day
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnswerKey {
+    days: BTreeMap<u32, Expected>,
+}
+
+impl FromStr for AnswerKey {
+    type Err = AnswersError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tables: BTreeMap<String, Expected> =
+            toml::from_str(input).map_err(AnswersError::Toml)?;
+        let mut days = BTreeMap::new();
+        for (name, expected) in tables {
+            let day = name
+                .strip_prefix("day-")
+                .and_then(|day| day.parse().ok())
+                .ok_or(AnswersError::BadDay(name))?;
+            days.insert(day, expected);
+        }
+        Ok(AnswerKey { days })
+    }
+}
+
+impl AnswerKey {
+    /// Read the answers from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AnswersError> {
+        fs::read_to_string(path).map_err(AnswersError::Io)?.parse()
+    }
+
+    /// Read the answers from `answers.toml` at the root of the workspace
+    pub fn workspace() -> Result<Self, AnswersError> {
+        AnswerKey::load(crate::workspace_root().join("answers.toml"))
+    }
+
+    /// Get the recorded answers for a day
+    pub fn get(&self, day: u32) -> Option<&Expected> {
+        self.days.get(&day)
+    }
+
+    /// The days with recorded answers, in order
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        self.days.keys().copied()
+    }
+}
+
+/// Whether a day still gets its recorded answers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase", tag = "status")]
+pub enum Status {
+    /// Every recorded answer matches
+    Ok,
+    /// Something doesn't match, or the day couldn't be run
+    Failed { problems: Vec<String> },
+    /// The day can't be checked, for example because its input isn't cached
+    Skipped { reason: String },
+}
+
+/// The result of checking one day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verification {
+    pub day: u32,
+    #[serde(flatten)]
+    pub status: Status,
+}
+
+/// Compare a day's report to the answers recorded for it
+pub fn check(report: &Report, expected: &Expected) -> Status {
+    let answers = match (&report.answers, &report.error) {
+        (Some(answers), _) => answers,
+        (None, error) => {
+            return Status::Failed {
+                problems: error.iter().cloned().collect(),
+            }
+        }
+    };
+
+    let mut problems = Vec::new();
+    let parts = [
+        ("part1", &expected.part1, &answers.part1),
+        ("part2", &expected.part2, &answers.part2),
+    ];
+    for (part, expected, actual) in parts.iter() {
+        match expected {
+            Some(expected) if expected.trim_end() != actual.trim_end() => problems.push(format!(
+                "{}: expected {:?}, got {:?}",
+                part, expected, actual
+            )),
+            _ => {}
+        }
+    }
+
+    if problems.is_empty() {
+        Status::Ok
+    } else {
+        Status::Failed { problems }
+    }
+}
+
+/// Run every day with recorded answers and a cached or committed input, and check its answers
+pub fn verify(key: &AnswerKey, inputs: &Inputs) -> Vec<Verification> {
+    key.days()
+        .map(|day| {
+            let status = match (solution(day), inputs.cached(day)) {
+                (None, _) => Status::Skipped {
+                    reason: format!("Day {} isn't solved yet", day),
+                },
+                (_, None) => Status::Skipped {
+                    reason: String::from("no cached or committed input (see aoc fetch)"),
+                },
+                (Some(solution), Some(input)) => {
+                    check(&Report::run(solution, &input), &key.days[&day])
+                }
+            };
+            Verification { day, status }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let key: AnswerKey = "[day-01]\npart1 = \"656\"\n\n[day-12]\npart2 = \"1\"\n"
+            .parse()
+            .unwrap();
+        assert_eq!(key.days().collect::<Vec<_>>(), vec![1, 12]);
+        assert!("[one]\npart1 = \"1\"\n".parse::<AnswerKey>().is_err());
+
+        let report = Report::run(solution(1).unwrap(), "12\n1969\n");
+        assert_eq!(check(&report, key.get(1).unwrap()), Status::Ok);
+        let expected = Expected {
+            part1: Some(String::from("656")),
+            part2: Some(String::from("969")),
+        };
+        assert_eq!(
+            check(&report, &expected),
+            Status::Failed {
+                problems: vec![String::from("part2: expected \"969\", got \"968\"")]
+            }
+        );
+    }
+
+    /// Every day with a cached or committed input still gets the answers recorded in
+    /// `answers.toml`
+    #[test]
+    fn test_recorded_answers() {
+        let key = AnswerKey::workspace().unwrap();
+        let mut checked = 0;
+        for verification in verify(&key, &Inputs::workspace()) {
+            match verification.status {
+                Status::Ok => checked += 1,
+                Status::Failed { problems } => {
+                    panic!("day {}: {}", verification.day, problems.join("; "))
+                }
+                Status::Skipped { .. } => {}
+            }
+        }
+        assert!(checked > 0, "No day was checked");
+    }
+}
@@ -0,0 +1,110 @@
+//! Day 1: The Tyranny of the Rocket Equation
+
+use aoc_utils::Puzzle;
+use std::str::FromStr;
+
+fn simple_fuel_required(mass: usize) -> usize {
+    if mass <= 8 {
+        return 0;
+    }
+
+    ((mass as f64) / 3.0).floor() as usize - 2
+}
+
+/// A module, which needs fuel for its mass
+#[derive(Debug)]
+pub struct SpaceModule {
+    mass: usize,
+}
+
+impl SpaceModule {
+    fn new(mass: usize) -> SpaceModule {
+        SpaceModule { mass }
+    }
+
+    fn fuel_required(&self) -> usize {
+        simple_fuel_required(self.mass)
+    }
+
+    fn adjusted_fuel_required(&self) -> usize {
+        let mut total = 0;
+        let mut last = self.mass;
+        loop {
+            last = simple_fuel_required(last);
+            if last == 0 {
+                break;
+            }
+            total += last;
+        }
+        total
+    }
+}
+
+impl FromStr for SpaceModule {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mass = input.parse()?;
+
+        Ok(SpaceModule::new(mass))
+    }
+}
+
+/// The fuel for every module, with and without the fuel for the fuel
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    type Input = Vec<SpaceModule>;
+
+    fn parse(input: &str) -> Self::Input {
+        input.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    fn part1(modules: &Self::Input) -> String {
+        let fuel: usize = modules.iter().map(SpaceModule::fuel_required).sum();
+        fuel.to_string()
+    }
+
+    fn part2(modules: &Self::Input) -> String {
+        let fuel: usize = modules
+            .iter()
+            .map(SpaceModule::adjusted_fuel_required)
+            .sum();
+        fuel.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_1() {
+        let sm = SpaceModule::new(12);
+        assert_eq!(sm.fuel_required(), 2);
+
+        let sm = SpaceModule::new(14);
+        assert_eq!(sm.fuel_required(), 2);
+
+        let sm = SpaceModule::new(1969);
+        assert_eq!(sm.fuel_required(), 654);
+
+        let sm = SpaceModule::new(100756);
+        assert_eq!(sm.fuel_required(), 33583);
+    }
+
+    #[test]
+    fn test_adjusted() {
+        let sm = SpaceModule::new(12);
+        assert_eq!(sm.adjusted_fuel_required(), 2);
+
+        let sm = SpaceModule::new(14);
+        assert_eq!(sm.adjusted_fuel_required(), 2);
+
+        let sm = SpaceModule::new(1969);
+        assert_eq!(sm.adjusted_fuel_required(), 966);
+
+        let sm = SpaceModule::new(100756);
+        assert_eq!(sm.adjusted_fuel_required(), 50346);
+    }
+}
@@ -0,0 +1,39 @@
+//! Day 2: 1202 Program Alarm
+
+use aoc_utils::Puzzle;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+
+/// Run the program with a noun and a verb, and get its output
+fn run(program: &Program, noun: i64, verb: i64) -> i64 {
+    let mut processor = IntcodeProcess::new(program);
+    processor.patch(&[(1, noun), (2, verb)]).unwrap();
+    let result = processor.run();
+    assert_eq!(result, Err(IntcodeError::CatchFire));
+    processor.load(0).unwrap()
+}
+
+/// The gravity assist program, restored to its state before the alarm
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        run(program, 12, 2).to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        for noun in 0..=99 {
+            for verb in 0..=99 {
+                if run(program, noun, verb) == 19690720 {
+                    return (100 * noun + verb).to_string();
+                }
+            }
+        }
+        panic!("no noun and verb produce 19690720");
+    }
+}
@@ -1,11 +1,11 @@
+use aoc_utils::Direction;
 use std::fmt::Debug;
 use std::str::FromStr;
 
-pub enum Command {
-    Up(isize),
-    Down(isize),
-    Left(isize),
-    Right(isize),
+/// A stretch of wire, like `U7`
+pub struct Command {
+    pub direction: Direction,
+    pub size: i64,
 }
 
 #[derive(Debug)]
@@ -16,12 +16,7 @@ pub enum CommandParseError {
 
 impl Debug for Command {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        match self {
-            Command::Up(size) => write!(fmt, "U{}", size),
-            Command::Down(size) => write!(fmt, "D{}", size),
-            Command::Left(size) => write!(fmt, "L{}", size),
-            Command::Right(size) => write!(fmt, "R{}", size),
-        }
+        write!(fmt, "{}{}", self.direction.letter(), self.size)
     }
 }
 
@@ -29,30 +24,15 @@ impl FromStr for Command {
     type Err = CommandParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let command = match input.chars().nth(0) {
-            Some('U') => Command::Up(
-                input[1..]
-                    .parse()
-                    .map_err(|_| CommandParseError::InvalidNumber)?,
-            ),
-            Some('D') => Command::Down(
-                input[1..]
-                    .parse()
-                    .map_err(|_| CommandParseError::InvalidNumber)?,
-            ),
-            Some('L') => Command::Left(
-                input[1..]
-                    .parse()
-                    .map_err(|_| CommandParseError::InvalidNumber)?,
-            ),
-            Some('R') => Command::Right(
-                input[1..]
-                    .parse()
-                    .map_err(|_| CommandParseError::InvalidNumber)?,
-            ),
-            _ => Err(CommandParseError::InvalidDirection)?,
-        };
+        let direction = input
+            .chars()
+            .next()
+            .and_then(Direction::from_letter)
+            .ok_or(CommandParseError::InvalidDirection)?;
+        let size = input[1..]
+            .parse()
+            .map_err(|_| CommandParseError::InvalidNumber)?;
 
-        Ok(command)
+        Ok(Command { direction, size })
     }
 }
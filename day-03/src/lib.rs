@@ -0,0 +1,106 @@
+//! Day 3: Crossed Wires
+
+mod command;
+// mod point_iter;
+mod segment;
+
+use aoc_utils::{Point, Puzzle};
+use command::Command;
+// use point_iter::{Point, PointIter};
+use segment::{Segment, SegmentIter};
+
+/// The two wires, as the segments they're made of from the central port
+pub struct Wires {
+    first: Vec<Segment>,
+    second: Vec<Segment>,
+}
+
+fn parse_wire(line: &str) -> Vec<Segment> {
+    let commands: Vec<Command> = line.split(",").map(|s| s.parse().unwrap()).collect();
+    SegmentIter::new(commands.into_iter()).collect()
+}
+
+/// Where the wires cross, closest to the central port
+pub struct Day03;
+
+impl Puzzle for Day03 {
+    type Input = Wires;
+
+    fn parse(input: &str) -> Self::Input {
+        let mut lines = input.lines();
+        let first = lines.next().expect("Expected two lines of input");
+        let second = lines.next().expect("Expected two lines of input");
+
+        Wires {
+            first: parse_wire(first),
+            second: parse_wire(second),
+        }
+    }
+
+    fn part1(wires: &Self::Input) -> String {
+        let mut min_manhatten_distance = None;
+        for first_segment in &wires.first[..] {
+            for second_segment in &wires.second[..] {
+                if let Some(point) = first_segment.intersection(second_segment) {
+                    let manhatten_distance = point.manhattan_distance(Point::ORIGIN);
+                    if let Some(min) = min_manhatten_distance {
+                        if manhatten_distance < min {
+                            min_manhatten_distance = Some(manhatten_distance)
+                        }
+                    } else {
+                        min_manhatten_distance = Some(manhatten_distance)
+                    }
+                }
+            }
+        }
+
+        min_manhatten_distance
+            .expect("The wires should cross")
+            .to_string()
+    }
+
+    fn part2(wires: &Self::Input) -> String {
+        let mut min_path = None;
+        let mut first_path_magnitude = 0;
+        for first_segment in &wires.first[..] {
+            let mut second_path_magnitude = 0;
+            for second_segment in &wires.second[..] {
+                if let Some(pt) = first_segment.intersection(second_segment) {
+                    let total_distance = first_segment.magnitude_to_point(&pt)
+                        + second_segment.magnitude_to_point(&pt)
+                        + first_path_magnitude
+                        + second_path_magnitude;
+
+                    if let Some(min) = min_path {
+                        if total_distance < min {
+                            min_path = Some(total_distance)
+                        }
+                    } else {
+                        min_path = Some(total_distance)
+                    }
+                }
+                second_path_magnitude += second_segment.magnitude();
+            }
+            first_path_magnitude += first_segment.magnitude();
+        }
+
+        min_path.expect("The wires should cross").to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wires() {
+        let wires = Day03::parse("R8,U5,L5,D3\nU7,R6,D4,L4\n");
+        assert_eq!(Day03::part1(&wires), "6");
+        assert_eq!(Day03::part2(&wires), "30");
+
+        let wires =
+            Day03::parse("R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83\n");
+        assert_eq!(Day03::part1(&wires), "159");
+        assert_eq!(Day03::part2(&wires), "610");
+    }
+}
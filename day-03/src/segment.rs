@@ -1,12 +1,11 @@
 use super::command::Command;
+use aoc_utils::Point;
 use std::cmp::{max, min};
 
-pub type Point = (isize, isize);
-
 #[derive(Debug, Eq, PartialEq)]
 pub enum Segment {
-    Vertical { x: isize, y0: isize, y1: isize },
-    Horizontal { y: isize, x0: isize, x1: isize },
+    Vertical { x: i64, y0: i64, y1: i64 },
+    Horizontal { y: i64, x0: i64, x1: i64 },
 }
 
 impl Segment {
@@ -16,18 +15,18 @@ impl Segment {
             (Vertical { x, y0, y1 }, Horizontal { y, x0, x1 })
                 if min(x0, x1) < x && x < max(x0, x1) && min(y0, y1) < y && y < max(y0, y1) =>
             {
-                Some((*x, *y))
+                Some(Point::new(*x, *y))
             }
             (Horizontal { y, x0, x1 }, Vertical { x, y0, y1 })
                 if min(x0, x1) < x && x < max(x0, x1) && min(y0, y1) < y && y < max(y0, y1) =>
             {
-                Some((*x, *y))
+                Some(Point::new(*x, *y))
             }
             _ => None,
         }
     }
 
-    pub fn magnitude(&self) -> isize {
+    pub fn magnitude(&self) -> i64 {
         use Segment::*;
         match self {
             Vertical { y0, y1, .. } => (y1 - y0).abs(),
@@ -35,11 +34,11 @@ impl Segment {
         }
     }
 
-    pub fn magnitude_to_point(&self, point: &Point) -> isize {
+    pub fn magnitude_to_point(&self, point: &Point) -> i64 {
         use Segment::*;
         match self {
-            Vertical { y0, .. } => (point.1 - y0).abs(),
-            Horizontal { x0, .. } => (point.0 - x0).abs(),
+            Vertical { y0, .. } => (point.y - y0).abs(),
+            Horizontal { x0, .. } => (point.x - x0).abs(),
         }
     }
 }
@@ -54,7 +53,7 @@ impl<I: Iterator<Item = Command>> SegmentIter<I> {
     pub fn new(iterator: I) -> SegmentIter<I> {
         SegmentIter {
             iterator,
-            current_point: (0, 0),
+            current_point: Point::ORIGIN,
         }
     }
 }
@@ -66,43 +65,23 @@ where
     type Item = Segment;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let direction = self.iterator.next()?;
+        let command = self.iterator.next()?;
 
         let previous_point = self.current_point;
+        let next_point = previous_point + command.direction.vec() * command.size;
 
-        let (segment, next_point) = match direction {
-            Command::Up(size) => (
-                Segment::Vertical {
-                    x: previous_point.0,
-                    y0: previous_point.1,
-                    y1: previous_point.1 + size,
-                },
-                (self.current_point.0, self.current_point.1 + size),
-            ),
-            Command::Down(size) => (
-                Segment::Vertical {
-                    x: previous_point.0,
-                    y0: previous_point.1,
-                    y1: previous_point.1 - size,
-                },
-                (self.current_point.0, self.current_point.1 - size),
-            ),
-            Command::Left(size) => (
-                Segment::Horizontal {
-                    y: previous_point.1,
-                    x0: previous_point.0,
-                    x1: previous_point.0 - size,
-                },
-                (self.current_point.0 - size, self.current_point.1),
-            ),
-            Command::Right(size) => (
-                Segment::Horizontal {
-                    y: previous_point.1,
-                    x0: previous_point.0,
-                    x1: previous_point.0 + size,
-                },
-                (self.current_point.0 + size, self.current_point.1),
-            ),
+        let segment = if previous_point.x == next_point.x {
+            Segment::Vertical {
+                x: previous_point.x,
+                y0: previous_point.y,
+                y1: next_point.y,
+            }
+        } else {
+            Segment::Horizontal {
+                y: previous_point.y,
+                x0: previous_point.x,
+                x1: next_point.x,
+            }
         };
 
         self.current_point = next_point;
@@ -129,30 +108,32 @@ mod test {
             x1: 5,
         };
 
-        assert_eq!(s1.intersection(&s2), Some((2, 1)));
-        assert_eq!(s2.intersection(&s1), Some((2, 1)));
+        assert_eq!(s1.intersection(&s2), Some(Point::new(2, 1)));
+        assert_eq!(s2.intersection(&s1), Some(Point::new(2, 1)));
     }
 
     #[test]
     fn test_iter() {
-        use Command::*;
         use Segment::*;
 
-        let vec = vec![Up(10), Right(10), Down(5), Left(5)];
-        let mut iter = SegmentIter::new(vec.into_iter());
+        // Up is towards smaller y, the same as on a grid
+        let commands = ["U10", "R10", "D5", "L5"]
+            .iter()
+            .map(|s| s.parse().unwrap());
+        let mut iter = SegmentIter::new(commands);
 
         assert_eq!(
             iter.next(),
             Some(Vertical {
                 x: 0,
                 y0: 0,
-                y1: 10
+                y1: -10
             })
         );
         assert_eq!(
             iter.next(),
             Some(Horizontal {
-                y: 10,
+                y: -10,
                 x0: 0,
                 x1: 10
             })
@@ -161,14 +142,14 @@ mod test {
             iter.next(),
             Some(Vertical {
                 x: 10,
-                y0: 10,
-                y1: 5
+                y0: -10,
+                y1: -5
             })
         );
         assert_eq!(
             iter.next(),
             Some(Horizontal {
-                y: 5,
+                y: -5,
                 x0: 10,
                 x1: 5
             })
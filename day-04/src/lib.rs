@@ -0,0 +1,124 @@
+//! Day 4: Secure Container
+
+use aoc_utils::Puzzle;
+use std::ops::RangeInclusive;
+
+fn is_valid_number(num: usize) -> bool {
+    let mut num = num;
+    let mut last_numeral = num % 10;
+    num /= 10;
+
+    let mut found_repeat = false;
+
+    while num > 0 {
+        let current_numeral = num % 10;
+
+        if current_numeral > last_numeral {
+            return false;
+        }
+        if current_numeral == last_numeral {
+            found_repeat = true;
+        }
+
+        last_numeral = current_numeral;
+        num /= 10;
+    }
+
+    found_repeat
+}
+
+fn is_valid_number_2(num: usize) -> bool {
+    let mut num = num;
+    let mut last_numeral = num % 10;
+    num /= 10;
+
+    let mut found_repeat = false;
+    let mut current_repeat_count = 0;
+
+    while num > 0 {
+        let current_numeral = num % 10;
+
+        if current_numeral > last_numeral {
+            return false;
+        }
+        if current_numeral == last_numeral {
+            if current_repeat_count == 0 {
+                current_repeat_count = 2;
+            } else {
+                current_repeat_count += 1;
+            }
+        } else {
+            if current_repeat_count == 2 {
+                found_repeat = true;
+            }
+            current_repeat_count = 0;
+        }
+
+        last_numeral = current_numeral;
+        num /= 10;
+    }
+
+    found_repeat || current_repeat_count == 2
+}
+
+/// How many passwords in the range could be the one
+pub struct Day04;
+
+impl Puzzle for Day04 {
+    type Input = RangeInclusive<usize>;
+
+    /// Parse a range like `372304-847060`
+    fn parse(input: &str) -> Self::Input {
+        let mut bounds = input.trim().split('-').map(|n| n.parse().unwrap());
+        let start = bounds.next().unwrap();
+        let end = bounds.next().expect("Expected a range like 372304-847060");
+        start..=end
+    }
+
+    fn part1(range: &Self::Input) -> String {
+        range
+            .clone()
+            .filter(|&i| is_valid_number(i))
+            .count()
+            .to_string()
+    }
+
+    fn part2(range: &Self::Input) -> String {
+        range
+            .clone()
+            .filter(|&i| is_valid_number_2(i))
+            .count()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_1() {
+        assert!(is_valid_number(111111));
+        assert!(!is_valid_number(223450));
+        assert!(!is_valid_number(123789));
+    }
+
+    #[test]
+    fn test_2() {
+        assert!(!is_valid_number_2(111111));
+        assert!(!is_valid_number_2(223450));
+        assert!(!is_valid_number_2(123789));
+        assert!(is_valid_number_2(112233));
+        assert!(!is_valid_number_2(123444));
+        assert!(is_valid_number_2(111122));
+
+        assert!(is_valid_number_2(111233));
+        assert!(!is_valid_number_2(122223));
+        assert!(is_valid_number_2(122334));
+        assert!(is_valid_number_2(112345));
+        assert!(is_valid_number_2(112334));
+        assert!(is_valid_number_2(113334));
+        assert!(!is_valid_number_2(133333));
+        assert!(!is_valid_number_2(333335));
+    }
+}
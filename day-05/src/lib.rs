@@ -0,0 +1,32 @@
+//! Day 5: Sunny with a Chance of Asteroids
+
+use aoc_utils::Puzzle;
+use intcode::Program;
+
+/// Run the diagnostic program for a system, and get its diagnostic code
+fn diagnostic_code(program: &Program, system_id: i64) -> i64 {
+    let outputs = intcode::run(program.as_slice(), &[system_id]).unwrap();
+    // Assert that everything but the last output is 0.
+    let (last, diagnostics) = outputs.split_last().unwrap();
+    assert!(diagnostics.iter().all(|x| *x == 0));
+    *last
+}
+
+/// The diagnostic codes for the air conditioner and the thermal radiator controller
+pub struct Day05;
+
+impl Puzzle for Day05 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        diagnostic_code(program, 1).to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        diagnostic_code(program, 5).to_string()
+    }
+}
@@ -0,0 +1,169 @@
+//! Day 6: Universal Orbit Map
+
+use aoc_utils::pathfinding::bfs;
+use aoc_utils::Puzzle;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+struct OrbitListing {
+    orbitee: String,
+    orbiter: String,
+}
+
+impl FromStr for OrbitListing {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut iter = input.split(")");
+        let orbitee = iter.next().ok_or(())?.into();
+        let orbiter = iter.next().ok_or(())?.into();
+
+        if iter.next().is_some() {
+            return Err(());
+        }
+
+        Ok(OrbitListing { orbitee, orbiter })
+    }
+}
+
+fn build_map(listings: impl Iterator<Item = OrbitListing>) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for listing in listings {
+        map.insert(listing.orbiter, listing.orbitee);
+    }
+
+    map
+}
+
+fn checksum(map: &HashMap<String, String>) -> usize {
+    let mut checksum = 0;
+
+    for key in map.keys() {
+        checksum += path_size(key, map);
+    }
+
+    checksum
+}
+
+fn path_size(key: &str, map: &HashMap<String, String>) -> usize {
+    if let Some(value) = map.get(key) {
+        1 + path_size(value, map)
+    } else {
+        0
+    }
+}
+
+/// The number of orbital transfers between the objects `k1` and `k2` orbit
+///
+/// Returns `None` if either of them doesn't orbit anything, or the objects they orbit aren't
+/// connected.
+fn jumps_between(k1: &str, k2: &str, map: &HashMap<String, String>) -> Option<usize> {
+    let mut orbiters: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (orbiter, orbitee) in map {
+        orbiters.entry(orbitee).or_default().push(orbiter);
+    }
+
+    // Moving between objects goes both ways: inwards to what an object orbits, or outwards to
+    // what orbits it
+    let neighbors = |key: &&str| {
+        let inwards = map.get(*key).map(String::as_str);
+        let outwards = orbiters.get(key).into_iter().flatten().copied();
+        inwards.into_iter().chain(outwards).collect::<Vec<_>>()
+    };
+    let start = map.get(k1)?.as_str();
+    let goal = map.get(k2)?.as_str();
+    bfs(start, neighbors, |&key| key == goal)
+}
+
+/// The orbit count checksum, and the transfers from you to Santa
+pub struct Day06;
+
+impl Puzzle for Day06 {
+    /// The object each object orbits
+    type Input = HashMap<String, String>;
+
+    fn parse(input: &str) -> Self::Input {
+        build_map(input.lines().map(|line| line.parse().unwrap()))
+    }
+
+    fn part1(map: &Self::Input) -> String {
+        checksum(map).to_string()
+    }
+
+    fn part2(map: &Self::Input) -> String {
+        jumps_between("YOU", "SAN", map)
+            .expect("YOU and SAN should orbit connected objects")
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let result = "COM)A".parse::<OrbitListing>();
+
+        assert_eq!(
+            result,
+            Ok(OrbitListing {
+                orbitee: "COM".into(),
+                orbiter: "A".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        let orbits = &[
+            "COM)B", "B)C", "C)D", "D)E", "E)F", "B)G", "G)H", "D)I", "E)J", "J)K", "K)L",
+        ];
+
+        let orbits = orbits
+            .iter()
+            .map(|listing| listing.parse::<OrbitListing>().unwrap());
+
+        let map = build_map(orbits);
+
+        assert_eq!(checksum(&map), 42);
+    }
+
+    #[test]
+    fn test_jumps_between() {
+        let orbits = &[
+            "COM)B", "B)C", "C)D", "D)E", "E)F", "B)G", "G)H", "D)I", "E)J", "J)K", "K)L", "K)YOU",
+            "I)SAN",
+        ];
+
+        let orbits = orbits
+            .iter()
+            .map(|listing| listing.parse::<OrbitListing>().unwrap());
+
+        let map = build_map(orbits);
+
+        assert_eq!(jumps_between("YOU", "SAN", &map), Some(4));
+    }
+
+    #[test]
+    fn test_jumps_between_neighbors() {
+        let orbits = &["COM)B", "B)C", "C)D", "B)E", "X)Y"];
+
+        let orbits = orbits
+            .iter()
+            .map(|listing| listing.parse::<OrbitListing>().unwrap());
+
+        let map = build_map(orbits);
+
+        // The same object, and two objects orbiting the same thing, need no transfers
+        assert_eq!(jumps_between("D", "D", &map), Some(0));
+        assert_eq!(jumps_between("C", "E", &map), Some(0));
+        // D orbits C, which orbits B
+        assert_eq!(jumps_between("D", "C", &map), Some(1));
+        assert_eq!(jumps_between("C", "D", &map), Some(1));
+        // COM doesn't orbit anything, and Y isn't connected to the rest
+        assert_eq!(jumps_between("COM", "D", &map), None);
+        assert_eq!(jumps_between("Y", "D", &map), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Find the phase settings that produce the most thrust from a chain of amplifiers
+
+use aoc_utils::{permutations, Puzzle};
+use intcode::{AmplifierChain, IntcodeError};
+use std::thread;
+
+/// Find the phase settings for a chain of five amplifiers that produce the most thrust
+pub fn find_max_thrust_amplifier(program: Vec<i64>) -> (i64, Vec<i64>) {
+    find_max_parallel(program, &[0, 1, 2, 3, 4], AmplifierChain::run_once)
+}
+
+/// Find the phase settings for a loop of five amplifiers that produce the most thrust
+///
+/// The settings are tried in parallel, but the result is always the same as
+/// `find_max_thrust_amplifier_feedback_sequential`, even when several settings tie.
+pub fn find_max_thrust_amplifier_feedback(program: Vec<i64>) -> (i64, Vec<i64>) {
+    find_max_parallel(program, &[5, 6, 7, 8, 9], AmplifierChain::run_feedback)
+}
+
+/// Like `find_max_thrust_amplifier_feedback`, but trying each setting one at a time
+pub fn find_max_thrust_amplifier_feedback_sequential(program: Vec<i64>) -> (i64, Vec<i64>) {
+    let candidates: Vec<_> = permutations(&[5, 6, 7, 8, 9]).collect();
+    find_max(&program, &candidates, AmplifierChain::run_feedback)
+}
+
+fn find_max_parallel(
+    program: Vec<i64>,
+    phases: &[i64],
+    run: impl Fn(&AmplifierChain) -> Result<i64, IntcodeError> + Sync,
+) -> (i64, Vec<i64>) {
+    let candidates: Vec<_> = permutations(phases).collect();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+
+    let program = &program;
+    let run = &run;
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || find_max(program, chunk, run)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    // Chunks are combined in order, keeping the earliest of equal maximums, just like
+    // `find_max` does
+    let mut max = i64::MIN;
+    let mut settings = Vec::new();
+    for (output, phases) in results {
+        if output > max {
+            max = output;
+            settings = phases;
+        }
+    }
+
+    (max, settings)
+}
+
+fn find_max(
+    program: &[i64],
+    candidates: &[Vec<i64>],
+    run: impl Fn(&AmplifierChain) -> Result<i64, IntcodeError>,
+) -> (i64, Vec<i64>) {
+    let mut max = i64::MIN;
+    let mut settings = Vec::new();
+
+    for phases in candidates {
+        let chain = AmplifierChain::new(program.to_vec(), phases);
+        let output = run(&chain).unwrap();
+        if output > max {
+            max = output;
+            settings = phases.clone();
+        }
+    }
+
+    (max, settings)
+}
+
+/// The most thrust from the amplifiers, in a chain and in a feedback loop
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Self::Input {
+        input
+            .trim()
+            .split(",")
+            .map(|s| s.parse::<i64>().unwrap())
+            .collect()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        find_max_thrust_amplifier(program.clone()).0.to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        find_max_thrust_amplifier_feedback(program.clone())
+            .0
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_example_a1() {
+        let input = vec![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+
+        let output = AmplifierChain::new(input, &[4, 3, 2, 1, 0])
+            .run_once()
+            .unwrap();
+
+        assert_eq!(output, 43210);
+    }
+
+    #[test]
+    fn test_example_a1_find() {
+        let input = vec![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+
+        let (max, settings) = find_max_thrust_amplifier(input);
+
+        assert_eq!(max, 43210);
+        assert_eq!(settings, vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_example_a2() {
+        let input = vec![
+            3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23,
+            99, 0, 0,
+        ];
+
+        let output = AmplifierChain::new(input, &[0, 1, 2, 3, 4])
+            .run_once()
+            .unwrap();
+
+        assert_eq!(output, 54321);
+    }
+
+    #[test]
+    fn test_example_a2_find() {
+        let input = vec![
+            3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23,
+            99, 0, 0,
+        ];
+
+        let (max, settings) = find_max_thrust_amplifier(input);
+
+        assert_eq!(max, 54321);
+        assert_eq!(settings, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_example_b1() {
+        let input = vec![
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ];
+
+        let output = AmplifierChain::new(input, &[9, 8, 7, 6, 5])
+            .run_feedback()
+            .unwrap();
+
+        assert_eq!(output, 139629729);
+    }
+
+    #[test]
+    fn test_example_b2() {
+        let input = vec![
+            3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001, 54,
+            -5, 54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54, 0, 55, 1001, 55, 1, 55, 2, 53, 55, 53, 4,
+            53, 1001, 56, -1, 56, 1005, 56, 6, 99, 0, 0, 0, 0, 10,
+        ];
+
+        let output = AmplifierChain::new(input, &[9, 7, 8, 5, 6])
+            .run_feedback()
+            .unwrap();
+
+        assert_eq!(output, 18216);
+    }
+
+    #[test]
+    fn test_example_b2_find() {
+        let input = vec![
+            3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001, 54,
+            -5, 54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54, 0, 55, 1001, 55, 1, 55, 2, 53, 55, 53, 4,
+            53, 1001, 56, -1, 56, 1005, 56, 6, 99, 0, 0, 0, 0, 10,
+        ];
+
+        let (max, settings) = find_max_thrust_amplifier_feedback(input);
+
+        assert_eq!(max, 18216);
+        assert_eq!(settings, vec![9, 7, 8, 5, 6]);
+    }
+
+    #[test]
+    fn test_feedback_parallel_matches_sequential() {
+        let input = vec![
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ];
+
+        assert_eq!(
+            find_max_thrust_amplifier_feedback(input.clone()),
+            find_max_thrust_amplifier_feedback_sequential(input)
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! Day 8: Space Image Format
+
+use aoc_utils::ansi::render_blocks;
+#[cfg(feature = "gif")]
+use aoc_utils::image::write_gif;
+use aoc_utils::image::{parse_palette, write_png, write_ppm, Rgb};
+use aoc_utils::ocr::recognize;
+use aoc_utils::{Grid, Puzzle};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The size of the password image
+pub const WIDTH: usize = 25;
+pub const HEIGHT: usize = 6;
+
+/// Why an image couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// A character that isn't a pixel: 0 for black, 1 for white, or 2 for transparent. The
+    /// position counts characters from the start of the input.
+    InvalidCharacter { c: char, position: usize },
+    /// The last layer, counting from 0, ran out of pixels before it was full
+    TruncatedLayer { layer: usize, pixels: usize },
+    /// There are no pixels, or the layers have no area
+    Empty,
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ImageError::InvalidCharacter { c, position } => {
+                write!(f, "Invalid character {:?} at position {}", c, position)
+            }
+            ImageError::TruncatedLayer { layer, pixels } => {
+                write!(f, "Layer {} only has {} pixels", layer, pixels)
+            }
+            ImageError::Empty => write!(f, "The image is empty"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+/// One layer of an image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    pixels: Grid<Pixel>,
+}
+
+impl Layer {
+    /// The pixels of the layer
+    pub fn pixels(&self) -> &Grid<Pixel> {
+        &self.pixels
+    }
+
+    /// The number of pixels of one color
+    pub fn count(&self, pixel: Pixel) -> usize {
+        self.pixels.cells().iter().filter(|&&p| p == pixel).count()
+    }
+
+    /// Show this layer through the transparent pixels of the layers in front of it
+    fn stack_under(&self, pixels: &mut Grid<Pixel>) {
+        for (position, &pixel) in self.pixels.iter() {
+            pixels[position] = pixels[position] + pixel;
+        }
+    }
+}
+
+/// The layers of an image, from the front
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+}
+
+impl Image {
+    /// Read an image made of layers of the given size, one digit per pixel
+    ///
+    /// Whitespace between the digits is ignored.
+    pub fn parse(input: &str, width: usize, height: usize) -> Result<Image, ImageError> {
+        let mut pixels = Vec::new();
+        for (position, c) in input.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let pixel = Pixel::from_digit(c).ok_or(ImageError::InvalidCharacter { c, position })?;
+            pixels.push(pixel);
+        }
+
+        let size = width * height;
+        if size == 0 || pixels.is_empty() {
+            return Err(ImageError::Empty);
+        }
+        if pixels.len() % size != 0 {
+            return Err(ImageError::TruncatedLayer {
+                layer: pixels.len() / size,
+                pixels: pixels.len() % size,
+            });
+        }
+
+        let layers = pixels
+            .chunks(size)
+            .map(|chunk| Layer {
+                pixels: Grid::from_vec(width, height, chunk.to_vec()).unwrap(),
+            })
+            .collect();
+        Ok(Image {
+            width,
+            height,
+            layers,
+        })
+    }
+
+    /// Get the number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The layers, from the front
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// The number of white pixels times the number of transparent ones, on the layer with
+    /// the fewest black pixels
+    pub fn checksum(&self) -> usize {
+        let layer = self
+            .layers
+            .iter()
+            .min_by_key(|layer| layer.count(Pixel::Black))
+            .expect("An image always has a layer");
+        layer.count(Pixel::White) * layer.count(Pixel::Transparent)
+    }
+
+    /// Stack the layers to see the image
+    pub fn rasterize(&self) -> RasterizedImage {
+        let mut pixels = Grid::new(self.width, self.height, Pixel::Transparent);
+
+        for layer in self.layers.iter() {
+            layer.stack_under(&mut pixels);
+        }
+
+        RasterizedImage { pixels }
+    }
+
+    /// Save an animated GIF of the layers being stacked one at a time, from the front
+    #[cfg(feature = "gif")]
+    pub fn write_gif(
+        &self,
+        path: impl AsRef<Path>,
+        scale: usize,
+        palette: &Palette,
+    ) -> io::Result<()> {
+        let mut pixels = Grid::new(self.width, self.height, Pixel::Transparent);
+        let mut frames = vec![pixels.clone()];
+        for layer in self.layers.iter() {
+            layer.stack_under(&mut pixels);
+            frames.push(pixels.clone());
+        }
+
+        let out = BufWriter::new(File::create(path)?);
+        write_gif(out, &frames, scale, GIF_DELAY, |&pixel| {
+            palette.color(pixel)
+        })
+    }
+}
+
+/// How long each frame of an animation shows for, in hundredths of a second
+#[cfg(feature = "gif")]
+const GIF_DELAY: u16 = 10;
+
+/// The image as it's seen, with the layers stacked on top of each other
+#[derive(Debug)]
+pub struct RasterizedImage {
+    pixels: Grid<Pixel>,
+}
+
+impl RasterizedImage {
+    /// Draw the image in the terminal, in color
+    pub fn to_blocks(&self, palette: &Palette) -> String {
+        render_blocks(&self.pixels, |&pixel| palette.color(pixel))
+    }
+
+    /// Save the image as a PNG, with each pixel drawn `scale` times as wide and tall
+    pub fn write_png(
+        &self,
+        path: impl AsRef<Path>,
+        scale: usize,
+        palette: &Palette,
+    ) -> io::Result<()> {
+        let out = BufWriter::new(File::create(path)?);
+        write_png(out, &self.pixels, scale, |&pixel| palette.color(pixel))
+    }
+
+    /// Save the image as a PPM, with each pixel drawn `scale` times as wide and tall
+    pub fn write_ppm(
+        &self,
+        path: impl AsRef<Path>,
+        scale: usize,
+        palette: &Palette,
+    ) -> io::Result<()> {
+        let out = BufWriter::new(File::create(path)?);
+        write_ppm(out, &self.pixels, scale, |&pixel| palette.color(pixel))
+    }
+}
+
+/// The colors pixels are saved in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub black: Rgb,
+    pub white: Rgb,
+    /// For pixels that no layer colors in
+    pub transparent: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            black: [0, 0, 0],
+            white: [255, 255, 255],
+            transparent: [128, 128, 128],
+        }
+    }
+}
+
+impl Palette {
+    fn color(&self, pixel: Pixel) -> Rgb {
+        match pixel {
+            Pixel::Black => self.black,
+            Pixel::White => self.white,
+            Pixel::Transparent => self.transparent,
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// Parse the colors for black and white as hex, like `1e1e2e,f9e2af`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match parse_palette(input)?[..] {
+            [black, white] => Ok(Palette {
+                black,
+                white,
+                ..Palette::default()
+            }),
+            _ => Err(format!(
+                "Expected two colors, like 000000,ffffff, got {}",
+                input
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RasterizedImage {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}", self.pixels)
+    }
+}
+
+/// The color of a pixel
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
+pub enum Pixel {
+    #[default]
+    Transparent,
+    Black,
+    White,
+}
+
+impl Pixel {
+    /// The pixel for a digit of the image format
+    fn from_digit(c: char) -> Option<Pixel> {
+        match c {
+            '0' => Some(Pixel::Black),
+            '1' => Some(Pixel::White),
+            '2' => Some(Pixel::Transparent),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for Pixel {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                Pixel::White => " ",
+                Pixel::Black => "*",
+                Pixel::Transparent => "?",
+            }
+        )
+    }
+}
+
+impl std::fmt::Display for Pixel {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                Pixel::Black => " ",
+                Pixel::White => "*",
+                Pixel::Transparent => "?",
+            }
+        )
+    }
+}
+
+impl std::ops::Add for Pixel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match self {
+            Pixel::Black => Pixel::Black,
+            Pixel::White => Pixel::White,
+            Pixel::Transparent => other,
+        }
+    }
+}
+
+/// The checksum of the password image, and the password it shows
+pub struct Day08;
+
+impl Puzzle for Day08 {
+    type Input = Image;
+
+    fn parse(input: &str) -> Self::Input {
+        Image::parse(input, WIDTH, HEIGHT).unwrap()
+    }
+
+    fn part1(image: &Self::Input) -> String {
+        image.checksum().to_string()
+    }
+
+    /// The password, or the picture of it if it can't be read
+    fn part2(image: &Self::Input) -> String {
+        let image = image.rasterize();
+        let lit = image.pixels.map(|&pixel| pixel == Pixel::White);
+        recognize(&lit).unwrap_or_else(|| image.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let image = Image::parse("001211\n222012\n", 3, 2).unwrap();
+        assert_eq!(image.layers().len(), 2);
+        assert_eq!(image.layers()[0].count(Pixel::Black), 2);
+        assert_eq!(image.layers()[1].pixels()[(2, 0)], Pixel::Transparent);
+        assert_eq!(image.checksum(), 4);
+
+        assert_eq!(
+            Image::parse("0012x1", 3, 2),
+            Err(ImageError::InvalidCharacter {
+                c: 'x',
+                position: 4
+            })
+        );
+        assert_eq!(
+            Image::parse("0012110", 3, 2),
+            Err(ImageError::TruncatedLayer {
+                layer: 1,
+                pixels: 1
+            })
+        );
+        assert_eq!(Image::parse("\n", 3, 2), Err(ImageError::Empty));
+        assert_eq!(Image::parse("0012", 0, 2), Err(ImageError::Empty));
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let image = Image::parse("0222112222120000", 2, 2).unwrap();
+        assert_eq!(image.rasterize().to_string(), " *\n* \n");
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(
+            "1e1e2e,#F9E2AF".parse(),
+            Ok(Palette {
+                black: [0x1e, 0x1e, 0x2e],
+                white: [0xf9, 0xe2, 0xaf],
+                ..Palette::default()
+            })
+        );
+        assert!("000000".parse::<Palette>().is_err());
+        assert!("000000,fffff".parse::<Palette>().is_err());
+    }
+}
@@ -1,252 +1,68 @@
-use std::io::{self, Read};
+use aoc_utils::puzzle::{print_answers, read_stdin};
+use day_08::{Day08, Image, Palette, HEIGHT, WIDTH};
+use std::env;
+use std::process;
 
-struct DigitIterator<R> {
-    read: R,
-}
-
-impl<R> DigitIterator<R>
-where
-    R: Read,
-{
-    fn new(read: R) -> Self {
-        DigitIterator { read }
-    }
-}
-
-impl<R> Iterator for DigitIterator<R>
-where
-    R: Read,
-{
-    type Item = u8;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            let mut n = [0];
-            let result = self.read.read(&mut n);
-            match result {
-                Ok(0) => return None,
-                Ok(1) => {
-                    if n[0] == 10 {
-                        continue;
-                    } else if 0x30 <= n[0] && n[0] <= 0x39 {
-                        return Some(n[0] - 0x30);
-                    } else {
-                        panic!("Unexpected character '{}'", n[0] as char);
-                    }
-                }
-                Ok(size) => panic!("How in the world did we read {} bytes?", size),
-                Err(e) => panic!("Unexpected error: {}", e),
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Layer {
-    width: usize,
-    height: usize,
-    items: Vec<u8>,
-}
-
-enum LayerError {
-    NoInputAvailable,
-    UnexpectedEndOfInput,
-}
-
-impl Layer {
-    fn new(
-        width: usize,
-        height: usize,
-        source: &mut impl Iterator<Item = u8>,
-    ) -> Result<Layer, LayerError> {
-        let mut items = Vec::with_capacity(width * height);
-        for i in 0..width {
-            for j in 0..height {
-                match source.next() {
-                    Some(next) => items.push(next),
-                    None => {
-                        if i == 0 && j == 0 {
-                            Err(LayerError::NoInputAvailable)?
-                        } else {
-                            Err(LayerError::UnexpectedEndOfInput)?
-                        }
-                    }
-                }
-            }
-        }
-        Ok(Layer {
-            width,
-            height,
-            items,
-        })
-    }
-
-    fn count_digit(&self, digit: u8) -> usize {
-        self.items.iter().filter(|&i| *i == digit).count()
-    }
-}
-
-#[derive(Debug)]
-struct Image {
-    width: usize,
-    height: usize,
-    layers: Vec<Layer>,
-}
-
-impl Image {
-    fn new(
-        width: usize,
-        height: usize,
-        source: &mut impl Iterator<Item = u8>,
-    ) -> Result<Image, String> {
-        let mut layers = Vec::new();
-        loop {
-            match Layer::new(width, height, source) {
-                Ok(layer) => layers.push(layer),
-                Err(LayerError::NoInputAvailable) => break,
-                Err(LayerError::UnexpectedEndOfInput) => {
-                    Err("Unexpected end of input".to_string())?
-                }
-            }
-        }
-
-        Ok(Image {
-            width,
-            height,
-            layers,
-        })
-    }
-
-    fn rasterize(&self) -> RasterizedImage {
-        let mut pixels = Vec::new();
-
-        for _ in 0..self.width {
-            for _ in 0..self.height {
-                pixels.push(Pixel::Transparent);
-            }
-        }
-
-        for layer in self.layers.iter() {
-            for (idx, item) in layer.items.iter().enumerate() {
-                pixels[idx] = pixels[idx] + (*item).into()
-            }
-        }
+const USAGE: &str = "Usage: day-08 [--width <n>] [--height <n>] [--blocks] [--png <file>] \
+                     [--ppm <file>] [--gif <file>] [--scale <n>] [--palette <black>,<white>] \
+                     < input";
 
-        RasterizedImage {
-            width: self.width,
-            height: self.height,
-            pixels,
+fn main() {
+    let mut width = WIDTH;
+    let mut height = HEIGHT;
+    let mut blocks = false;
+    let mut png = None;
+    let mut ppm = None;
+    let mut gif = None;
+    let mut scale = 10;
+    let mut palette = Palette::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--blocks" {
+            blocks = true;
+            continue;
         }
-    }
-}
-
-#[derive(Debug)]
-struct RasterizedImage {
-    width: usize,
-    height: usize,
-    pixels: Vec<Pixel>,
-}
-
-impl std::fmt::Display for RasterizedImage {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        let mut iter = self.pixels.iter();
-        for _ in 0..self.height {
-            for _ in 0..self.width {
-                write!(fmt, "{}", iter.next().unwrap())?;
-            }
-            write!(fmt, "\n")?;
+        let value = args.next().expect(USAGE);
+        match arg.as_str() {
+            "--width" => width = value.parse().expect(USAGE),
+            "--height" => height = value.parse().expect(USAGE),
+            "--png" => png = Some(value),
+            "--ppm" => ppm = Some(value),
+            "--gif" => gif = Some(value),
+            "--scale" => scale = value.parse().expect(USAGE),
+            "--palette" => palette = value.parse().unwrap(),
+            _ => panic!("{}", USAGE),
         }
-
-        assert_eq!(iter.next(), None);
-        Ok(())
     }
-}
 
-#[derive(Eq, PartialEq, Clone, Copy)]
-enum Pixel {
-    Transparent,
-    Black,
-    White,
-}
+    let image = Image::parse(&read_stdin(), width, height).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    print_answers::<Day08>(&image);
 
-impl From<u8> for Pixel {
-    fn from(item: u8) -> Pixel {
-        match item {
-            0 => Pixel::Black,
-            1 => Pixel::White,
-            2 => Pixel::Transparent,
-            i => panic!("Unexpected pixel value {}", i),
-        }
+    let rasterized = image.rasterize();
+    if blocks {
+        print!("{}", rasterized.to_blocks(&palette));
     }
-}
-
-impl Default for Pixel {
-    fn default() -> Self {
-        Pixel::Transparent
+    if let Some(path) = png {
+        rasterized.write_png(path, scale, &palette).unwrap();
     }
-}
-
-impl std::fmt::Debug for Pixel {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(
-            fmt,
-            "{}",
-            match self {
-                Pixel::White => " ",
-                Pixel::Black => "*",
-                Pixel::Transparent => "?",
-            }
-        )
+    if let Some(path) = ppm {
+        rasterized.write_ppm(path, scale, &palette).unwrap();
     }
-}
-
-impl std::fmt::Display for Pixel {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(
-            fmt,
-            "{}",
-            match self {
-                Pixel::Black => " ",
-                Pixel::White => "*",
-                Pixel::Transparent => "?",
-            }
-        )
+    if let Some(path) = gif {
+        write_gif(&image, &path, scale, &palette);
     }
 }
 
-impl std::ops::Add for Pixel {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        match self {
-            Pixel::Black => Pixel::Black,
-            Pixel::White => Pixel::White,
-            Pixel::Transparent => other,
-        }
-    }
+#[cfg(feature = "gif")]
+fn write_gif(image: &Image, path: &str, scale: usize, palette: &Palette) {
+    image.write_gif(path, scale, palette).unwrap();
 }
 
-fn main() {
-    let mut digit_iterator = DigitIterator::new(io::stdin());
-
-    let image = Image::new(25, 6, &mut digit_iterator).unwrap();
-    assert_eq!(digit_iterator.next(), None);
-
-    let mut fewest = 25 * 6 + 1;
-    let mut focus_layer = None;
-    for layer in image.layers.iter() {
-        let zero_count = layer.count_digit(0);
-        if zero_count < fewest {
-            focus_layer = Some(layer);
-            fewest = zero_count;
-        }
-    }
-
-    let layer = focus_layer.expect("A minimum layer should have been found");
-    let ones = layer.count_digit(1);
-    let twos = layer.count_digit(2);
-    println!("{}", ones * twos);
-
-    let rasterized = image.rasterize();
-    println!("{}", rasterized);
+#[cfg(not(feature = "gif"))]
+fn write_gif(_: &Image, _: &str, _: usize, _: &Palette) {
+    panic!("Saving a GIF needs the gif feature: cargo run --features gif");
 }
@@ -0,0 +1,33 @@
+//! Day 9: Sensor Boost
+
+use aoc_utils::Puzzle;
+use intcode::Program;
+
+/// Run the BOOST program in a mode, and get its outputs
+///
+/// A working computer outputs a single value; a broken one outputs the opcodes that failed
+/// first.
+fn boost(program: &Program, mode: i64) -> String {
+    let outputs = intcode::run(program.as_slice(), &[mode]).unwrap();
+    let outputs: Vec<String> = outputs.iter().map(|output| output.to_string()).collect();
+    outputs.join(",")
+}
+
+/// The BOOST keycode, and the coordinates of the distress signal
+pub struct Day09;
+
+impl Puzzle for Day09 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        boost(program, 1)
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        boost(program, 2)
+    }
+}
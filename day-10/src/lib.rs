@@ -0,0 +1,181 @@
+//! Day 10: Monitoring Station
+
+use aoc_utils::math::gcd;
+use aoc_utils::Puzzle;
+use std::collections::BTreeMap;
+
+pub type Point = (i64, i64);
+
+fn parse(input: &str) -> Vec<Point> {
+    let mut asteroids = Vec::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.trim().chars().enumerate() {
+            if c == '#' {
+                asteroids.push((x as i64, y as i64));
+            }
+        }
+    }
+    asteroids
+}
+
+/// The direction from one asteroid to another, reduced so that every asteroid along the same
+/// line of sight has the same direction
+fn direction(from: Point, to: Point) -> Point {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let divisor = gcd(dx, dy);
+    (dx / divisor, dy / divisor)
+}
+
+/// The angle of a direction, clockwise from straight up, in the range [0, 2π)
+fn angle(direction: Point) -> f64 {
+    let angle = (direction.0 as f64).atan2(-direction.1 as f64);
+    if angle < 0.0 {
+        angle + 2.0 * std::f64::consts::PI
+    } else {
+        angle
+    }
+}
+
+/// The other asteroids, grouped by the direction they're in, closest first
+fn lines_of_sight(station: Point, asteroids: &[Point]) -> BTreeMap<Point, Vec<Point>> {
+    let mut lines: BTreeMap<Point, Vec<Point>> = BTreeMap::new();
+    for &asteroid in asteroids.iter().filter(|&&asteroid| asteroid != station) {
+        lines
+            .entry(direction(station, asteroid))
+            .or_default()
+            .push(asteroid);
+    }
+    for line in lines.values_mut() {
+        line.sort_by_key(|asteroid| {
+            (asteroid.0 - station.0).abs() + (asteroid.1 - station.1).abs()
+        });
+    }
+    lines
+}
+
+/// The asteroid that can see the most other asteroids, and how many it can see
+fn best_location(asteroids: &[Point]) -> Option<(Point, usize)> {
+    asteroids
+        .iter()
+        .map(|&station| (station, lines_of_sight(station, asteroids).len()))
+        .max_by_key(|&(_, visible)| visible)
+}
+
+/// The order the laser at `station` vaporizes every other asteroid
+fn vaporization_order(station: Point, asteroids: &[Point]) -> Vec<Point> {
+    let mut lines: Vec<(Point, Vec<Point>)> =
+        lines_of_sight(station, asteroids).into_iter().collect();
+    lines.sort_by(|(a, _), (b, _)| angle(*a).partial_cmp(&angle(*b)).unwrap());
+
+    let mut order = Vec::new();
+    let mut round = 0;
+    while order.len() < asteroids.len() - 1 {
+        for (_, line) in lines.iter() {
+            if let Some(&asteroid) = line.get(round) {
+                order.push(asteroid);
+            }
+        }
+        round += 1;
+    }
+    order
+}
+
+/// The best place for a monitoring station, and the 200th asteroid it vaporizes
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    type Input = Vec<Point>;
+
+    fn parse(input: &str) -> Self::Input {
+        parse(input)
+    }
+
+    fn part1(asteroids: &Self::Input) -> String {
+        let (_, visible) = best_location(asteroids).expect("There should be an asteroid");
+        visible.to_string()
+    }
+
+    fn part2(asteroids: &Self::Input) -> String {
+        let (station, _) = best_location(asteroids).expect("There should be an asteroid");
+        let order = vaporization_order(station, asteroids);
+        let (x, y) = order[199];
+        (x * 100 + y).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LARGE_EXAMPLE: &str = "\
+.#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##";
+
+    #[test]
+    fn test_best_location() {
+        let asteroids = parse(".#..#\n.....\n#####\n....#\n...##");
+        assert_eq!(best_location(&asteroids), Some(((3, 4), 8)));
+
+        let asteroids = parse(
+            "......#.#.\n#..#.#....\n..#######.\n.#.#.###..\n.#..#.....\n\
+             ..#....#.#\n#..#....#.\n.##.#..###\n##...#..#.\n.#....####",
+        );
+        assert_eq!(best_location(&asteroids), Some(((5, 8), 33)));
+
+        let asteroids = parse(LARGE_EXAMPLE);
+        assert_eq!(best_location(&asteroids), Some(((11, 13), 210)));
+    }
+
+    #[test]
+    fn test_vaporization_order() {
+        let asteroids = parse(
+            ".#....#####...#..\n##...##.#####..##\n##...#...#.#####.\n\
+             ..#.....#...###..\n..#.#.....#....##",
+        );
+        let order = vaporization_order((8, 3), &asteroids);
+        assert_eq!(
+            &order[..9],
+            &[
+                (8, 1),
+                (9, 0),
+                (9, 1),
+                (10, 0),
+                (9, 2),
+                (11, 1),
+                (12, 1),
+                (11, 2),
+                (15, 1)
+            ]
+        );
+
+        let asteroids = parse(LARGE_EXAMPLE);
+        let order = vaporization_order((11, 13), &asteroids);
+        assert_eq!(order[0], (11, 12));
+        assert_eq!(order[1], (12, 1));
+        assert_eq!(order[19], (16, 0));
+        assert_eq!(order[99], (10, 16));
+        assert_eq!(order[199], (8, 2));
+        assert_eq!(order[200], (10, 9));
+        assert_eq!(order[298], (11, 1));
+        assert_eq!(order.len(), 299);
+    }
+}
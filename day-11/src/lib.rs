@@ -0,0 +1,209 @@
+//! Day 11: Space Police
+
+use aoc_utils::ocr::recognize_points;
+use aoc_utils::{render_points, Grid, Puzzle};
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::collections::HashMap;
+
+const BLACK: i64 = 0;
+const WHITE: i64 = 1;
+
+/// Where the robot is, in the frames of an animation
+pub const ROBOT: i64 = 2;
+
+/// The part of the hull to draw, as its top left corner and its width and height
+type Bounds = ((i64, i64), usize, usize);
+
+/// The hull-painting robot, apart from the program that drives it
+struct Robot {
+    position: (i64, i64),
+    direction: (i64, i64),
+    panels: HashMap<(i64, i64), i64>,
+}
+
+impl Robot {
+    fn new(starting_color: i64) -> Self {
+        let mut panels = HashMap::new();
+        panels.insert((0, 0), starting_color);
+        Robot {
+            position: (0, 0),
+            direction: (0, -1),
+            panels,
+        }
+    }
+
+    /// The color of the panel the robot is over, which every panel starts as black
+    fn camera(&self) -> i64 {
+        self.panels.get(&self.position).copied().unwrap_or(BLACK)
+    }
+
+    /// Paint the current panel, turn left (0) or right (1), and move forward one panel
+    fn apply(&mut self, color: i64, turn: i64) {
+        self.panels.insert(self.position, color);
+        let (dx, dy) = self.direction;
+        self.direction = match turn {
+            0 => (dy, -dx),
+            1 => (-dy, dx),
+            turn => panic!("Unexpected turn {}", turn),
+        };
+        self.position = (
+            self.position.0 + self.direction.0,
+            self.position.1 + self.direction.1,
+        );
+    }
+
+    /// The number of panels painted at least once (the starting panel counts as painted)
+    fn painted(&self) -> usize {
+        self.panels.len()
+    }
+
+    fn white_panels(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.panels
+            .iter()
+            .filter(|&(_, &color)| color == WHITE)
+            .map(|(&position, _)| position)
+    }
+
+    /// The smallest part of the hull that holds every painted panel
+    fn bounds(&self) -> Bounds {
+        let min_x = self.panels.keys().map(|p| p.0).min().unwrap_or(0);
+        let max_x = self.panels.keys().map(|p| p.0).max().unwrap_or(-1);
+        let min_y = self.panels.keys().map(|p| p.1).min().unwrap_or(0);
+        let max_y = self.panels.keys().map(|p| p.1).max().unwrap_or(-1);
+        let width = (max_x - min_x + 1) as usize;
+        let height = (max_y - min_y + 1) as usize;
+        ((min_x, min_y), width, height)
+    }
+
+    /// The colors of part of the hull
+    fn hull_within(&self, ((min_x, min_y), width, height): Bounds) -> Grid<i64> {
+        let mut hull = Grid::new(width, height, BLACK);
+        for (&(x, y), &color) in &self.panels {
+            if let Some(panel) = hull.get_mut((x - min_x, y - min_y)) {
+                *panel = color;
+            }
+        }
+        hull
+    }
+
+    /// The smallest part of the hull that holds every painted panel, with `true` for white
+    fn hull(&self) -> Grid<bool> {
+        self.hull_within(self.bounds()).map(|&color| color == WHITE)
+    }
+}
+
+/// Run the program as the robot's brain until it halts
+fn paint(program: &Program, starting_color: i64) -> Result<Robot, IntcodeError> {
+    paint_watching(program, starting_color, |_| {})
+}
+
+/// Run the program as the robot's brain until it halts, showing `watch` the robot before it
+/// starts and after every move
+fn paint_watching(
+    program: &Program,
+    starting_color: i64,
+    mut watch: impl FnMut(&Robot),
+) -> Result<Robot, IntcodeError> {
+    let mut process = IntcodeProcess::new(program);
+    let mut robot = Robot::new(starting_color);
+    watch(&robot);
+    loop {
+        process.add_input(robot.camera());
+        match process.run_to_outputs(2) {
+            Ok(outputs) => robot.apply(outputs[0], outputs[1]),
+            Err(IntcodeError::CatchFire) => return Ok(robot),
+            Err(e) => return Err(e),
+        }
+        watch(&robot);
+    }
+}
+
+/// The hull once the robot has painted the registration identifier, starting on white
+pub fn registration_hull(program: &Program) -> Result<Grid<bool>, IntcodeError> {
+    Ok(paint(program, WHITE)?.hull())
+}
+
+/// The hull as the robot paints the registration identifier, after each move, for animating
+///
+/// Each frame is the colors of the panels, with the robot drawn as [`ROBOT`].
+pub fn registration_frames(program: &Program) -> Result<Vec<Grid<i64>>, IntcodeError> {
+    frames(program, WHITE)
+}
+
+fn frames(program: &Program, starting_color: i64) -> Result<Vec<Grid<i64>>, IntcodeError> {
+    let bounds = paint(program, starting_color)?.bounds();
+    let ((min_x, min_y), _, _) = bounds;
+    let mut frames = Vec::new();
+    paint_watching(program, starting_color, |robot| {
+        let mut frame = robot.hull_within(bounds);
+        let (x, y) = robot.position;
+        if let Some(panel) = frame.get_mut((x - min_x, y - min_y)) {
+            *panel = ROBOT;
+        }
+        frames.push(frame);
+    })?;
+    Ok(frames)
+}
+
+/// The panels the robot paints, and the registration identifier it paints when started on white
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        paint(program, BLACK).unwrap().painted().to_string()
+    }
+
+    /// The registration identifier, or the picture of it if it can't be read
+    fn part2(program: &Self::Input) -> String {
+        let robot = paint(program, WHITE).unwrap();
+        recognize_points(robot.white_panels())
+            .unwrap_or_else(|| render_points(robot.white_panels()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_robot() {
+        let mut robot = Robot::new(BLACK);
+        let instructions = [(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)];
+        for &(color, turn) in instructions.iter() {
+            robot.apply(color, turn);
+        }
+
+        assert_eq!(robot.painted(), 6);
+        assert_eq!(robot.position, (0, -1));
+        assert_eq!(robot.direction, (-1, 0));
+        assert_eq!(robot.camera(), BLACK);
+        assert_eq!(render_points(robot.white_panels()), "  #\n  #\n## \n");
+        let hull = robot.hull().map(|&white| if white { '#' } else { '.' });
+        assert_eq!(hull.to_string(), "..#\n..#\n##.\n");
+    }
+
+    #[test]
+    fn test_paint() {
+        // Paint white and turn right until the camera sees white, then halt
+        let program = Program::new(vec![
+            3, 100, 1005, 100, 13, 104, 1, 104, 1, 1105, 1, 0, 0, 99,
+        ]);
+        let robot = paint(&program, BLACK).unwrap();
+        assert_eq!(robot.painted(), 4);
+        assert_eq!(robot.white_panels().count(), 4);
+
+        // It goes around a square, so it's back where it started at the end
+        let frames = frames(&program, BLACK).unwrap();
+        let drawn: Vec<String> = frames.iter().map(Grid::to_string).collect();
+        assert_eq!(
+            drawn,
+            vec!["20\n00\n", "12\n00\n", "11\n02\n", "11\n21\n", "21\n11\n"]
+        );
+    }
+}
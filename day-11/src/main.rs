@@ -0,0 +1,58 @@
+use aoc_utils::ansi::render_blocks;
+use aoc_utils::image::{parse_palette, Rgb};
+use aoc_utils::puzzle::{print_answers, read_stdin};
+use aoc_utils::Puzzle;
+use day_11::{registration_hull, Day11};
+use intcode::Program;
+use std::env;
+
+const USAGE: &str = "Usage: day-11 [--blocks] [--gif <file>] [--scale <n>] \
+                     [--palette <black>,<white>[,<robot>]] < input";
+
+fn main() {
+    let mut blocks = false;
+    let mut gif = None;
+    let mut scale = 4;
+    let mut palette = vec![[0, 0, 0], [255, 255, 255], [255, 64, 64]];
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--blocks" => blocks = true,
+            "--gif" => gif = Some(args.next().expect(USAGE)),
+            "--scale" => scale = args.next().expect(USAGE).parse().expect(USAGE),
+            "--palette" => {
+                let colors = parse_palette(&args.next().expect(USAGE)).unwrap();
+                assert!(matches!(colors.len(), 2 | 3), "{}", USAGE);
+                palette.splice(..colors.len(), colors);
+            }
+            _ => panic!("{}", USAGE),
+        }
+    }
+
+    let program = Day11::parse(&read_stdin());
+    print_answers::<Day11>(&program);
+    if blocks {
+        let hull = registration_hull(&program).unwrap();
+        print!("{}", render_blocks(&hull, |&white| palette[white as usize]));
+    }
+    if let Some(path) = gif {
+        write_gif(&program, &path, scale, &palette);
+    }
+}
+
+/// Save an animation of the robot painting the registration identifier
+#[cfg(feature = "gif")]
+fn write_gif(program: &Program, path: &str, scale: usize, palette: &[Rgb]) {
+    use std::fs::File;
+    use std::io::BufWriter;
+
+    let frames = day_11::registration_frames(program).unwrap();
+    let out = BufWriter::new(File::create(path).unwrap());
+    aoc_utils::image::write_gif(out, &frames, scale, 5, |&color| palette[color as usize]).unwrap();
+}
+
+#[cfg(not(feature = "gif"))]
+fn write_gif(_: &Program, _: &str, _: usize, _: &[Rgb]) {
+    panic!("Saving a GIF needs the gif feature: cargo run --features gif");
+}
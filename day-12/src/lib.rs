@@ -0,0 +1,180 @@
+//! Day 12: The N-Body Problem
+
+use aoc_utils::geometry::energy;
+use aoc_utils::math::lcm;
+use aoc_utils::{find_cycle, Puzzle, Vec3};
+use std::str::FromStr;
+
+/// A moon, and how it's moving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Moon {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+impl FromStr for Moon {
+    type Err = String;
+
+    /// Parse a moon's position, like `<x=-1, y=0, z=2>`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let inner = input
+            .trim()
+            .strip_prefix('<')
+            .and_then(|input| input.strip_suffix('>'))
+            .ok_or_else(|| format!("Expected <x=.., y=.., z=..>, got {}", input))?;
+
+        let mut position = Vec3::default();
+        let mut parts = inner.split(',');
+        for (axis, name) in ["x", "y", "z"].iter().enumerate() {
+            let part = parts
+                .next()
+                .ok_or_else(|| format!("Missing {} in {}", name, input))?;
+            let mut assignment = part.trim().splitn(2, '=');
+            if assignment.next() != Some(name) {
+                return Err(format!("Expected {} in {}", name, input));
+            }
+            position[axis] = assignment
+                .next()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| format!("Invalid {} in {}", name, input))?;
+        }
+        if parts.next().is_some() {
+            return Err(format!("Too many coordinates in {}", input));
+        }
+
+        Ok(Moon {
+            position,
+            velocity: Vec3::default(),
+        })
+    }
+}
+
+impl Moon {
+    fn energy(&self) -> i64 {
+        energy(self.position, self.velocity)
+    }
+}
+
+/// Apply gravity along one axis, then move along it
+fn step_axis(moons: &mut [Moon], axis: usize) {
+    for i in 0..moons.len() {
+        for j in 0..moons.len() {
+            let pull = (moons[j].position[axis] - moons[i].position[axis]).signum();
+            moons[i].velocity[axis] += pull;
+        }
+    }
+    for moon in moons.iter_mut() {
+        moon.position[axis] += moon.velocity[axis];
+    }
+}
+
+fn step(moons: &mut [Moon]) {
+    let pulls: Vec<Vec3> = moons
+        .iter()
+        .map(|moon| {
+            moons
+                .iter()
+                .map(|other| moon.position.gravity_towards(other.position))
+                .sum()
+        })
+        .collect();
+    for (moon, pull) in moons.iter_mut().zip(pulls) {
+        moon.velocity += pull;
+        moon.position += moon.velocity;
+    }
+}
+
+fn total_energy(moons: &[Moon], steps: usize) -> i64 {
+    let mut moons = moons.to_vec();
+    for _ in 0..steps {
+        step(&mut moons);
+    }
+    moons.iter().map(Moon::energy).sum()
+}
+
+/// The number of steps until one axis repeats
+///
+/// Each step can be run backwards, so the cycle always goes back to the first state.
+fn axis_period(moons: &[Moon], axis: usize) -> i64 {
+    let cycle = find_cycle(moons.to_vec(), |moons| {
+        let mut moons = moons.clone();
+        step_axis(&mut moons, axis);
+        moons
+    });
+    cycle.length as i64
+}
+
+/// The number of steps until every moon is back where it started, by combining the axes'
+/// periods, since the axes move independently
+fn period(moons: &[Moon]) -> i64 {
+    (0..3).map(|axis| axis_period(moons, axis)).fold(1, lcm)
+}
+
+/// The energy in the system after 1000 steps, and how long until it repeats
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Input = Vec<Moon>;
+
+    fn parse(input: &str) -> Self::Input {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    fn part1(moons: &Self::Input) -> String {
+        total_energy(moons, 1000).to_string()
+    }
+
+    fn part2(moons: &Self::Input) -> String {
+        period(moons).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(moons: &[&str]) -> Vec<Moon> {
+        moons.iter().map(|moon| moon.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "<x=-1, y=0, z=2>".parse::<Moon>(),
+            Ok(Moon {
+                position: Vec3::new(-1, 0, 2),
+                velocity: Vec3::default(),
+            })
+        );
+        assert!("<x=-1, y=0>".parse::<Moon>().is_err());
+        assert!("<x=-1, z=0, y=2>".parse::<Moon>().is_err());
+    }
+
+    #[test]
+    fn test_first_example() {
+        let moons = parse(&[
+            "<x=-1, y=0, z=2>",
+            "<x=2, y=-10, z=-7>",
+            "<x=4, y=-8, z=8>",
+            "<x=3, y=5, z=-1>",
+        ]);
+        assert_eq!(total_energy(&moons, 10), 179);
+        assert_eq!(period(&moons), 2772);
+    }
+
+    #[test]
+    fn test_second_example() {
+        let moons = parse(&[
+            "<x=-8, y=-10, z=0>",
+            "<x=5, y=5, z=10>",
+            "<x=2, y=-7, z=3>",
+            "<x=9, y=-8, z=-3>",
+        ]);
+        assert_eq!(total_energy(&moons, 100), 1940);
+        assert_eq!(period(&moons), 4_686_774_924);
+    }
+}
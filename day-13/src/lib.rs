@@ -0,0 +1,390 @@
+//! The arcade cabinet, driven by its intcode program
+//!
+//! The program draws the screen with triples of outputs: `x, y, tile`, or `-1, 0, score` to
+//! show the score. It asks for input whenever it wants to know which way the joystick is
+//! tilted. A [`Game`] keeps track of the screen and score as the program runs, and stops
+//! whenever the program wants input, so it can be played by [`Game::autoplay`] or by hand.
+//! Games can be saved and loaded part way through, with the intcode snapshot format.
+
+use aoc_utils::{Grid, Puzzle};
+use intcode::memory::CopyOnWriteMemory;
+use intcode::snapshot::SnapshotError;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+
+/// What's drawn at a position on the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(Tile::Empty),
+            1 => Some(Tile::Wall),
+            2 => Some(Tile::Block),
+            3 => Some(Tile::Paddle),
+            4 => Some(Tile::Ball),
+            _ => None,
+        }
+    }
+
+    /// The character the tile is drawn as
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Empty => ' ',
+            Tile::Wall => '#',
+            Tile::Block => '=',
+            Tile::Paddle => '-',
+            Tile::Ball => 'o',
+        }
+    }
+}
+
+/// Which way the joystick is tilted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Joystick {
+    Left,
+    Neutral,
+    Right,
+}
+
+impl Joystick {
+    fn to_input(self) -> i64 {
+        match self {
+            Joystick::Left => -1,
+            Joystick::Neutral => 0,
+            Joystick::Right => 1,
+        }
+    }
+}
+
+/// Why the game stopped running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The game wants to know which way the joystick is tilted
+    WaitingForJoystick,
+    /// The program halted
+    GameOver,
+}
+
+/// An error from running the game
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    /// The program failed
+    Intcode(IntcodeError),
+    /// The program drew a tile that doesn't exist
+    UnknownTile(i64),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Intcode(error) => write!(f, "the program failed: {:?}", error),
+            GameError::UnknownTile(id) => write!(f, "the program drew an unknown tile {}", id),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<IntcodeError> for GameError {
+    fn from(error: IntcodeError) -> Self {
+        GameError::Intcode(error)
+    }
+}
+
+/// The arcade cabinet
+pub struct Game {
+    process: IntcodeProcess<i64, CopyOnWriteMemory<i64>>,
+    /// Outputs that aren't a full triple yet
+    pending: Vec<i64>,
+    screen: HashMap<(i64, i64), Tile>,
+    score: i64,
+    ball: Option<(i64, i64)>,
+    paddle: Option<(i64, i64)>,
+}
+
+impl Game {
+    /// Load the game, without starting it
+    pub fn new(program: &Program) -> Self {
+        Game {
+            process: IntcodeProcess::new(program),
+            pending: Vec::with_capacity(3),
+            screen: HashMap::new(),
+            score: 0,
+            ball: None,
+            paddle: None,
+        }
+    }
+
+    /// Load a game saved with [`Game::save`]
+    pub fn load(reader: impl Read) -> Result<Self, SnapshotError> {
+        let process = IntcodeProcess::load_snapshot(reader)?;
+        let mut game = Game {
+            process,
+            pending: Vec::with_capacity(3),
+            screen: HashMap::new(),
+            score: 0,
+            ball: None,
+            paddle: None,
+        };
+
+        // The process keeps every output it has made, so the screen can be drawn again from
+        // them. They were all checked when they were first drawn, so drawing can't fail.
+        let outputs = game.process.outputs().to_vec();
+        for output in outputs {
+            game.pending.push(output);
+            if game.pending.len() == 3 {
+                game.draw().unwrap();
+            }
+        }
+        Ok(game)
+    }
+
+    /// Save the game, to continue later with [`Game::load`]
+    pub fn save(&self, writer: impl Write) -> Result<(), SnapshotError> {
+        self.process.save_snapshot(writer)
+    }
+
+    /// Insert two quarters, to play for free
+    pub fn insert_quarters(&mut self) {
+        self.process.store(0, 2).unwrap();
+    }
+
+    /// Run the game until it wants the joystick, or is over
+    pub fn run(&mut self) -> Result<Status, GameError> {
+        loop {
+            match self.process.step() {
+                Ok(Some(output)) => {
+                    self.pending.push(output);
+                    if self.pending.len() == 3 {
+                        self.draw()?;
+                    }
+                }
+                Ok(None) => {}
+                Err(IntcodeError::NoInputAvailable) => return Ok(Status::WaitingForJoystick),
+                Err(IntcodeError::CatchFire) => return Ok(Status::GameOver),
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Handle a full triple of outputs
+    fn draw(&mut self) -> Result<(), GameError> {
+        let (x, y, value) = (self.pending[0], self.pending[1], self.pending[2]);
+        self.pending.clear();
+
+        if (x, y) == (-1, 0) {
+            self.score = value;
+            return Ok(());
+        }
+
+        let tile = Tile::from_id(value).ok_or(GameError::UnknownTile(value))?;
+        match tile {
+            Tile::Ball => self.ball = Some((x, y)),
+            Tile::Paddle => self.paddle = Some((x, y)),
+            _ => {}
+        }
+        self.screen.insert((x, y), tile);
+        Ok(())
+    }
+
+    /// Tilt the joystick for the next time the game asks
+    pub fn joystick(&mut self, joystick: Joystick) {
+        self.process.add_input(joystick.to_input());
+    }
+
+    /// Play until the game is over by keeping the paddle under the ball, and get the score
+    pub fn autoplay(&mut self) -> Result<i64, GameError> {
+        self.autoplay_watching(|_| {})
+    }
+
+    /// Play like [`Game::autoplay`], showing `watch` the game every time it waits for the
+    /// joystick, and once more when it's over
+    pub fn autoplay_watching(&mut self, mut watch: impl FnMut(&Game)) -> Result<i64, GameError> {
+        loop {
+            let status = self.run()?;
+            watch(self);
+            if status == Status::GameOver {
+                break;
+            }
+            let joystick = match (self.ball, self.paddle) {
+                (Some(ball), Some(paddle)) if ball.0 < paddle.0 => Joystick::Left,
+                (Some(ball), Some(paddle)) if ball.0 > paddle.0 => Joystick::Right,
+                _ => Joystick::Neutral,
+            };
+            self.joystick(joystick);
+        }
+        Ok(self.score)
+    }
+
+    /// The number of block tiles on the screen
+    pub fn blocks(&self) -> usize {
+        self.screen
+            .values()
+            .filter(|&&tile| tile == Tile::Block)
+            .count()
+    }
+
+    /// The score shown on the screen
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// The tile drawn at a position, if any
+    pub fn tile(&self, x: i64, y: i64) -> Tile {
+        self.screen.get(&(x, y)).copied().unwrap_or(Tile::Empty)
+    }
+
+    /// The width and height of the screen, from the origin to the furthest tile drawn
+    pub fn size(&self) -> (i64, i64) {
+        self.screen.keys().fold((0, 0), |(width, height), &(x, y)| {
+            (width.max(x + 1), height.max(y + 1))
+        })
+    }
+
+    /// The tiles on the screen, from the origin to the furthest tile drawn
+    pub fn grid(&self) -> Grid<Tile> {
+        let (width, height) = self.size();
+        let mut grid = Grid::new(width as usize, height as usize, Tile::Empty);
+        for (&position, &tile) in &self.screen {
+            grid[position] = tile;
+        }
+        grid
+    }
+}
+
+impl fmt::Display for Game {
+    /// Draw the screen, with the score underneath
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.size();
+        for y in 0..height {
+            let row: String = (0..width).map(|x| self.tile(x, y).to_char()).collect();
+            writeln!(f, "{}", row)?;
+        }
+        write!(f, "score: {}", self.score)
+    }
+}
+
+/// The blocks on the screen when the game starts, and the score after breaking them all
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        let mut game = Game::new(program);
+        game.run().unwrap();
+        game.blocks().to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        let mut game = Game::new(program);
+        game.insert_quarters();
+        game.autoplay().unwrap().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use intcode::asm::assemble;
+
+    /// A game with a block in the corner, a ball at x=3, and a paddle that starts at x=0. The
+    /// player wins 100 points once the paddle is under the ball.
+    fn test_game() -> Game {
+        let program = assemble(
+            "
+                out 0
+                out 0
+                out 2
+            loop:
+                out [paddle]
+                out 2
+                out 3
+                out 3
+                out 1
+                out 4
+                eq [paddle], 3, [done]
+                jnz [done], finish
+                in [joystick]
+                out [paddle]
+                out 2
+                out 0
+                add [paddle], [joystick], [paddle]
+                jz 0, loop
+            finish:
+                out -1
+                out 0
+                out 100
+                halt
+            paddle: .data 0
+            done: .data 0
+            joystick: .data 0
+            ",
+        )
+        .unwrap();
+        Game::new(&Program::new(program))
+    }
+
+    #[test]
+    fn test_screen() {
+        let mut game = test_game();
+        assert_eq!(game.run(), Ok(Status::WaitingForJoystick));
+        assert_eq!(game.blocks(), 1);
+        assert_eq!(game.tile(3, 1), Tile::Ball);
+        assert_eq!(game.to_string(), "=   \n   o\n-   \nscore: 0");
+        assert_eq!(
+            game.grid().map(|tile| tile.to_char()).to_string(),
+            "=   \n   o\n-   \n"
+        );
+
+        game.joystick(Joystick::Right);
+        assert_eq!(game.run(), Ok(Status::WaitingForJoystick));
+        assert_eq!(game.to_string(), "=   \n   o\n -  \nscore: 0");
+    }
+
+    #[test]
+    fn test_autoplay() {
+        let mut game = test_game();
+        game.insert_quarters();
+        assert_eq!(game.process.load(0), Ok(2));
+
+        let mut game = test_game();
+        assert_eq!(game.autoplay(), Ok(100));
+        assert_eq!(game.score(), 100);
+
+        let mut game = test_game();
+        let mut paddles = Vec::new();
+        let score = game.autoplay_watching(|game| paddles.push(game.paddle.unwrap().0));
+        assert_eq!(score, Ok(100));
+        assert_eq!(paddles, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut game = test_game();
+        game.run().unwrap();
+        game.joystick(Joystick::Right);
+        game.run().unwrap();
+
+        let mut saved = Vec::new();
+        game.save(&mut saved).unwrap();
+        let mut loaded = Game::load(&saved[..]).unwrap();
+        assert_eq!(loaded.to_string(), game.to_string());
+        assert_eq!(loaded.paddle, Some((1, 2)));
+        assert_eq!(loaded.autoplay(), Ok(100));
+    }
+}
@@ -0,0 +1,96 @@
+mod play;
+
+use aoc_utils::ansi::render_blocks;
+use aoc_utils::image::{parse_palette, Rgb};
+use day_13::{Day13, Game};
+use intcode::Program;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: day-13 < input, day-13 --play <input> [--save <file>], \
+                     day-13 --blocks <input> [--palette <colors>], or \
+                     day-13 --gif <input> <file> [--palette <colors>], \
+                     where the colors are <empty>,<wall>,<block>,<paddle>,<ball>";
+
+/// The colors of the empty space, walls, blocks, paddle, and ball
+const PALETTE: [Rgb; 5] = [
+    [0, 0, 0],
+    [128, 128, 128],
+    [64, 160, 255],
+    [255, 255, 255],
+    [255, 64, 64],
+];
+
+/// Parse `--palette <colors>`, if it's there
+fn palette(args: &mut impl Iterator<Item = String>) -> Vec<Rgb> {
+    let palette = match (args.next().as_deref(), args.next()) {
+        (None, None) => PALETTE.to_vec(),
+        (Some("--palette"), Some(palette)) => parse_palette(&palette).unwrap(),
+        _ => panic!("{}", USAGE),
+    };
+    assert_eq!(palette.len(), PALETTE.len(), "{}", USAGE);
+    palette
+}
+
+fn load(input: &str) -> Game {
+    let program: Program = fs::read_to_string(input).unwrap().parse().unwrap();
+    let mut game = Game::new(&program);
+    game.insert_quarters();
+    game
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        None => aoc_utils::puzzle::run::<Day13>(),
+        Some("--play") => {
+            let input = args.next().expect(USAGE);
+            let save_path = match (args.next().as_deref(), args.next()) {
+                (None, None) => PathBuf::from("day-13.snapshot"),
+                (Some("--save"), Some(path)) => PathBuf::from(path),
+                _ => panic!("{}", USAGE),
+            };
+
+            play::play(load(&input), &save_path).unwrap();
+        }
+        Some("--blocks") => {
+            let input = args.next().expect(USAGE);
+            let palette = palette(&mut args);
+
+            // Draw the screen as it is when the game first asks for the joystick
+            let mut game = load(&input);
+            game.run().unwrap();
+            print!(
+                "{}",
+                render_blocks(&game.grid(), |&tile| palette[tile as usize])
+            );
+            println!("score: {}", game.score());
+        }
+        Some("--gif") => {
+            let input = args.next().expect(USAGE);
+            let path = args.next().expect(USAGE);
+            let palette = palette(&mut args);
+            write_gif(load(&input), &path, &palette);
+        }
+        Some(_) => panic!("{}", USAGE),
+    }
+}
+
+/// Save an animation of the game playing itself, with a frame every time it moves
+#[cfg(feature = "gif")]
+fn write_gif(mut game: Game, path: &str, palette: &[Rgb]) {
+    use std::fs::File;
+    use std::io::BufWriter;
+
+    let mut frames = Vec::new();
+    game.autoplay_watching(|game| frames.push(game.grid()))
+        .unwrap();
+    let out = BufWriter::new(File::create(path).unwrap());
+    aoc_utils::image::write_gif(out, &frames, 4, 2, |&tile| palette[tile as usize]).unwrap();
+}
+
+#[cfg(not(feature = "gif"))]
+fn write_gif(_: Game, _: &str, _: &[Rgb]) {
+    panic!("Saving a GIF needs the gif feature: cargo run --features gif");
+}
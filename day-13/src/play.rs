@@ -0,0 +1,138 @@
+//! Play the game by hand, in the terminal
+//!
+//! The game moves on every tick, whether or not a key was pressed, with the joystick tilted
+//! the way of the last arrow key pressed during the tick. The game can be saved to a file and
+//! loaded again at any time.
+
+use day_13::{Game, Joystick, Status};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long the game waits for the joystick
+const TICK: Duration = Duration::from_millis(150);
+
+const HELP: &str = " ← → move · s save · l load · q quit ";
+
+struct Play<'a> {
+    game: Game,
+    status: Status,
+    save_path: &'a Path,
+    /// What happened to the last save or load, shown under the screen
+    message: String,
+}
+
+/// Play the game until the player quits
+pub fn play(game: Game, save_path: &Path) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run(
+        &mut terminal,
+        Play {
+            game,
+            status: Status::WaitingForJoystick,
+            save_path,
+            message: String::new(),
+        },
+    );
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, mut play: Play) -> io::Result<()> {
+    play.advance(None);
+    loop {
+        terminal.draw(|frame| draw(frame, &play))?;
+
+        let deadline = Instant::now() + TICK;
+        let mut joystick = None;
+        while event::poll(deadline.saturating_duration_since(Instant::now()))? {
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+                _ => continue,
+            };
+            match key {
+                KeyCode::Left => joystick = Some(Joystick::Left),
+                KeyCode::Right => joystick = Some(Joystick::Right),
+                KeyCode::Char('s') => play.save(),
+                KeyCode::Char('l') => play.load(),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            }
+        }
+        play.advance(Some(joystick.unwrap_or(Joystick::Neutral)));
+    }
+}
+
+impl Play<'_> {
+    /// Tilt the joystick, if the game is waiting for it, and run until it waits again
+    fn advance(&mut self, joystick: Option<Joystick>) {
+        if self.status != Status::WaitingForJoystick {
+            return;
+        }
+        if let Some(joystick) = joystick {
+            self.game.joystick(joystick);
+        }
+        match self.game.run() {
+            Ok(status) => self.status = status,
+            Err(error) => {
+                self.status = Status::GameOver;
+                self.message = error.to_string();
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        let result = File::create(self.save_path)
+            .map_err(Into::into)
+            .and_then(|file| self.game.save(BufWriter::new(file)));
+        self.message = match result {
+            Ok(()) => format!("saved to {}", self.save_path.display()),
+            Err(error) => format!("couldn't save: {:?}", error),
+        };
+    }
+
+    fn load(&mut self) {
+        let result = File::open(self.save_path)
+            .map_err(Into::into)
+            .and_then(|file| Game::load(BufReader::new(file)));
+        match result {
+            Ok(game) => {
+                self.game = game;
+                self.status = Status::WaitingForJoystick;
+                self.message = format!("loaded {}", self.save_path.display());
+            }
+            Err(error) => self.message = format!("couldn't load: {:?}", error),
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, play: &Play) {
+    let (width, height) = play.game.size();
+    let [screen, message] =
+        Layout::vertical([Constraint::Length(height as u16 + 2), Constraint::Length(1)])
+            .areas(frame.area());
+
+    let lines: Vec<Line> = (0..height)
+        .map(|y| {
+            let row: String = (0..width).map(|x| play.game.tile(x, y).to_char()).collect();
+            Line::from(row)
+        })
+        .collect();
+    let title = match play.status {
+        Status::WaitingForJoystick => format!(" Score: {} ", play.game.score()),
+        Status::GameOver => format!(" Game over! Score: {} ", play.game.score()),
+    };
+    let block = Block::bordered().title(title).title_bottom(HELP);
+    let screen = screen.intersection(Rect {
+        width: width as u16 + 2,
+        ..screen
+    });
+    frame.render_widget(Paragraph::new(lines).block(block), screen);
+    frame.render_widget(Line::from(play.message.as_str()), message);
+}
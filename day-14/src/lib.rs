@@ -0,0 +1,287 @@
+//! Day 14: Space Stoichiometry
+
+use aoc_utils::Puzzle;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const TRILLION: u64 = 1_000_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Quantity {
+    amount: u64,
+    chemical: String,
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+
+    /// Parse a quantity like `7 A`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split_whitespace();
+        let amount = parts
+            .next()
+            .and_then(|amount| amount.parse().ok())
+            .ok_or_else(|| format!("Invalid amount in {:?}", input))?;
+        let chemical = parts
+            .next()
+            .ok_or_else(|| format!("Missing chemical in {:?}", input))?
+            .to_string();
+        if parts.next().is_some() {
+            return Err(format!("Unexpected text after {:?}", input));
+        }
+        Ok(Quantity { amount, chemical })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reaction {
+    inputs: Vec<Quantity>,
+    output: Quantity,
+}
+
+impl FromStr for Reaction {
+    type Err = String;
+
+    /// Parse a reaction like `7 A, 1 B => 1 C`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut sides = input.split("=>");
+        let inputs = sides
+            .next()
+            .ok_or_else(|| format!("Missing inputs in {:?}", input))?
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        let output = sides
+            .next()
+            .ok_or_else(|| format!("Missing output in {:?}", input))?
+            .parse()?;
+        if sides.next().is_some() {
+            return Err(format!("Too many arrows in {:?}", input));
+        }
+        Ok(Reaction { inputs, output })
+    }
+}
+
+/// Every reaction, by the chemical it produces
+pub struct Nanofactory {
+    reactions: HashMap<String, Reaction>,
+    /// Every chemical, each one before all of the chemicals used to make it
+    order: Vec<String>,
+}
+
+impl FromStr for Nanofactory {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut reactions = HashMap::new();
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let reaction: Reaction = line.parse()?;
+            reactions.insert(reaction.output.chemical.clone(), reaction);
+        }
+
+        let mut order = Vec::new();
+        let mut visited = HashMap::new();
+        visit("FUEL", &reactions, &mut visited, &mut order)?;
+        order.reverse();
+
+        Ok(Nanofactory { reactions, order })
+    }
+}
+
+/// Add a chemical to the order after everything it's made from, by depth-first search
+fn visit(
+    chemical: &str,
+    reactions: &HashMap<String, Reaction>,
+    visited: &mut HashMap<String, bool>,
+    order: &mut Vec<String>,
+) -> Result<(), String> {
+    match visited.get(chemical) {
+        Some(true) => return Ok(()),
+        Some(false) => return Err(format!("{} is made from itself", chemical)),
+        None => {}
+    }
+
+    visited.insert(chemical.to_string(), false);
+    if let Some(reaction) = reactions.get(chemical) {
+        for input in &reaction.inputs {
+            visit(&input.chemical, reactions, visited, order)?;
+        }
+    } else if chemical != "ORE" {
+        return Err(format!("Nothing makes {}", chemical));
+    }
+    visited.insert(chemical.to_string(), true);
+    order.push(chemical.to_string());
+    Ok(())
+}
+
+impl Nanofactory {
+    /// The ore needed to make the given amount of fuel
+    ///
+    /// Chemicals are handled in topological order, so by the time a chemical is made, everything
+    /// that uses it has added to what's needed. Each reaction then runs just enough times, and
+    /// whatever it makes beyond that is left over.
+    fn ore_for_fuel(&self, fuel: u64) -> u64 {
+        let mut needed: HashMap<&str, u64> = HashMap::new();
+        needed.insert("FUEL", fuel);
+
+        for chemical in &self.order {
+            let reaction = match self.reactions.get(chemical) {
+                Some(reaction) => reaction,
+                None => continue,
+            };
+            let amount = needed.get(chemical.as_str()).copied().unwrap_or(0);
+            let times = amount.div_ceil(reaction.output.amount);
+            for input in &reaction.inputs {
+                *needed.entry(&input.chemical).or_insert(0) += input.amount * times;
+            }
+        }
+
+        needed.get("ORE").copied().unwrap_or(0)
+    }
+
+    /// The most fuel that can be made from the given amount of ore, by binary search
+    fn max_fuel(&self, ore: u64) -> u64 {
+        let mut low = 0;
+        let mut high = 1;
+        while self.ore_for_fuel(high) <= ore {
+            low = high;
+            high *= 2;
+        }
+        // The answer is at least `low` and less than `high`
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if self.ore_for_fuel(middle) <= ore {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+}
+
+/// The ore for one fuel, and the fuel a trillion ore makes
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    type Input = Nanofactory;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(factory: &Self::Input) -> String {
+        factory.ore_for_fuel(1).to_string()
+    }
+
+    fn part2(factory: &Self::Input) -> String {
+        factory.max_fuel(TRILLION).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLES: [&str; 5] = [
+        "\
+10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL",
+        "\
+9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL",
+        "\
+157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT",
+        "\
+2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG
+17 NVRVD, 3 JNWZP => 8 VPVL
+53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL
+22 VJHF, 37 MNCFX => 5 FWMGM
+139 ORE => 4 NVRVD
+144 ORE => 7 JNWZP
+5 MNCFX, 7 RFSQX, 2 FWMGM, 2 VPVL, 19 CXFTF => 3 HVMC
+5 VJHF, 7 MNCFX, 9 VPVL, 37 CXFTF => 6 GNMV
+145 ORE => 6 MNCFX
+1 NVRVD => 8 CXFTF
+1 VJHF, 6 MNCFX => 4 RFSQX
+176 ORE => 6 VJHF",
+        "\
+171 ORE => 8 CNZTR
+7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL
+114 ORE => 4 BHXH
+14 VRPVC => 6 BMBT
+6 BHXH, 18 KTJDG, 12 WPTQ, 7 PLWSL, 31 FHTLT, 37 ZDVW => 1 FUEL
+6 WPTQ, 2 BMBT, 8 ZLQW, 18 KTJDG, 1 XMNCP, 6 MZWV, 1 RJRHP => 6 FHTLT
+15 XDBXC, 2 LTCX, 1 VRPVC => 6 ZLQW
+13 WPTQ, 10 LTCX, 3 RJRHP, 14 XMNCP, 2 MZWV, 1 ZLQW => 1 ZDVW
+5 BMBT => 4 WPTQ
+189 ORE => 9 KTJDG
+1 MZWV, 17 XDBXC, 3 XCVML => 2 XMNCP
+12 VRPVC, 27 CNZTR => 2 XDBXC
+15 KTJDG, 12 BHXH => 5 XCVML
+3 BHXH, 2 VRPVC => 7 MZWV
+121 ORE => 7 VRPVC
+7 XCVML => 6 RJRHP
+5 BHXH, 4 VRPVC => 5 LTCX",
+    ];
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "7 A, 1 B => 1 C".parse::<Reaction>(),
+            Ok(Reaction {
+                inputs: vec![
+                    Quantity {
+                        amount: 7,
+                        chemical: "A".into()
+                    },
+                    Quantity {
+                        amount: 1,
+                        chemical: "B".into()
+                    },
+                ],
+                output: Quantity {
+                    amount: 1,
+                    chemical: "C".into()
+                },
+            })
+        );
+        assert!("1 A => 1 B".parse::<Nanofactory>().is_err());
+        assert!("1 FUEL => 1 FUEL".parse::<Nanofactory>().is_err());
+    }
+
+    #[test]
+    fn test_ore_for_fuel() {
+        let ore: Vec<u64> = EXAMPLES
+            .iter()
+            .map(|example| example.parse::<Nanofactory>().unwrap().ore_for_fuel(1))
+            .collect();
+        assert_eq!(ore, vec![31, 165, 13312, 180697, 2210736]);
+    }
+
+    #[test]
+    fn test_max_fuel() {
+        let fuel: Vec<u64> = EXAMPLES[2..]
+            .iter()
+            .map(|example| example.parse::<Nanofactory>().unwrap().max_fuel(TRILLION))
+            .collect();
+        assert_eq!(fuel, vec![82892753, 5586022, 460664]);
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_utils::puzzle::run::<day_14::Day14>();
+}
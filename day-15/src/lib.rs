@@ -0,0 +1,219 @@
+//! Day 15: Oxygen System
+
+use aoc_utils::pathfinding::{bfs, distances};
+use aoc_utils::Puzzle;
+use intcode::memory::CopyOnWriteMemory;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+type Point = (i64, i64);
+
+/// The movement commands, in the order the droid numbers them, starting from 1
+const DIRECTIONS: [Point; 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+/// Something that can be told to move, and says what it ran into
+trait Droid: Clone {
+    /// Try to move in one of the `DIRECTIONS`, by index
+    fn try_move(&mut self, direction: usize) -> Result<Cell, IntcodeError>;
+}
+
+/// The repair droid, driven by its intcode program
+#[derive(Clone)]
+struct IntcodeDroid(IntcodeProcess<i64, CopyOnWriteMemory<i64>>);
+
+impl Droid for IntcodeDroid {
+    fn try_move(&mut self, direction: usize) -> Result<Cell, IntcodeError> {
+        self.0.add_input(direction as i64 + 1);
+        match self.0.run_to_output()? {
+            0 => Ok(Cell::Wall),
+            1 => Ok(Cell::Open),
+            2 => Ok(Cell::Oxygen),
+            status => panic!("Unexpected status {}", status),
+        }
+    }
+}
+
+/// Everything the droid found
+pub struct Map {
+    cells: HashMap<Point, Cell>,
+}
+
+/// Explore the whole area by breadth-first search
+///
+/// Instead of walking the droid back and forth, every cell it reaches keeps its own copy of
+/// the droid that's standing there, which goes on to try each direction.
+fn explore(droid: impl Droid) -> Result<Map, IntcodeError> {
+    let mut cells = HashMap::new();
+    let mut queue = VecDeque::new();
+    cells.insert((0, 0), Cell::Open);
+    queue.push_back(((0, 0), droid));
+
+    while let Some((position, droid)) = queue.pop_front() {
+        for (direction, (dx, dy)) in DIRECTIONS.iter().enumerate() {
+            let next = (position.0 + dx, position.1 + dy);
+            if cells.contains_key(&next) {
+                continue;
+            }
+
+            let mut droid = droid.clone();
+            let cell = droid.try_move(direction)?;
+            cells.insert(next, cell);
+            if cell != Cell::Wall {
+                queue.push_back((next, droid));
+            }
+        }
+    }
+
+    Ok(Map { cells })
+}
+
+impl Map {
+    fn oxygen_system(&self) -> Option<Point> {
+        self.cells
+            .iter()
+            .find(|&(_, &cell)| cell == Cell::Oxygen)
+            .map(|(&position, _)| position)
+    }
+
+    /// The cells next to a position that aren't walls
+    fn open_neighbors(&self, (x, y): Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |next| matches!(self.cells.get(next), Some(Cell::Open | Cell::Oxygen)))
+    }
+
+    /// The fewest moves from the start to the oxygen system
+    fn distance_to_oxygen(&self) -> Option<usize> {
+        let oxygen = self.oxygen_system()?;
+        bfs(
+            (0, 0),
+            |&position| self.open_neighbors(position),
+            |&position| position == oxygen,
+        )
+    }
+
+    /// The minutes it takes oxygen to spread from the oxygen system to every open cell
+    fn fill_time(&self) -> Option<usize> {
+        let oxygen = self.oxygen_system()?;
+        let paths = distances(oxygen, |&position| self.open_neighbors(position));
+        paths.distances().values().copied().max()
+    }
+}
+
+impl fmt::Display for Map {
+    /// Draw the map, with the droid's start as `D` and the oxygen system as `O`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min_x = self.cells.keys().map(|p| p.0).min().unwrap_or(0);
+        let max_x = self.cells.keys().map(|p| p.0).max().unwrap_or(0);
+        let min_y = self.cells.keys().map(|p| p.1).min().unwrap_or(0);
+        let max_y = self.cells.keys().map(|p| p.1).max().unwrap_or(0);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let c = match self.cells.get(&(x, y)) {
+                    _ if (x, y) == (0, 0) => 'D',
+                    Some(Cell::Wall) => '#',
+                    Some(Cell::Open) => '.',
+                    Some(Cell::Oxygen) => 'O',
+                    None => ' ',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The shortest route to the oxygen system, and how long the oxygen takes to fill the area
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    /// Everything the droid finds, since both parts need the whole area explored
+    type Input = Map;
+
+    fn parse(input: &str) -> Self::Input {
+        let program: Program = input.parse().unwrap();
+        explore(IntcodeDroid(IntcodeProcess::new(&program))).unwrap()
+    }
+
+    fn part1(map: &Self::Input) -> String {
+        map.distance_to_oxygen().unwrap().to_string()
+    }
+
+    fn part2(map: &Self::Input) -> String {
+        map.fill_time().unwrap().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    /// A droid that walks around a maze drawn as text
+    #[derive(Clone)]
+    struct MazeDroid {
+        maze: Rc<Vec<Vec<char>>>,
+        position: Point,
+    }
+
+    impl MazeDroid {
+        fn new(maze: &str) -> Self {
+            let maze: Vec<Vec<char>> = maze.lines().map(|line| line.chars().collect()).collect();
+            let position = maze
+                .iter()
+                .enumerate()
+                .find_map(|(y, row)| row.iter().position(|&c| c == 'D').map(|x| (x, y)))
+                .map(|(x, y)| (x as i64, y as i64))
+                .unwrap();
+            MazeDroid {
+                maze: Rc::new(maze),
+                position,
+            }
+        }
+    }
+
+    impl Droid for MazeDroid {
+        fn try_move(&mut self, direction: usize) -> Result<Cell, IntcodeError> {
+            let (dx, dy) = DIRECTIONS[direction];
+            let next = (self.position.0 + dx, self.position.1 + dy);
+            let cell = match self.maze[next.1 as usize][next.0 as usize] {
+                '#' => return Ok(Cell::Wall),
+                'O' => Cell::Oxygen,
+                _ => Cell::Open,
+            };
+            self.position = next;
+            Ok(cell)
+        }
+    }
+
+    const MAZE: &str =
+        concat!(" ##   \n", "#..## \n", "#.#..#\n", "#.O.# \n", "#D##  \n", " #    \n",);
+
+    #[test]
+    fn test_explore() {
+        let map = explore(MazeDroid::new(MAZE)).unwrap();
+        assert_eq!(map.oxygen_system(), Some((1, -1)));
+        assert_eq!(map.distance_to_oxygen(), Some(2));
+        assert_eq!(
+            map.to_string(),
+            " ##   \n#..## \n#.#..#\n#.O.# \n#D##  \n #    \n"
+        );
+    }
+
+    #[test]
+    fn test_fill_time() {
+        let map = explore(MazeDroid::new(MAZE)).unwrap();
+        assert_eq!(map.fill_time(), Some(4));
+    }
+}
@@ -0,0 +1,11 @@
+use aoc_utils::puzzle::{print_answers, read_stdin};
+use aoc_utils::Puzzle;
+use day_15::Day15;
+
+fn main() {
+    let map = Day15::parse(&read_stdin());
+    if std::env::args().any(|arg| arg == "--map") {
+        print!("{}", map);
+    }
+    print_answers::<Day15>(&map);
+}
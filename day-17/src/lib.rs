@@ -0,0 +1,294 @@
+//! Day 17: Set and Forget
+
+use aoc_utils::{Grid, Puzzle};
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::fmt;
+
+/// The longest a movement routine or movement function can be, not counting the newline
+const MAX_ROUTINE_LENGTH: usize = 20;
+
+/// The camera's view of the scaffolding
+struct View {
+    grid: Grid<u8>,
+}
+
+impl View {
+    fn new(camera: &str) -> Self {
+        let grid = Grid::parse(camera.trim_end_matches('\n'), |c| Some(c as u8))
+            .expect("The camera's view should be rectangular");
+        View { grid }
+    }
+
+    fn get(&self, x: i64, y: i64) -> u8 {
+        self.grid.get((x, y)).copied().unwrap_or(b'.')
+    }
+
+    fn is_scaffold(&self, x: i64, y: i64) -> bool {
+        matches!(self.get(x, y), b'#' | b'^' | b'v' | b'<' | b'>')
+    }
+
+    /// The sum of the alignment parameters of every scaffold intersection
+    fn alignment(&self) -> i64 {
+        let mut sum = 0;
+        for (x, y) in self.grid.positions() {
+            let intersection = self.is_scaffold(x, y)
+                && self
+                    .grid
+                    .neighbors((x, y))
+                    .filter(|&(x, y)| self.is_scaffold(x, y))
+                    .count()
+                    == 4;
+            if intersection {
+                sum += x * y;
+            }
+        }
+        sum
+    }
+
+    fn robot(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.grid.iter().find_map(|(position, &c)| {
+            let direction = match c {
+                b'^' => (0, -1),
+                b'v' => (0, 1),
+                b'<' => (-1, 0),
+                b'>' => (1, 0),
+                _ => return None,
+            };
+            Some((position, direction))
+        })
+    }
+
+    /// The moves that take the robot from where it is to the end of the scaffolding, going
+    /// straight through every intersection
+    fn path(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let ((mut x, mut y), (mut dx, mut dy)) = match self.robot() {
+            Some(robot) => robot,
+            None => return moves,
+        };
+
+        loop {
+            let turn = if self.is_scaffold(x + dy, y - dx) {
+                (dx, dy) = (dy, -dx);
+                Turn::Left
+            } else if self.is_scaffold(x - dy, y + dx) {
+                (dx, dy) = (-dy, dx);
+                Turn::Right
+            } else {
+                return moves;
+            };
+
+            let mut distance = 0;
+            while self.is_scaffold(x + dx, y + dy) {
+                x += dx;
+                y += dy;
+                distance += 1;
+            }
+            moves.push(Move { turn, distance });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+/// Turn, then move forward
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    turn: Turn,
+    distance: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let turn = match self.turn {
+            Turn::Left => 'L',
+            Turn::Right => 'R',
+        };
+        write!(f, "{},{}", turn, self.distance)
+    }
+}
+
+/// The length of a list of moves as ASCII, not counting the newline
+fn length(moves: &[Move]) -> usize {
+    moves.iter().map(|m| m.to_string().len()).sum::<usize>() + moves.len().saturating_sub(1)
+}
+
+fn to_ascii(moves: &[Move]) -> String {
+    let moves: Vec<String> = moves.iter().map(Move::to_string).collect();
+    moves.join(",")
+}
+
+/// A path split into a main routine of calls to up to three movement functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Compressed {
+    main: Vec<usize>,
+    functions: Vec<Vec<Move>>,
+}
+
+impl Compressed {
+    /// The input the vacuum robot expects: the main routine, then functions A, B, and C
+    fn to_ascii(&self) -> String {
+        let main: Vec<String> = self
+            .main
+            .iter()
+            .map(|&function| ((b'A' + function as u8) as char).to_string())
+            .collect();
+        let mut ascii = format!("{}\n", main.join(","));
+        for function in 0..3 {
+            let moves = self.functions.get(function).map_or(&[][..], |f| &f[..]);
+            ascii.push_str(&to_ascii(moves));
+            ascii.push('\n');
+        }
+        ascii
+    }
+}
+
+/// Split a path into a main routine and movement functions that fit in the robot's memory
+///
+/// This is a depth-first search: at each point in the path, try each function that matches
+/// what comes next, and if there's still room for another function, try every prefix that's
+/// short enough.
+fn compress(path: &[Move]) -> Option<Compressed> {
+    let mut compressed = Compressed {
+        main: Vec::new(),
+        functions: Vec::new(),
+    };
+    if search(path, &mut compressed) {
+        Some(compressed)
+    } else {
+        None
+    }
+}
+
+fn search(path: &[Move], compressed: &mut Compressed) -> bool {
+    if path.is_empty() {
+        return true;
+    }
+    // Every call takes two characters with its comma
+    if compressed.main.len() * 2 + 1 > MAX_ROUTINE_LENGTH {
+        return false;
+    }
+
+    for function in 0..compressed.functions.len() {
+        let moves = &compressed.functions[function];
+        if path.starts_with(moves) {
+            let rest = &path[moves.len()..];
+            compressed.main.push(function);
+            if search(rest, compressed) {
+                return true;
+            }
+            compressed.main.pop();
+        }
+    }
+
+    if compressed.functions.len() < 3 {
+        let function = compressed.functions.len();
+        for len in 1..=path.len() {
+            if length(&path[..len]) > MAX_ROUTINE_LENGTH {
+                break;
+            }
+            compressed.functions.push(path[..len].to_vec());
+            compressed.main.push(function);
+            if search(&path[len..], compressed) {
+                return true;
+            }
+            compressed.main.pop();
+            compressed.functions.pop();
+        }
+    }
+
+    false
+}
+
+/// Run the program and read what the camera sees
+fn camera(program: &Program) -> Result<String, IntcodeError> {
+    let mut process = IntcodeProcess::new(program);
+    match process.run() {
+        Err(IntcodeError::CatchFire) => {}
+        Err(e) => return Err(e),
+        Ok(()) => {}
+    }
+    Ok(process.outputs().iter().map(|&c| c as u8 as char).collect())
+}
+
+/// Wake the robot up, send it along the path, and get the dust it collected
+fn collect_dust(program: &Program, routines: &Compressed) -> Result<i64, IntcodeError> {
+    let mut process = IntcodeProcess::new(program);
+    process.store(0, 2)?;
+    process.add_ascii_input(&routines.to_ascii());
+    // No continuous video feed
+    process.add_ascii_input("n\n");
+    match process.run() {
+        Err(IntcodeError::CatchFire) => {}
+        Err(e) => return Err(e),
+        Ok(()) => {}
+    }
+    Ok(process.outputs().last().copied().unwrap_or(0))
+}
+
+/// The alignment parameters of the scaffold intersections, and the dust the robot collects
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        View::new(&camera(program).unwrap()).alignment().to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        let view = View::new(&camera(program).unwrap());
+        let routines = compress(&view.path()).expect("The path should be compressible");
+        collect_dust(program, &routines).unwrap().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_alignment() {
+        let view = View::new(
+            "..#..........\n..#..........\n#######...###\n#.#...#...#.#\n\
+             #############\n..#...#...#..\n..#####...^..\n",
+        );
+        assert_eq!(view.alignment(), 76);
+    }
+
+    #[test]
+    fn test_path_and_compress() {
+        let view = View::new(
+            "#######...#####\n#.....#...#...#\n#.....#...#...#\n......#...#...#\n\
+             ......#...###.#\n......#.....#.#\n^########...#.#\n......#.#...#.#\n\
+             ......#########\n........#...#..\n....#########..\n....#...#......\n\
+             ....#...#......\n....#...#......\n....#####......\n",
+        );
+        let path = view.path();
+        assert_eq!(
+            to_ascii(&path),
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+        );
+
+        let compressed = compress(&path).unwrap();
+        let expanded: Vec<Move> = compressed
+            .main
+            .iter()
+            .flat_map(|&function| compressed.functions[function].iter().copied())
+            .collect();
+        assert_eq!(expanded, path);
+        assert!(compressed.functions.len() <= 3);
+        for line in compressed.to_ascii().lines() {
+            assert!(line.len() <= MAX_ROUTINE_LENGTH);
+        }
+        assert_eq!(compressed.to_ascii().lines().count(), 4);
+    }
+}
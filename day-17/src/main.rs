@@ -0,0 +1,3 @@
+fn main() {
+    aoc_utils::puzzle::run::<day_17::Day17>();
+}
@@ -0,0 +1,250 @@
+//! Day 18: Many-Worlds Interpretation
+
+use aoc_utils::pathfinding::{dijkstra, distances};
+use aoc_utils::Puzzle;
+
+/// A set of keys or doors, one bit for each letter
+type Keys = u32;
+
+fn key_bit(c: u8) -> Keys {
+    1 << (c.to_ascii_lowercase() - b'a')
+}
+
+/// The map of the vault
+#[derive(Clone)]
+pub struct Vault {
+    rows: Vec<Vec<u8>>,
+}
+
+/// The shortest walk from one point of interest to a key, and the doors along the way
+#[derive(Debug, Clone, Copy)]
+struct Route {
+    key: usize,
+    distance: usize,
+    doors: Keys,
+}
+
+impl Vault {
+    fn new(map: &str) -> Self {
+        let rows = map
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.bytes().collect())
+            .collect();
+        Vault { rows }
+    }
+
+    /// Replace the single entrance with four, walled off from each other, for part 2
+    fn split_entrance(&mut self) {
+        let (x, y) = self.find(|c| c == b'@')[0];
+        let replacement = [b"@#@", b"###", b"@#@"];
+        for (dy, row) in replacement.iter().enumerate() {
+            for (dx, &c) in row.iter().enumerate() {
+                self.rows[y + dy - 1][x + dx - 1] = c;
+            }
+        }
+    }
+
+    fn find(&self, predicate: impl Fn(u8) -> bool) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if predicate(c) {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+
+    /// Every key reachable from a position, by breadth-first search
+    fn routes_from(&self, start: (usize, usize)) -> Vec<Route> {
+        let paths = distances(start, |&(x, y)| {
+            let neighbors = vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)];
+            neighbors
+                .into_iter()
+                .filter(|&(x, y)| self.rows[y][x] != b'#')
+        });
+
+        let mut routes = Vec::new();
+        for (&(x, y), &distance) in paths.distances() {
+            let c = self.rows[y][x];
+            if !c.is_ascii_lowercase() || distance == 0 {
+                continue;
+            }
+            let doors = paths
+                .path_to(&(x, y))
+                .unwrap()
+                .into_iter()
+                .map(|(x, y)| self.rows[y][x])
+                .filter(u8::is_ascii_uppercase)
+                .fold(0, |doors, door| doors | key_bit(door));
+            routes.push(Route {
+                key: (c - b'a') as usize,
+                distance,
+                doors,
+            });
+        }
+        routes
+    }
+
+    /// The fewest steps for the robots at the entrances to collect every key
+    ///
+    /// Walks between points of interest are found once, then Dijkstra's algorithm searches
+    /// over where each robot is and which keys have been collected.
+    fn collect_keys(&self) -> Option<usize> {
+        let keys = self.find(|c| c.is_ascii_lowercase());
+        let entrances = self.find(|c| c == b'@');
+        let all_keys = keys
+            .iter()
+            .fold(0, |all, &(x, y)| all | key_bit(self.rows[y][x]));
+
+        // Points of interest 0 through 25 are the keys, and the entrances come after them
+        let mut routes = vec![Vec::new(); 26 + entrances.len()];
+        for &(x, y) in &keys {
+            routes[(self.rows[y][x] - b'a') as usize] = self.routes_from((x, y));
+        }
+        for (robot, &entrance) in entrances.iter().enumerate() {
+            routes[26 + robot] = self.routes_from(entrance);
+        }
+
+        let start: Vec<usize> = (0..entrances.len()).map(|robot| 26 + robot).collect();
+        let neighbors = |(positions, collected): &(Vec<usize>, Keys)| {
+            let mut next = Vec::new();
+            for (robot, &position) in positions.iter().enumerate() {
+                for route in &routes[position] {
+                    let bit = 1 << route.key;
+                    if collected & bit != 0 || route.doors & !collected != 0 {
+                        continue;
+                    }
+                    let mut positions = positions.clone();
+                    positions[robot] = route.key;
+                    next.push(((positions, collected | bit), route.distance));
+                }
+            }
+            next
+        };
+        dijkstra((start, 0), neighbors, |&(_, collected)| {
+            collected == all_keys
+        })
+    }
+}
+
+/// The fewest steps to collect every key, alone and with four robots
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    type Input = Vault;
+
+    fn parse(input: &str) -> Self::Input {
+        Vault::new(input)
+    }
+
+    fn part1(vault: &Self::Input) -> String {
+        vault.collect_keys().unwrap().to_string()
+    }
+
+    fn part2(vault: &Self::Input) -> String {
+        let mut vault = vault.clone();
+        vault.split_entrance();
+        vault.collect_keys().unwrap().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn steps(map: &[&str]) -> Option<usize> {
+        Vault::new(&map.join("\n")).collect_keys()
+    }
+
+    #[test]
+    fn test_one_robot() {
+        assert_eq!(steps(&["#########", "#b.A.@.a#", "#########"]), Some(8));
+        assert_eq!(
+            steps(&[
+                "########################",
+                "#f.D.E.e.C.b.A.@.a.B.c.#",
+                "######################.#",
+                "#d.....................#",
+                "########################",
+            ]),
+            Some(86)
+        );
+        assert_eq!(
+            steps(&[
+                "########################",
+                "#...............b.C.D.f#",
+                "#.######################",
+                "#.....@.a.B.c.d.A.e.F.g#",
+                "########################",
+            ]),
+            Some(132)
+        );
+        assert_eq!(
+            steps(&[
+                "#################",
+                "#i.G..c...e..H.p#",
+                "########.########",
+                "#j.A..b...f..D.o#",
+                "########@########",
+                "#k.E..a...g..B.n#",
+                "########.########",
+                "#l.F..d...h..C.m#",
+                "#################",
+            ]),
+            Some(136)
+        );
+        assert_eq!(
+            steps(&[
+                "########################",
+                "#@..............ac.GI.b#",
+                "###d#e#f################",
+                "###A#B#C################",
+                "###g#h#i################",
+                "########################",
+            ]),
+            Some(81)
+        );
+    }
+
+    #[test]
+    fn test_four_robots() {
+        let mut vault = Vault::new(
+            &[
+                "#######", "#a.#Cd#", "##...##", "##.@.##", "##...##", "#cB#Ab#", "#######",
+            ]
+            .join("\n"),
+        );
+        vault.split_entrance();
+        assert_eq!(vault.collect_keys(), Some(8));
+
+        assert_eq!(
+            steps(&[
+                "###############",
+                "#d.ABC.#.....a#",
+                "######@#@######",
+                "###############",
+                "######@#@######",
+                "#b.....#.....c#",
+                "###############",
+            ]),
+            Some(24)
+        );
+        assert_eq!(
+            steps(&[
+                "#############",
+                "#g#f.D#..h#l#",
+                "#F###e#E###.#",
+                "#dCba@#@BcIJ#",
+                "#############",
+                "#nK.L@#@G...#",
+                "#M###N#H###.#",
+                "#o#m..#i#jk.#",
+                "#############",
+            ]),
+            Some(72)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Day 19: Tractor Beam
+
+use aoc_utils::Puzzle;
+use intcode::{IntcodeProcess, Program};
+
+/// Something that can say whether a point is in the tractor beam
+trait Beam {
+    fn pulled(&mut self, x: i64, y: i64) -> bool;
+}
+
+/// The drone system, which needs a fresh run of its program for every point
+struct DroneSystem<'a> {
+    program: &'a Program,
+}
+
+impl Beam for DroneSystem<'_> {
+    fn pulled(&mut self, x: i64, y: i64) -> bool {
+        let mut process = IntcodeProcess::new(self.program);
+        process.add_inputs(&[x, y]);
+        process.run_to_output().unwrap() == 1
+    }
+}
+
+/// The number of points in the beam in the square of the given size at the emitter
+fn affected_points(beam: &mut impl Beam, size: i64) -> usize {
+    let mut count = 0;
+    for y in 0..size {
+        for x in 0..size {
+            if beam.pulled(x, y) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// The top left corner of the square of the given size, closest to the emitter, that fits in
+/// the beam
+///
+/// Rather than scanning every point, this follows the left edge of the beam down, one row at a
+/// time, with the row as the bottom of the square. The beam only widens and moves right, so
+/// the edge never moves left, and the square fits as soon as its top right corner is pulled
+/// too.
+fn closest_square(beam: &mut impl Beam, size: i64) -> (i64, i64) {
+    let mut left = 0;
+    let mut y = size - 1;
+    loop {
+        // Rows close to the emitter can miss the beam completely, so don't look forever
+        let limit = left + 2 * y + 10;
+        let edge = (left..=limit).find(|&x| beam.pulled(x, y));
+        if let Some(x) = edge {
+            left = x;
+            if beam.pulled(x + size - 1, y - size + 1) {
+                return (x, y - size + 1);
+            }
+        }
+        y += 1;
+    }
+}
+
+/// The points the beam affects near the emitter, and the closest place Santa's ship fits
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        let mut drones = DroneSystem { program };
+        affected_points(&mut drones, 50).to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        let mut drones = DroneSystem { program };
+        let (x, y) = closest_square(&mut drones, 100);
+        (x * 10000 + y).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_utils::Grid;
+
+    /// A beam between two lines through the emitter
+    struct Wedge;
+
+    impl Beam for Wedge {
+        fn pulled(&mut self, x: i64, y: i64) -> bool {
+            x * 7 >= y * 5 && x * 3 <= y * 4
+        }
+    }
+
+    /// A beam drawn like the puzzle's examples, with `#` for pulled points
+    struct Drawing(Grid<bool>);
+
+    impl Beam for Drawing {
+        fn pulled(&mut self, x: i64, y: i64) -> bool {
+            self.0.get((x, y)) == Some(&true)
+        }
+    }
+
+    #[test]
+    fn test_affected_points() {
+        let example = "\
+#.........
+.#........
+..##......
+...###....
+....###...
+.....####.
+......####
+......####
+.......###
+........##
+";
+        let mut beam = Drawing(Grid::parse(example, |c| Some(c == '#')).unwrap());
+        assert_eq!(affected_points(&mut beam, 10), 27);
+
+        assert_eq!(affected_points(&mut Wedge, 5), 7);
+    }
+
+    #[test]
+    fn test_closest_square() {
+        let fits = |x: i64, y: i64, size: i64| {
+            [(x, y), (x + size - 1, y), (x, y + size - 1)]
+                .iter()
+                .all(|&(x, y)| Wedge.pulled(x, y))
+        };
+        let expected = (0..1000)
+            .flat_map(|y| (0..1000).map(move |x| (x, y)))
+            .find(|&(x, y)| fits(x, y, 10))
+            .unwrap();
+
+        assert_eq!(closest_square(&mut Wedge, 10), expected);
+    }
+}
@@ -0,0 +1,227 @@
+//! Day 20: Donut Maze
+
+use aoc_utils::pathfinding::bfs;
+use aoc_utils::Puzzle;
+use std::collections::HashMap;
+
+type Point = (usize, usize);
+
+/// Where a portal takes you, and whether it goes a level deeper (inner) or back out (outer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Portal {
+    to: Point,
+    depth_change: i64,
+}
+
+/// The maze, with its portals
+pub struct Maze {
+    rows: Vec<Vec<u8>>,
+    portals: HashMap<Point, Portal>,
+    start: Point,
+    end: Point,
+}
+
+impl Maze {
+    fn new(input: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+        let get = |x: usize, y: usize| {
+            rows.get(y)
+                .and_then(|row: &Vec<u8>| row.get(x))
+                .copied()
+                .unwrap_or(b' ')
+        };
+
+        // The edges of the donut, to tell outer portals from inner ones
+        let walls: Vec<Point> = (0..rows.len())
+            .flat_map(|y| (0..rows[y].len()).map(move |x| (x, y)))
+            .filter(|&(x, y)| rows[y][x] == b'#')
+            .collect();
+        let min_x = walls.iter().map(|p| p.0).min().ok_or("No maze")?;
+        let max_x = walls.iter().map(|p| p.0).max().ok_or("No maze")?;
+        let min_y = walls.iter().map(|p| p.1).min().ok_or("No maze")?;
+        let max_y = walls.iter().map(|p| p.1).max().ok_or("No maze")?;
+
+        // Every open tile next to a label, by the label
+        let mut labels: HashMap<[u8; 2], Vec<Point>> = HashMap::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if c != b'.' {
+                    continue;
+                }
+                // Labels read left to right or top to bottom, whichever side they're on
+                let label = if get(x, y - 1).is_ascii_uppercase() {
+                    [get(x, y - 2), get(x, y - 1)]
+                } else if get(x, y + 1).is_ascii_uppercase() {
+                    [get(x, y + 1), get(x, y + 2)]
+                } else if get(x - 1, y).is_ascii_uppercase() {
+                    [get(x - 2, y), get(x - 1, y)]
+                } else if get(x + 1, y).is_ascii_uppercase() {
+                    [get(x + 1, y), get(x + 2, y)]
+                } else {
+                    continue;
+                };
+                labels.entry(label).or_default().push((x, y));
+            }
+        }
+
+        let mut start = None;
+        let mut end = None;
+        let mut portals = HashMap::new();
+        for (label, tiles) in labels {
+            match (&label, &tiles[..]) {
+                (b"AA", &[tile]) => start = Some(tile),
+                (b"ZZ", &[tile]) => end = Some(tile),
+                (_, &[a, b]) => {
+                    let outer =
+                        |(x, y): Point| x == min_x || x == max_x || y == min_y || y == max_y;
+                    let depth_change = |tile| if outer(tile) { -1 } else { 1 };
+                    portals.insert(
+                        a,
+                        Portal {
+                            to: b,
+                            depth_change: depth_change(a),
+                        },
+                    );
+                    portals.insert(
+                        b,
+                        Portal {
+                            to: a,
+                            depth_change: depth_change(b),
+                        },
+                    );
+                }
+                _ => {
+                    return Err(format!(
+                        "Portal {} has {} ends",
+                        String::from_utf8_lossy(&label),
+                        tiles.len()
+                    ))
+                }
+            }
+        }
+
+        Ok(Maze {
+            rows,
+            portals,
+            start: start.ok_or("No entrance")?,
+            end: end.ok_or("No exit")?,
+        })
+    }
+
+    /// The open tiles next to a tile, not counting portals
+    fn walk(&self, (x, y): Point) -> impl Iterator<Item = Point> + '_ {
+        vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(move |&(x, y)| self.rows[y].get(x) == Some(&b'.'))
+    }
+
+    /// The fewest steps from the entrance to the exit, where portals lead straight across
+    fn shortest_path(&self) -> Option<usize> {
+        bfs(
+            self.start,
+            |&tile| {
+                let portal = self.portals.get(&tile).map(|portal| portal.to);
+                self.walk(tile).chain(portal).collect::<Vec<_>>()
+            },
+            |&tile| tile == self.end,
+        )
+    }
+
+    /// The fewest steps from the entrance to the exit, where inner portals lead into a copy of
+    /// the maze one level deeper, and outer portals lead back out a level
+    ///
+    /// The outer portals are walls at the outermost level, and the exit is only open there. A
+    /// path never needs to go deeper than there are portals, so that's where the search stops.
+    fn shortest_recursive_path(&self) -> Option<usize> {
+        let max_depth = self.portals.len() as i64;
+        bfs(
+            (self.start, 0),
+            |&(tile, depth)| {
+                let portal = self
+                    .portals
+                    .get(&tile)
+                    .map(|portal| (portal.to, depth + portal.depth_change))
+                    .filter(|&(_, depth)| (0..=max_depth).contains(&depth));
+                self.walk(tile)
+                    .map(|tile| (tile, depth))
+                    .chain(portal)
+                    .collect::<Vec<_>>()
+            },
+            |&(tile, depth)| tile == self.end && depth == 0,
+        )
+    }
+}
+
+/// The shortest way through the maze, flat and recursive
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Self::Input {
+        Maze::new(input).unwrap()
+    }
+
+    fn part1(maze: &Self::Input) -> String {
+        maze.shortest_path().unwrap().to_string()
+    }
+
+    fn part2(maze: &Self::Input) -> String {
+        maze.shortest_recursive_path().unwrap().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &[&str] = &[
+        "         A           ",
+        "         A           ",
+        "  #######.#########  ",
+        "  #######.........#  ",
+        "  #######.#######.#  ",
+        "  #######.#######.#  ",
+        "  #######.#######.#  ",
+        "  #####  B    ###.#  ",
+        "BC...##  C    ###.#  ",
+        "  ##.##       ###.#  ",
+        "  ##...DE  F  ###.#  ",
+        "  #####    G  ###.#  ",
+        "  #########.#####.#  ",
+        "DE..#######...###.#  ",
+        "  #.#########.###.#  ",
+        "FG..#########.....#  ",
+        "  ###########.#####  ",
+        "             Z       ",
+        "             Z       ",
+    ];
+
+    #[test]
+    fn test_parse() {
+        let maze = Maze::new(&EXAMPLE.join("\n")).unwrap();
+        assert_eq!(maze.start, (9, 2));
+        assert_eq!(maze.end, (13, 16));
+        assert_eq!(
+            maze.portals.get(&(9, 6)),
+            Some(&Portal {
+                to: (2, 8),
+                depth_change: 1
+            })
+        );
+        assert_eq!(
+            maze.portals.get(&(2, 8)),
+            Some(&Portal {
+                to: (9, 6),
+                depth_change: -1
+            })
+        );
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let maze = Maze::new(&EXAMPLE.join("\n")).unwrap();
+        assert_eq!(maze.shortest_path(), Some(23));
+        assert_eq!(maze.shortest_recursive_path(), Some(26));
+    }
+}
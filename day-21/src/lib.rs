@@ -0,0 +1,99 @@
+//! Survey the hull with the springdroid, running springscript programs on its intcode brain
+
+pub mod springscript;
+
+use aoc_utils::Puzzle;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use springscript::{search, Mode, Springscript};
+use std::fmt;
+
+/// Jump if there's a hole in the next three tiles, as long as there's ground to land on
+pub const WALK: &str = "
+    NOT A J
+    NOT B T
+    OR T J
+    NOT C T
+    OR T J
+    AND D J
+";
+
+/// Like walking, but only if, after landing, the droid can step forward or jump again
+pub const RUN: &str = "
+    NOT A J
+    NOT B T
+    OR T J
+    NOT C T
+    OR T J
+    AND D J
+    NOT E T
+    NOT T T
+    OR H T
+    AND T J
+";
+
+/// What happened to the droid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// It made it across, and reported the hull damage
+    Damage(i64),
+    /// It fell into space; this is its last view of the hull
+    Fell(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Damage(damage) => write!(f, "{}", damage),
+            Outcome::Fell(view) => write!(f, "{}", view),
+        }
+    }
+}
+
+/// Run the springdroid with the given program, and see if it makes it across
+pub fn survey(program: &Program, script: &Springscript) -> Result<Outcome, IntcodeError> {
+    let mut process = IntcodeProcess::new(program);
+    process.add_ascii_input(&script.to_ascii());
+    match process.run() {
+        Err(IntcodeError::CatchFire) | Ok(()) => {}
+        Err(e) => return Err(e),
+    }
+
+    match process.outputs().last() {
+        Some(&damage) if damage > 127 => Ok(Outcome::Damage(damage)),
+        _ => Ok(Outcome::Fell(
+            process.outputs().iter().map(|&c| c as u8 as char).collect(),
+        )),
+    }
+}
+
+/// Survey the hull in a mode, with the given script or with one found by brute force
+pub fn solve(program: &Program, mode: Mode, source: &str, brute_force: bool) -> Outcome {
+    let script = if brute_force {
+        search(mode, 15, |script| {
+            matches!(survey(program, script), Ok(Outcome::Damage(_)))
+        })
+        .expect("No springscript program works")
+    } else {
+        Springscript::parse(mode, source).unwrap()
+    };
+    survey(program, &script).unwrap()
+}
+
+/// The hull damage the droid reports, walking and running
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        solve(program, Mode::Walk, WALK, false).to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        solve(program, Mode::Run, RUN, false).to_string()
+    }
+}
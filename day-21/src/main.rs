@@ -0,0 +1,16 @@
+use aoc_utils::puzzle::{print_answers, read_stdin};
+use aoc_utils::Puzzle;
+use day_21::springscript::Mode;
+use day_21::{solve, Day21, RUN, WALK};
+use std::env;
+
+fn main() {
+    let program = Day21::parse(&read_stdin());
+
+    if env::args().any(|arg| arg == "--search") {
+        println!("part1={}", solve(&program, Mode::Walk, WALK, true));
+        println!("part2={}", solve(&program, Mode::Run, RUN, true));
+    } else {
+        print_answers::<Day21>(&program);
+    }
+}
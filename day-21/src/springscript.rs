@@ -0,0 +1,366 @@
+//! Springscript programs, which decide when the springdroid jumps
+//!
+//! A program is a list of `AND X Y`, `OR X Y`, and `NOT X Y` instructions, where `X` is any
+//! register and `Y` is one of the writable registers, `T` and `J`. The droid jumps if `J` is
+//! true at the end. Registers `A` to `D` say whether there's ground 1 to 4 tiles ahead; when
+//! running, `E` to `I` look up to 9 tiles ahead.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// The most instructions the droid's memory can hold
+pub const MAX_INSTRUCTIONS: usize = 15;
+
+/// How the droid moves, which decides how far ahead it can see
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Walk,
+    Run,
+}
+
+impl Mode {
+    /// The number of sensor registers, starting from `A`
+    fn sensors(self) -> usize {
+        match self {
+            Mode::Walk => 4,
+            Mode::Run => 9,
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            Mode::Walk => "WALK",
+            Mode::Run => "RUN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    And,
+    Or,
+    Not,
+}
+
+/// A register: a sensor from `A` (0) to `I` (8), or `T` or `J`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    Sensor(u8),
+    T,
+    J,
+}
+
+impl Register {
+    fn writable(self) -> bool {
+        matches!(self, Register::T | Register::J)
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Register::Sensor(sensor) => write!(f, "{}", (b'A' + sensor) as char),
+            Register::T => write!(f, "T"),
+            Register::J => write!(f, "J"),
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = SpringscriptError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.as_bytes() {
+            b"T" => Ok(Register::T),
+            b"J" => Ok(Register::J),
+            &[c] if (b'A'..=b'I').contains(&c) => Ok(Register::Sensor(c - b'A')),
+            _ => Err(SpringscriptError::UnknownRegister(input.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Op,
+    pub source: Register,
+    pub target: Register,
+}
+
+impl Instruction {
+    /// Run the instruction, given the sensors and `T` and `J`, and get the new `T` and `J`
+    fn execute(&self, sensors: u16, (t, j): (bool, bool)) -> (bool, bool) {
+        let source = match self.source {
+            Register::Sensor(sensor) => sensors & (1 << sensor) != 0,
+            Register::T => t,
+            Register::J => j,
+        };
+        let target = match self.target {
+            Register::T => t,
+            _ => j,
+        };
+        let result = match self.op {
+            Op::And => target && source,
+            Op::Or => target || source,
+            Op::Not => !source,
+        };
+        match self.target {
+            Register::T => (result, j),
+            _ => (t, result),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Not => "NOT",
+        };
+        write!(f, "{} {} {}", op, self.source, self.target)
+    }
+}
+
+/// An error from building or parsing a springscript program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpringscriptError {
+    /// The program has more instructions than fit in the droid's memory
+    TooLong,
+    /// An instruction writes to a register other than `T` or `J`
+    ReadOnlyRegister(Register),
+    /// An instruction reads a sensor that the droid doesn't have in this mode
+    UnavailableSensor(Register),
+    UnknownRegister(String),
+    UnknownInstruction(String),
+}
+
+impl fmt::Display for SpringscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpringscriptError::TooLong => {
+                write!(f, "more than {} instructions", MAX_INSTRUCTIONS)
+            }
+            SpringscriptError::ReadOnlyRegister(register) => {
+                write!(f, "register {} can't be written", register)
+            }
+            SpringscriptError::UnavailableSensor(register) => {
+                write!(f, "register {} can only be read when running", register)
+            }
+            SpringscriptError::UnknownRegister(register) => {
+                write!(f, "unknown register {:?}", register)
+            }
+            SpringscriptError::UnknownInstruction(line) => {
+                write!(f, "unknown instruction {:?}", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpringscriptError {}
+
+/// A springscript program for one mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Springscript {
+    mode: Mode,
+    instructions: Vec<Instruction>,
+}
+
+impl Springscript {
+    /// Start an empty program, which never jumps
+    pub fn new(mode: Mode) -> Self {
+        Springscript {
+            mode,
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Parse a program, one instruction per line
+    pub fn parse(mode: Mode, source: &str) -> Result<Self, SpringscriptError> {
+        let mut script = Springscript::new(mode);
+        for line in source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let op = match parts[0] {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "NOT" => Op::Not,
+                _ => return Err(SpringscriptError::UnknownInstruction(line.to_string())),
+            };
+            if parts.len() != 3 {
+                return Err(SpringscriptError::UnknownInstruction(line.to_string()));
+            }
+            script.push(op, parts[1].parse()?, parts[2].parse()?)?;
+        }
+        Ok(script)
+    }
+
+    /// Add an instruction, checking that it fits and uses registers it's allowed to
+    pub fn push(
+        &mut self,
+        op: Op,
+        source: Register,
+        target: Register,
+    ) -> Result<&mut Self, SpringscriptError> {
+        if self.instructions.len() == MAX_INSTRUCTIONS {
+            return Err(SpringscriptError::TooLong);
+        }
+        if !target.writable() {
+            return Err(SpringscriptError::ReadOnlyRegister(target));
+        }
+        if let Register::Sensor(sensor) = source {
+            if sensor as usize >= self.mode.sensors() {
+                return Err(SpringscriptError::UnavailableSensor(source));
+            }
+        }
+        self.instructions.push(Instruction { op, source, target });
+        Ok(self)
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Whether the droid jumps, given what its sensors see (bit 0 is `A`, and so on)
+    pub fn jumps(&self, sensors: u16) -> bool {
+        self.run(sensors, (false, false)).1
+    }
+
+    /// Run the instructions from the given `T` and `J`, and get them at the end
+    fn run(&self, sensors: u16, registers: (bool, bool)) -> (bool, bool) {
+        self.instructions
+            .iter()
+            .fold(registers, |registers, instruction| {
+                instruction.execute(sensors, registers)
+            })
+    }
+
+    /// The input the droid expects: the instructions, then the command to start moving
+    pub fn to_ascii(&self) -> String {
+        let mut ascii = String::new();
+        for instruction in &self.instructions {
+            ascii.push_str(&format!("{}\n", instruction));
+        }
+        ascii.push_str(self.mode.command());
+        ascii.push('\n');
+        ascii
+    }
+}
+
+/// Look for a program that `accept`s, trying shorter programs first
+///
+/// Most programs are just a longer way to write a shorter one, so rather than trying every
+/// list of instructions, this keeps track of what each program does to `T` and `J` for every
+/// combination of sensors, and only tries programs that do something new. `accept` is only
+/// called for programs that jump in a way no earlier program did.
+pub fn search(
+    mode: Mode,
+    max_instructions: usize,
+    mut accept: impl FnMut(&Springscript) -> bool,
+) -> Option<Springscript> {
+    let combinations: Vec<u16> = (0..1 << mode.sensors()).collect();
+
+    let mut instructions = Vec::new();
+    for op in [Op::And, Op::Or, Op::Not].iter() {
+        let sources = (0..mode.sensors() as u8)
+            .map(Register::Sensor)
+            .chain(vec![Register::T, Register::J]);
+        for source in sources {
+            for target in [Register::T, Register::J].iter() {
+                instructions.push((*op, source, *target));
+            }
+        }
+    }
+
+    let start = Springscript::new(mode);
+    if accept(&start) {
+        return Some(start);
+    }
+    let start_behavior = vec![(false, false); combinations.len()];
+    let mut seen = HashSet::new();
+    let mut tried = HashSet::new();
+    seen.insert(start_behavior.clone());
+    tried.insert(vec![false; combinations.len()]);
+    let mut queue = VecDeque::new();
+    queue.push_back((start, start_behavior));
+
+    while let Some((script, previous)) = queue.pop_front() {
+        if script.instructions.len() == max_instructions.min(MAX_INSTRUCTIONS) {
+            continue;
+        }
+        for &(op, source, target) in &instructions {
+            let mut next = script.clone();
+            next.push(op, source, target).unwrap();
+            let last = next.instructions[next.instructions.len() - 1];
+            let next_behavior: Vec<(bool, bool)> = combinations
+                .iter()
+                .zip(&previous)
+                .map(|(&sensors, &registers)| last.execute(sensors, registers))
+                .collect();
+            if !seen.insert(next_behavior.clone()) {
+                continue;
+            }
+            let jumps: Vec<bool> = next_behavior.iter().map(|&(_, j)| j).collect();
+            if tried.insert(jumps) && accept(&next) {
+                return Some(next);
+            }
+            queue.push_back((next, next_behavior));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_validate() {
+        let script = Springscript::parse(Mode::Walk, "NOT A J\nNOT B T\nOR T J\nAND D J").unwrap();
+        assert_eq!(script.instructions().len(), 4);
+        assert_eq!(
+            script.to_ascii(),
+            "NOT A J\nNOT B T\nOR T J\nAND D J\nWALK\n"
+        );
+        // A hole right ahead, and ground to land on
+        assert!(script.jumps(0b1000));
+        assert!(!script.jumps(0b1111));
+        assert!(!script.jumps(0b0000));
+
+        assert_eq!(
+            Springscript::parse(Mode::Walk, "NOT A B"),
+            Err(SpringscriptError::ReadOnlyRegister(Register::Sensor(1)))
+        );
+        assert_eq!(
+            Springscript::parse(Mode::Walk, "NOT E J"),
+            Err(SpringscriptError::UnavailableSensor(Register::Sensor(4)))
+        );
+        assert!(Springscript::parse(Mode::Run, "NOT E J").is_ok());
+        assert_eq!(
+            Springscript::parse(Mode::Walk, "XOR A J"),
+            Err(SpringscriptError::UnknownInstruction("XOR A J".to_string()))
+        );
+        assert_eq!(
+            Springscript::parse(Mode::Walk, &"NOT A J\n".repeat(16)),
+            Err(SpringscriptError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        // Jump if there's a hole in A, B, or C, and ground at D
+        let wanted = |sensors: u16| sensors & 0b0111 != 0b0111 && sensors & 0b1000 != 0;
+        let mut calls = 0;
+        let script = search(Mode::Walk, 6, |script| {
+            calls += 1;
+            (0..16).all(|sensors| script.jumps(sensors) == wanted(sensors))
+        })
+        .unwrap();
+
+        assert!(script.instructions().len() <= 6);
+        assert!(calls < 1000);
+    }
+}
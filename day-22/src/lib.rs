@@ -0,0 +1,201 @@
+//! Day 22: Slam Shuffle
+
+use aoc_utils::math::{mod_inv, mod_pow};
+use aoc_utils::Puzzle;
+use std::str::FromStr;
+
+/// A way of shuffling the deck
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Technique {
+    NewStack,
+    Cut(i128),
+    Increment(i128),
+}
+
+impl FromStr for Technique {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let number = |prefix: &str| {
+            input[prefix.len()..]
+                .parse()
+                .map_err(|_| format!("Invalid number in {:?}", input))
+        };
+        if input == "deal into new stack" {
+            Ok(Technique::NewStack)
+        } else if input.starts_with("cut ") {
+            Ok(Technique::Cut(number("cut ")?))
+        } else if input.starts_with("deal with increment ") {
+            Ok(Technique::Increment(number("deal with increment ")?))
+        } else {
+            Err(format!("Unknown technique {:?}", input))
+        }
+    }
+}
+
+/// A shuffle, as where it moves the card at each position: `a * position + b`, modulo the
+/// size of the deck
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shuffle {
+    a: i128,
+    b: i128,
+    size: i128,
+}
+
+impl Shuffle {
+    fn identity(size: i128) -> Self {
+        Shuffle { a: 1, b: 0, size }
+    }
+
+    fn from_technique(technique: Technique, size: i128) -> Self {
+        let (a, b) = match technique {
+            Technique::NewStack => (-1, -1),
+            Technique::Cut(n) => (1, -n),
+            Technique::Increment(n) => (n, 0),
+        };
+        Shuffle {
+            a: a.rem_euclid(size),
+            b: b.rem_euclid(size),
+            size,
+        }
+    }
+
+    fn from_techniques(techniques: &[Technique], size: i128) -> Self {
+        techniques
+            .iter()
+            .fold(Shuffle::identity(size), |shuffle, &technique| {
+                shuffle.then(Shuffle::from_technique(technique, size))
+            })
+    }
+
+    /// This shuffle, followed by another
+    fn then(self, other: Shuffle) -> Self {
+        Shuffle {
+            a: self.a * other.a % self.size,
+            b: (self.b * other.a + other.b) % self.size,
+            size: self.size,
+        }
+    }
+
+    /// This shuffle, done `times` times in a row
+    ///
+    /// Repeating `a * x + b` gives `a^n * x + b * (a^n - 1) / (a - 1)`, where the division is a
+    /// multiplication by the inverse, which exists as long as the size of the deck is prime.
+    fn repeat(self, times: u128) -> Self {
+        let a = mod_pow(self.a, times, self.size);
+        let b = if self.a == 1 {
+            self.b * (times % self.size as u128) as i128 % self.size
+        } else {
+            let inverse = mod_inv(self.a - 1, self.size).expect("The deck size should be prime");
+            self.b * ((a - 1).rem_euclid(self.size) * inverse % self.size) % self.size
+        };
+        Shuffle {
+            a,
+            b,
+            size: self.size,
+        }
+    }
+
+    /// Where the card at `position` ends up
+    fn apply(self, position: i128) -> i128 {
+        (self.a * position + self.b).rem_euclid(self.size)
+    }
+
+    /// Which position the card that ends up at `position` came from
+    fn invert(self, position: i128) -> i128 {
+        let inverse = mod_inv(self.a, self.size).expect("Shuffles can always be undone");
+        ((position - self.b).rem_euclid(self.size) * inverse) % self.size
+    }
+}
+
+/// Where card 2019 ends up, and which card ends up at position 2020 of the huge deck
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    type Input = Vec<Technique>;
+
+    fn parse(input: &str) -> Self::Input {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    fn part1(techniques: &Self::Input) -> String {
+        let shuffle = Shuffle::from_techniques(techniques, 10007);
+        shuffle.apply(2019).to_string()
+    }
+
+    fn part2(techniques: &Self::Input) -> String {
+        let shuffle = Shuffle::from_techniques(techniques, 119_315_717_514_047);
+        let shuffle = shuffle.repeat(101_741_582_076_661);
+        shuffle.invert(2020).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The deck after shuffling, from top to bottom
+    fn deck(techniques: &str, size: i128) -> Vec<i128> {
+        let techniques: Vec<Technique> = techniques
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let shuffle = Shuffle::from_techniques(&techniques, size);
+        let mut deck = vec![0; size as usize];
+        for card in 0..size {
+            deck[shuffle.apply(card) as usize] = card;
+        }
+        deck
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            deck(
+                "deal with increment 7\ndeal into new stack\ndeal into new stack",
+                10
+            ),
+            vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7]
+        );
+        assert_eq!(
+            deck("cut 6\ndeal with increment 7\ndeal into new stack", 10),
+            vec![3, 0, 7, 4, 1, 8, 5, 2, 9, 6]
+        );
+        assert_eq!(
+            deck("deal with increment 7\ndeal with increment 9\ncut -2", 10),
+            vec![6, 3, 0, 7, 4, 1, 8, 5, 2, 9]
+        );
+        assert_eq!(
+            deck(
+                "deal into new stack\ncut -2\ndeal with increment 7\ncut 8\ncut -4\n\
+                 deal with increment 7\ncut 3\ndeal with increment 9\ndeal with increment 3\n\
+                 cut -1",
+                10
+            ),
+            vec![9, 2, 5, 8, 1, 4, 7, 0, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_repeat_and_invert() {
+        let techniques = [
+            Technique::Increment(7),
+            Technique::Cut(-3),
+            Technique::NewStack,
+        ];
+        let once = Shuffle::from_techniques(&techniques, 10007);
+        let mut position = 2019;
+        for _ in 0..1000 {
+            position = once.apply(position);
+        }
+
+        let repeated = once.repeat(1000);
+        assert_eq!(repeated.apply(2019), position);
+        assert_eq!(repeated.invert(position), 2019);
+    }
+}
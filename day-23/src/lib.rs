@@ -0,0 +1,102 @@
+//! Day 23: Category Six
+
+use aoc_utils::Puzzle;
+use intcode::scheduler::{LastPacketNat, Packet, Scheduler};
+use intcode::{IntcodeError, Program};
+
+const COMPUTERS: usize = 50;
+const NAT_ADDRESS: i64 = 255;
+
+/// The first packet sent to the NAT's address, with nothing there to receive it
+fn first_nat_packet(program: &[i64]) -> Result<Packet, IntcodeError> {
+    let mut network = Scheduler::boot(program, COMPUTERS);
+    loop {
+        let outgoing = network.tick()?;
+        if let Some(&packet) = outgoing
+            .iter()
+            .find(|packet| packet.destination == NAT_ADDRESS)
+        {
+            return Ok(packet);
+        }
+    }
+}
+
+/// The first Y value the NAT sends to computer 0 twice in a row
+fn first_repeated_nat_y(program: &[i64]) -> Result<Option<i64>, IntcodeError> {
+    let mut network = Scheduler::boot(program, COMPUTERS);
+    network.set_nat(NAT_ADDRESS, LastPacketNat::new());
+    Ok(network.run_until_repeated_nat_y()?.map(|packet| packet.y))
+}
+
+/// The first packet the NAT receives, and the first value it wakes the network with twice
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        first_nat_packet(program.as_slice()).unwrap().y.to_string()
+    }
+
+    fn part2(program: &Self::Input) -> String {
+        first_repeated_nat_y(program.as_slice())
+            .unwrap()
+            .unwrap()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use intcode::asm::assemble;
+
+    /// Computer 0 sends (3, 42) to the NAT after booting; after that, it passes every packet
+    /// it receives on to the NAT
+    fn program() -> Vec<i64> {
+        assemble(
+            "
+                in [address]
+                jnz [address], poll
+                out 255
+                out 3
+                out 42
+            poll:
+                in [x]
+                eq [x], -1, [empty]
+                jnz [empty], poll
+                in [y]
+                out 255
+                out [x]
+                out [y]
+                jz 0, poll
+            address: .data 0
+            x: .data 0
+            y: .data 0
+            empty: .data 0
+            ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_first_nat_packet() {
+        assert_eq!(
+            first_nat_packet(&program()),
+            Ok(Packet {
+                destination: 255,
+                x: 3,
+                y: 42
+            })
+        );
+    }
+
+    #[test]
+    fn test_first_repeated_nat_y() {
+        assert_eq!(first_repeated_nat_y(&program()), Ok(Some(42)));
+    }
+}
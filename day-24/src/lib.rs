@@ -0,0 +1,187 @@
+//! Day 24: Planet of Discord
+
+use aoc_utils::{find_cycle, Puzzle};
+use std::collections::HashMap;
+
+/// The side of the square grid
+const SIZE: usize = 5;
+
+/// The middle tile, which in recursive grids holds the next level in
+const CENTER: usize = 12;
+
+/// A grid, with one bit per tile that's set if there's a bug there, row by row from the top
+/// left, so a grid's biodiversity rating is the grid itself
+pub type Grid = u32;
+
+fn parse(input: &str) -> Grid {
+    input
+        .chars()
+        .filter(|c| *c == '#' || *c == '.')
+        .enumerate()
+        .fold(
+            0,
+            |grid, (tile, c)| {
+                if c == '#' {
+                    grid | 1 << tile
+                } else {
+                    grid
+                }
+            },
+        )
+}
+
+/// The tiles next to each tile, on a single flat grid
+fn flat_neighbors(tile: usize) -> Vec<usize> {
+    let (x, y) = (tile % SIZE, tile / SIZE);
+    let mut neighbors = Vec::with_capacity(4);
+    if x > 0 {
+        neighbors.push(tile - 1);
+    }
+    if x < SIZE - 1 {
+        neighbors.push(tile + 1);
+    }
+    if y > 0 {
+        neighbors.push(tile - SIZE);
+    }
+    if y < SIZE - 1 {
+        neighbors.push(tile + SIZE);
+    }
+    neighbors
+}
+
+/// Whether a tile has a bug after a minute, given how many bugs are next to it
+fn lives(bug: bool, neighbors: u32) -> bool {
+    neighbors == 1 || (!bug && neighbors == 2)
+}
+
+fn step(grid: Grid) -> Grid {
+    (0..SIZE * SIZE).fold(0, |next, tile| {
+        let bug = grid & 1 << tile != 0;
+        let neighbors = flat_neighbors(tile)
+            .into_iter()
+            .filter(|&n| grid & 1 << n != 0)
+            .count() as u32;
+        if lives(bug, neighbors) {
+            next | 1 << tile
+        } else {
+            next
+        }
+    })
+}
+
+/// The biodiversity rating of the first layout to appear twice
+fn first_repeat(grid: Grid) -> Grid {
+    find_cycle(grid, |&grid| step(grid)).state
+}
+
+/// The tiles next to each tile in recursive grids, as the change in level and the tile
+///
+/// Level -1 is the grid around this one, and level +1 is the grid inside its center.
+fn recursive_neighbors(tile: usize) -> Vec<(i32, usize)> {
+    let (x, y) = (tile % SIZE, tile / SIZE);
+    let mut neighbors: Vec<(i32, usize)> = flat_neighbors(tile)
+        .into_iter()
+        .filter(|&n| n != CENTER)
+        .map(|n| (0, n))
+        .collect();
+
+    // The edges of this grid touch the tiles around the center of the grid outside it
+    if x == 0 {
+        neighbors.push((-1, CENTER - 1));
+    }
+    if x == SIZE - 1 {
+        neighbors.push((-1, CENTER + 1));
+    }
+    if y == 0 {
+        neighbors.push((-1, CENTER - SIZE));
+    }
+    if y == SIZE - 1 {
+        neighbors.push((-1, CENTER + SIZE));
+    }
+
+    // The tiles around the center touch a whole edge of the grid inside
+    let edge: Vec<usize> = match tile {
+        t if t == CENTER - SIZE => (0..SIZE).collect(),
+        t if t == CENTER + SIZE => (0..SIZE).map(|x| SIZE * (SIZE - 1) + x).collect(),
+        t if t == CENTER - 1 => (0..SIZE).map(|y| y * SIZE).collect(),
+        t if t == CENTER + 1 => (0..SIZE).map(|y| y * SIZE + SIZE - 1).collect(),
+        _ => Vec::new(),
+    };
+    neighbors.extend(edge.into_iter().map(|n| (1, n)));
+    neighbors
+}
+
+/// The number of bugs after `minutes` minutes, starting from one grid in infinitely nested ones
+fn recursive_bugs(grid: Grid, minutes: usize) -> u32 {
+    let neighbors: Vec<Vec<(i32, usize)>> = (0..SIZE * SIZE).map(recursive_neighbors).collect();
+    let mut levels: HashMap<i32, Grid> = HashMap::new();
+    levels.insert(0, grid);
+
+    for _ in 0..minutes {
+        let min = levels.keys().min().unwrap() - 1;
+        let max = levels.keys().max().unwrap() + 1;
+        let bug =
+            |level: i32, tile: usize| levels.get(&level).copied().unwrap_or(0) & 1 << tile != 0;
+
+        let mut next = HashMap::new();
+        for level in min..=max {
+            let mut grid = 0;
+            for tile in (0..SIZE * SIZE).filter(|&tile| tile != CENTER) {
+                let count = neighbors[tile]
+                    .iter()
+                    .filter(|&&(change, n)| bug(level + change, n))
+                    .count() as u32;
+                if lives(bug(level, tile), count) {
+                    grid |= 1 << tile;
+                }
+            }
+            if grid != 0 || levels.contains_key(&level) {
+                next.insert(level, grid);
+            }
+        }
+        levels = next;
+    }
+
+    levels.values().map(|grid| grid.count_ones()).sum()
+}
+
+/// The biodiversity of the first layout to repeat, and the bugs after 200 recursive minutes
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Self::Input {
+        parse(input)
+    }
+
+    fn part1(grid: &Self::Input) -> String {
+        first_repeat(*grid).to_string()
+    }
+
+    fn part2(grid: &Self::Input) -> String {
+        recursive_bugs(*grid, 200).to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "....#\n#..#.\n#..##\n..#..\n#....\n";
+
+    #[test]
+    fn test_first_repeat() {
+        let grid = parse(EXAMPLE);
+        assert_eq!(step(grid), parse("#..#.\n####.\n###.#\n##.##\n.##..\n"));
+        assert_eq!(first_repeat(grid), 2129920);
+    }
+
+    #[test]
+    fn test_recursive_bugs() {
+        assert_eq!(recursive_neighbors(18).len(), 4);
+        assert_eq!(recursive_neighbors(13).len(), 8);
+        assert_eq!(recursive_neighbors(0).len(), 4);
+        assert_eq!(recursive_bugs(parse(EXAMPLE), 10), 99);
+    }
+}
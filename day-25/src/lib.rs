@@ -0,0 +1,414 @@
+//! Play the text adventure aboard Santa's ship, by hand or automatically
+//!
+//! The [`Explorer`] walks every room it can reach, picking up every item that isn't known to
+//! be dangerous, and then stands at the security checkpoint trying combinations of items until
+//! the pressure-sensitive floor lets it through, which reveals the password.
+
+use aoc_utils::Puzzle;
+use intcode::memory::CopyOnWriteMemory;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Items that end the game, or make it impossible to go on, when picked up
+const DANGEROUS_ITEMS: &[&str] = &[
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+/// The room with the pressure-sensitive floor behind it
+const CHECKPOINT: &str = "Security Checkpoint";
+
+/// The most instructions the game may take to answer a command, to catch infinite loops
+const STEPS_PER_COMMAND: usize = 10_000_000;
+
+/// An error from playing the game
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdventureError {
+    Intcode(IntcodeError),
+    /// The game stopped answering commands
+    Stuck,
+    /// The game said something that isn't a room
+    UnexpectedOutput(String),
+    /// Every room has been explored, and there's no security checkpoint
+    NoCheckpoint,
+    /// No combination of items gets past the checkpoint
+    NoCombination,
+}
+
+impl fmt::Display for AdventureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdventureError::Intcode(error) => write!(f, "the game failed: {:?}", error),
+            AdventureError::Stuck => write!(f, "the game stopped answering"),
+            AdventureError::UnexpectedOutput(output) => {
+                write!(f, "the game said something unexpected: {:?}", output)
+            }
+            AdventureError::NoCheckpoint => write!(f, "there's no security checkpoint"),
+            AdventureError::NoCombination => {
+                write!(f, "no combination of items gets past the checkpoint")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdventureError {}
+
+/// Something that plays the game: takes commands, and answers with what happened
+pub trait Adventure {
+    /// Send a command, or nothing to see the first room, and get what the game says back
+    fn send(&mut self, command: Option<&str>) -> Result<String, AdventureError>;
+}
+
+/// The game, run by its intcode program
+pub struct IntcodeAdventure {
+    process: IntcodeProcess<i64, CopyOnWriteMemory<i64>>,
+}
+
+impl IntcodeAdventure {
+    pub fn new(program: &Program) -> Self {
+        IntcodeAdventure {
+            process: IntcodeProcess::new(program),
+        }
+    }
+}
+
+impl Adventure for IntcodeAdventure {
+    fn send(&mut self, command: Option<&str>) -> Result<String, AdventureError> {
+        if let Some(command) = command {
+            self.process.add_ascii_input(&format!("{}\n", command));
+        }
+        self.process.preempt_after(STEPS_PER_COMMAND);
+        let result = self.process.run();
+        self.process.cancel_preemption();
+
+        match result {
+            Err(IntcodeError::NoInputAvailable) | Err(IntcodeError::CatchFire) => {}
+            Err(IntcodeError::Preempted) => return Err(AdventureError::Stuck),
+            Err(error) => return Err(AdventureError::Intcode(error)),
+            Ok(()) => {}
+        }
+        Ok(self
+            .process
+            .drain_outputs()
+            .map(|c| c as u8 as char)
+            .collect())
+    }
+}
+
+/// A room, as the game describes it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    pub name: String,
+    pub doors: Vec<String>,
+    pub items: Vec<String>,
+}
+
+impl Room {
+    /// Read the last room described in some output
+    pub fn parse(output: &str) -> Option<Self> {
+        let start = output.rfind("== ")?;
+        let mut lines = output[start..].lines();
+        let name = lines
+            .next()?
+            .trim_start_matches("== ")
+            .trim_end_matches(" ==")
+            .to_string();
+
+        let mut doors = Vec::new();
+        let mut items = Vec::new();
+        let mut list = None;
+        for line in lines {
+            match line {
+                "Doors here lead:" => list = Some(&mut doors),
+                "Items here:" => list = Some(&mut items),
+                _ => match (line.strip_prefix("- "), list.as_mut()) {
+                    (Some(entry), Some(list)) => list.push(entry.to_string()),
+                    _ => list = None,
+                },
+            }
+        }
+
+        Some(Room { name, doors, items })
+    }
+}
+
+fn opposite(direction: &str) -> &'static str {
+    match direction {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        "west" => "east",
+        _ => panic!("Unknown direction {:?}", direction),
+    }
+}
+
+/// Plays the game automatically
+pub struct Explorer<A> {
+    adventure: A,
+    visited: HashSet<String>,
+    inventory: Vec<String>,
+    /// The way from the first room to the checkpoint, and the way from there to the floor
+    checkpoint: Option<(Vec<String>, String)>,
+}
+
+impl<A: Adventure> Explorer<A> {
+    pub fn new(adventure: A) -> Self {
+        Explorer {
+            adventure,
+            visited: HashSet::new(),
+            inventory: Vec::new(),
+            checkpoint: None,
+        }
+    }
+
+    /// Explore the ship, get past the checkpoint, and return the password
+    pub fn solve(&mut self) -> Result<String, AdventureError> {
+        let output = self.adventure.send(None)?;
+        let room = Room::parse(&output).ok_or(AdventureError::UnexpectedOutput(output))?;
+        self.explore(room, None, &mut Vec::new())?;
+
+        let (path, floor) = self
+            .checkpoint
+            .clone()
+            .ok_or(AdventureError::NoCheckpoint)?;
+        for direction in &path {
+            self.send(direction)?;
+        }
+        self.try_combinations(&floor)
+    }
+
+    pub fn inventory(&self) -> &[String] {
+        &self.inventory
+    }
+
+    fn send(&mut self, command: &str) -> Result<String, AdventureError> {
+        self.adventure.send(Some(command))
+    }
+
+    /// Visit a room and everything past it, picking up items on the way, and come back
+    fn explore(
+        &mut self,
+        room: Room,
+        back: Option<&str>,
+        path: &mut Vec<String>,
+    ) -> Result<(), AdventureError> {
+        self.visited.insert(room.name.clone());
+        for item in &room.items {
+            if !DANGEROUS_ITEMS.contains(&item.as_str()) {
+                self.send(&format!("take {}", item))?;
+                self.inventory.push(item.clone());
+            }
+        }
+
+        if room.name == CHECKPOINT {
+            // The door that isn't the way back leads to the floor, which throws the droid back
+            // here until it carries the right items
+            let floor = room.doors.iter().find(|&door| Some(door.as_str()) != back);
+            let floor = floor.ok_or(AdventureError::NoCheckpoint)?;
+            self.checkpoint = Some((path.clone(), floor.clone()));
+            return Ok(());
+        }
+
+        for door in &room.doors {
+            if Some(door.as_str()) == back {
+                continue;
+            }
+            let output = self.send(door)?;
+            let next = Room::parse(&output).ok_or(AdventureError::UnexpectedOutput(output))?;
+            if !self.visited.contains(&next.name) {
+                path.push(door.clone());
+                self.explore(next, Some(opposite(door)), path)?;
+                path.pop();
+            }
+            self.send(opposite(door))?;
+        }
+        Ok(())
+    }
+
+    /// Try every combination of items on the floor, changing one item at a time
+    fn try_combinations(&mut self, floor: &str) -> Result<String, AdventureError> {
+        let items = self.inventory.clone();
+        for item in &items {
+            self.send(&format!("drop {}", item))?;
+        }
+
+        let mut carrying = 0u64;
+        for n in 0u64..1 << items.len() {
+            // Gray codes differ from the one before in a single bit
+            let wanted = n ^ (n >> 1);
+            for (index, item) in items.iter().enumerate() {
+                let bit = 1 << index;
+                if (wanted ^ carrying) & bit != 0 {
+                    let verb = if wanted & bit != 0 { "take" } else { "drop" };
+                    self.send(&format!("{} {}", verb, item))?;
+                }
+            }
+            carrying = wanted;
+
+            let output = self.send(floor)?;
+            if !output.contains("Alert!") {
+                return password(&output).ok_or(AdventureError::UnexpectedOutput(output));
+            }
+        }
+        Err(AdventureError::NoCombination)
+    }
+}
+
+/// The password the game gives when the droid gets past the checkpoint
+fn password(output: &str) -> Option<String> {
+    let start = output.find("typing ")? + "typing ".len();
+    let digits: String = output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits)
+    }
+}
+
+/// The password for the airlock
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Self::Input {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Input) -> String {
+        let mut explorer = Explorer::new(IntcodeAdventure::new(program));
+        explorer.solve().unwrap()
+    }
+
+    /// There's no second puzzle on the last day; its star comes from finishing all the others
+    fn part2(_: &Self::Input) -> String {
+        String::from("Merry Christmas")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HULL_BREACH: &str = "\n\n\n== Hull Breach ==\nYou got in through a hole in the floor \
+        here.\n\nDoors here lead:\n- north\n- east\n\nItems here:\n- book\n\nCommand?\n";
+
+    #[test]
+    fn test_parse_room() {
+        assert_eq!(
+            Room::parse(HULL_BREACH),
+            Some(Room {
+                name: "Hull Breach".into(),
+                doors: vec!["north".into(), "east".into()],
+                items: vec!["book".into()],
+            })
+        );
+        assert_eq!(Room::parse("You can't go that way.\n\nCommand?\n"), None);
+        assert_eq!(
+            password("Oh, hello! You should be able to get in by typing 1234 on the keypad"),
+            Some("1234".into())
+        );
+    }
+
+    /// A small ship: the hull breach, with the kitchen to the north and the checkpoint to the
+    /// east. The floor only lets droids through that carry exactly the book and the mug.
+    struct Ship {
+        room: &'static str,
+        items: Vec<(&'static str, &'static str)>,
+        carrying: Vec<&'static str>,
+    }
+
+    impl Ship {
+        fn describe(&self, room: &str) -> String {
+            let doors: &[&str] = match room {
+                "Hull Breach" => &["north", "east"],
+                "Kitchen" => &["south"],
+                _ => &["west", "north"],
+            };
+            let mut output = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", room);
+            for door in doors {
+                output.push_str(&format!("- {}\n", door));
+            }
+            let items: Vec<_> = self.items.iter().filter(|(r, _)| *r == room).collect();
+            if !items.is_empty() {
+                output.push_str("\nItems here:\n");
+                for (_, item) in items {
+                    output.push_str(&format!("- {}\n", item));
+                }
+            }
+            output.push_str("\nCommand?\n");
+            output
+        }
+    }
+
+    impl Adventure for Ship {
+        fn send(&mut self, command: Option<&str>) -> Result<String, AdventureError> {
+            let command = match command {
+                Some(command) => command,
+                None => return Ok(self.describe(self.room)),
+            };
+            if let Some(item) = command.strip_prefix("take ") {
+                let index = self
+                    .items
+                    .iter()
+                    .position(|&(r, i)| r == self.room && i == item);
+                let (_, item) = self.items.remove(index.expect("no such item"));
+                self.carrying.push(item);
+                return Ok(format!("\nYou take the {}.\n\nCommand?\n", item));
+            }
+            if let Some(item) = command.strip_prefix("drop ") {
+                let index = self.carrying.iter().position(|&i| i == item);
+                let item = self.carrying.remove(index.expect("not carrying it"));
+                self.items.push((self.room, item));
+                return Ok(format!("\nYou drop the {}.\n\nCommand?\n", item));
+            }
+
+            self.room = match (self.room, command) {
+                ("Hull Breach", "north") => "Kitchen",
+                ("Hull Breach", "east") => CHECKPOINT,
+                ("Kitchen", "south") | (CHECKPOINT, "west") => "Hull Breach",
+                (CHECKPOINT, "north") => {
+                    let mut carrying = self.carrying.clone();
+                    carrying.sort_unstable();
+                    if carrying == ["book", "mug"] {
+                        return Ok("\n\n\n== Pressure-Sensitive Floor ==\nYou should be able \
+                            to get in by typing 1234 on the keypad at the main airlock.\n"
+                            .into());
+                    }
+                    return Ok(format!(
+                        "\n\n\n== Pressure-Sensitive Floor ==\nA loud voice says \"Alert! \
+                         Droids on this ship are heavier than the detected value!\"\n{}",
+                        self.describe(CHECKPOINT)
+                    ));
+                }
+                _ => return Ok("\nYou can't go that way.\n\nCommand?\n".into()),
+            };
+            Ok(self.describe(self.room))
+        }
+    }
+
+    #[test]
+    fn test_explorer() {
+        let ship = Ship {
+            room: "Hull Breach",
+            items: vec![
+                ("Hull Breach", "book"),
+                ("Kitchen", "mug"),
+                ("Kitchen", "infinite loop"),
+                ("Kitchen", "coin"),
+                (CHECKPOINT, "shell"),
+            ],
+            carrying: Vec::new(),
+        };
+        let mut explorer = Explorer::new(ship);
+        assert_eq!(explorer.solve(), Ok("1234".into()));
+        assert_eq!(explorer.inventory(), &["book", "mug", "coin", "shell"]);
+    }
+}
@@ -0,0 +1,23 @@
+use day_25::Day25;
+use intcode::terminal::run_interactive;
+use intcode::{IntcodeProcess, Program};
+use std::env;
+use std::fs;
+use std::io;
+
+const USAGE: &str = "Usage: day-25 < input, or day-25 --play <input>";
+
+fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        None => aoc_utils::puzzle::run::<Day25>(),
+        Some("--play") => {
+            let input = args.next().expect(USAGE);
+            let program: Program = fs::read_to_string(input).unwrap().parse().unwrap();
+            let mut process = IntcodeProcess::new(&program);
+            let stdin = io::stdin();
+            run_interactive(&mut process, stdin.lock(), io::stdout(), None).unwrap();
+        }
+        Some(_) => panic!("{}", USAGE),
+    }
+}
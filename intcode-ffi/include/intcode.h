@@ -0,0 +1,109 @@
+#ifndef INTCODE_H
+#define INTCODE_H
+
+/* Generated by cbindgen from intcode-ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The state of a process after it was stepped or run
+ */
+typedef enum IntcodeStatus {
+  /**
+   * The process executed an instruction and can keep going
+   */
+  INTCODE_STATUS_RUNNING = 0,
+  /**
+   * The process produced an output, which can be read with `intcode_pop_output`
+   */
+  INTCODE_STATUS_OUTPUT = 1,
+  /**
+   * The process needs more input before it can continue
+   */
+  INTCODE_STATUS_NEEDS_INPUT = 2,
+  /**
+   * The process halted
+   */
+  INTCODE_STATUS_HALTED = 3,
+  /**
+   * The process crashed, for example by segfaulting or hitting an unknown instruction
+   */
+  INTCODE_STATUS_ERROR = -1,
+} IntcodeStatus;
+
+/**
+ * An intcode process, along with the outputs that haven't been popped yet
+ */
+typedef struct IntcodeProcess IntcodeProcess;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a process from a program of `len` values
+ *
+ * The program is copied, so the caller keeps ownership of it. The returned process must be
+ * released with `intcode_destroy`.
+ *
+ * # Safety
+ *
+ * `program` must point to at least `len` readable values, or be null if `len` is zero.
+ */
+struct IntcodeProcess *intcode_create(const int64_t *program, uintptr_t len);
+
+/**
+ * Release a process created with `intcode_create`
+ *
+ * # Safety
+ *
+ * `process` must have come from `intcode_create` and not already been destroyed, or be null.
+ */
+void intcode_destroy(struct IntcodeProcess *process);
+
+/**
+ * Execute a single instruction
+ *
+ * # Safety
+ *
+ * `process` must be a live process from `intcode_create`.
+ */
+enum IntcodeStatus intcode_step(struct IntcodeProcess *process);
+
+/**
+ * Execute instructions until the process halts, needs input, or crashes
+ *
+ * # Safety
+ *
+ * `process` must be a live process from `intcode_create`.
+ */
+enum IntcodeStatus intcode_run(struct IntcodeProcess *process);
+
+/**
+ * Add a value to the process's input queue
+ *
+ * # Safety
+ *
+ * `process` must be a live process from `intcode_create`.
+ */
+void intcode_push_input(struct IntcodeProcess *process, int64_t value);
+
+/**
+ * Take the oldest output that hasn't been popped yet
+ *
+ * Returns false, leaving `value` untouched, if there are no outputs waiting.
+ *
+ * # Safety
+ *
+ * `process` must be a live process from `intcode_create`, and `value` must be writable.
+ */
+bool intcode_pop_output(struct IntcodeProcess *process, int64_t *value);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* INTCODE_H */
@@ -0,0 +1,188 @@
+//! A C-compatible API for embedding the intcode VM in other languages
+//!
+//! The header is checked in at `include/intcode.h`. After changing the API, regenerate it from
+//! this directory with:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --crate intcode-ffi --output include/intcode.h
+//! ```
+//!
+//! A typical session:
+//!
+//! ```c
+//! IntcodeProcess *process = intcode_create(program, program_len);
+//! intcode_push_input(process, 1);
+//! while (intcode_run(process) != INTCODE_STATUS_HALTED) { /* ... */ }
+//! int64_t value;
+//! while (intcode_pop_output(process, &value)) { printf("%lld\n", value); }
+//! intcode_destroy(process);
+//! ```
+
+use intcode::IntcodeError;
+use std::collections::VecDeque;
+use std::slice;
+
+/// The state of a process after it was stepped or run
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IntcodeStatus {
+    /// The process executed an instruction and can keep going
+    Running = 0,
+    /// The process produced an output, which can be read with `intcode_pop_output`
+    Output = 1,
+    /// The process needs more input before it can continue
+    NeedsInput = 2,
+    /// The process halted
+    Halted = 3,
+    /// The process crashed, for example by segfaulting or hitting an unknown instruction
+    Error = -1,
+}
+
+/// An intcode process, along with the outputs that haven't been popped yet
+pub struct IntcodeProcess {
+    process: intcode::IntcodeProcess,
+    outputs: VecDeque<i64>,
+}
+
+impl IntcodeProcess {
+    fn step(&mut self) -> IntcodeStatus {
+        let status = match self.process.step() {
+            Ok(Some(_)) => IntcodeStatus::Output,
+            Ok(None) => IntcodeStatus::Running,
+            Err(IntcodeError::NoInputAvailable) => IntcodeStatus::NeedsInput,
+            Err(IntcodeError::CatchFire) => IntcodeStatus::Halted,
+            Err(_) => IntcodeStatus::Error,
+        };
+        self.outputs.extend(self.process.drain_outputs());
+        status
+    }
+}
+
+/// Create a process from a program of `len` values
+///
+/// The program is copied, so the caller keeps ownership of it. The returned process must be
+/// released with `intcode_destroy`.
+///
+/// # Safety
+///
+/// `program` must point to at least `len` readable values, or be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_create(program: *const i64, len: usize) -> *mut IntcodeProcess {
+    let program = if len == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(program, len).to_vec()
+    };
+
+    Box::into_raw(Box::new(IntcodeProcess {
+        process: intcode::IntcodeProcess::from_vec(program),
+        outputs: VecDeque::new(),
+    }))
+}
+
+/// Release a process created with `intcode_create`
+///
+/// # Safety
+///
+/// `process` must have come from `intcode_create` and not already been destroyed, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_destroy(process: *mut IntcodeProcess) {
+    if !process.is_null() {
+        drop(Box::from_raw(process));
+    }
+}
+
+/// Execute a single instruction
+///
+/// # Safety
+///
+/// `process` must be a live process from `intcode_create`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_step(process: *mut IntcodeProcess) -> IntcodeStatus {
+    (*process).step()
+}
+
+/// Execute instructions until the process halts, needs input, or crashes
+///
+/// # Safety
+///
+/// `process` must be a live process from `intcode_create`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_run(process: *mut IntcodeProcess) -> IntcodeStatus {
+    let process = &mut *process;
+    loop {
+        match process.step() {
+            IntcodeStatus::Running | IntcodeStatus::Output => {}
+            status => return status,
+        }
+    }
+}
+
+/// Add a value to the process's input queue
+///
+/// # Safety
+///
+/// `process` must be a live process from `intcode_create`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_push_input(process: *mut IntcodeProcess, value: i64) {
+    (*process).process.add_input(value);
+}
+
+/// Take the oldest output that hasn't been popped yet
+///
+/// Returns false, leaving `value` untouched, if there are no outputs waiting.
+///
+/// # Safety
+///
+/// `process` must be a live process from `intcode_create`, and `value` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_pop_output(process: *mut IntcodeProcess, value: *mut i64) -> bool {
+    match (*process).outputs.pop_front() {
+        Some(output) => {
+            *value = output;
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ffi() {
+        let program = [3, 9, 4, 9, 104, 7, 99, 0, 0, 0];
+        let mut value = 0;
+
+        unsafe {
+            let process = intcode_create(program.as_ptr(), program.len());
+            assert_eq!(intcode_run(process), IntcodeStatus::NeedsInput);
+            assert!(!intcode_pop_output(process, &mut value));
+
+            intcode_push_input(process, 5);
+            assert_eq!(intcode_step(process), IntcodeStatus::Running);
+            assert_eq!(intcode_step(process), IntcodeStatus::Output);
+            assert!(intcode_pop_output(process, &mut value));
+            assert_eq!(value, 5);
+
+            assert_eq!(intcode_run(process), IntcodeStatus::Halted);
+            assert!(intcode_pop_output(process, &mut value));
+            assert_eq!(value, 7);
+            assert!(!intcode_pop_output(process, &mut value));
+
+            intcode_destroy(process);
+        }
+    }
+
+    #[test]
+    fn test_ffi_error() {
+        let program = [42];
+
+        unsafe {
+            let process = intcode_create(program.as_ptr(), program.len());
+            assert_eq!(intcode_run(process), IntcodeStatus::Error);
+            intcode_destroy(process);
+        }
+    }
+}
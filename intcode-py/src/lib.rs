@@ -0,0 +1,133 @@
+//! Python bindings for the intcode VM
+//!
+//! Build with `maturin develop --features extension-module`, then:
+//!
+//! ```python
+//! from intcode_py import IntcodeProcess
+//! process = IntcodeProcess([3, 0, 4, 0, 99])
+//! process.add_input(5)
+//! process.run_to_output()  # 5
+//! ```
+
+use intcode::{IntcodeError, IntcodeProcess as Process};
+use pyo3::exceptions::{PyIndexError, PyRuntimeError};
+use pyo3::prelude::*;
+
+/// An intcode process, wrapping the Rust VM
+#[pyclass(name = "IntcodeProcess", unsendable)]
+struct IntcodeProcess {
+    inner: Process,
+}
+
+#[pymethods]
+impl IntcodeProcess {
+    #[new]
+    fn new(program: Vec<i64>) -> Self {
+        IntcodeProcess {
+            inner: Process::from_vec(program),
+        }
+    }
+
+    /// Create a process from comma-separated program text
+    #[staticmethod]
+    fn from_text(text: &str) -> PyResult<Self> {
+        let program = text
+            .trim()
+            .split(',')
+            .map(|s| s.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(IntcodeProcess::new(program))
+    }
+
+    /// Add a value to the input queue
+    fn add_input(&mut self, value: i64) {
+        self.inner.add_input(value);
+    }
+
+    /// Add a string to the input queue, one character at a time
+    fn add_ascii_input(&mut self, input: &str) {
+        self.inner.add_ascii_input(input);
+    }
+
+    /// Run until the next output and return it, or None if the process halted
+    fn run_to_output(&mut self) -> PyResult<Option<i64>> {
+        match self.inner.run_to_output() {
+            Ok(value) => Ok(Some(value)),
+            Err(IntcodeError::CatchFire) => Ok(None),
+            Err(e) => Err(to_py_err(e)),
+        }
+    }
+
+    /// Run until the process halts, and return every output
+    fn run(&mut self) -> PyResult<Vec<i64>> {
+        match self.inner.run() {
+            Ok(()) | Err(IntcodeError::CatchFire) => Ok(self.inner.outputs().to_vec()),
+            Err(e) => Err(to_py_err(e)),
+        }
+    }
+
+    /// Get every output produced so far
+    fn outputs(&self) -> Vec<i64> {
+        self.inner.outputs().to_vec()
+    }
+
+    /// Get a copy of the memory
+    fn memory(&self) -> Vec<i64> {
+        self.inner.memory().to_vec()
+    }
+
+    /// Read the value at the given address
+    fn load(&self, address: i64) -> PyResult<i64> {
+        self.inner.load(address).map_err(to_py_err)
+    }
+
+    /// Write the value at the given address
+    fn store(&mut self, address: i64, value: i64) -> PyResult<()> {
+        self.inner.store(address, value).map_err(to_py_err)
+    }
+
+    /// The address of the next instruction
+    #[getter]
+    fn instruction_counter(&self) -> usize {
+        self.inner.instruction_counter()
+    }
+
+    /// The current relative base
+    #[getter]
+    fn relative_base(&self) -> i64 {
+        self.inner.relative_base()
+    }
+}
+
+fn to_py_err(error: IntcodeError) -> PyErr {
+    match error {
+        IntcodeError::SegfaultRead(address) | IntcodeError::SegfaultWrite(address) => {
+            PyIndexError::new_err(format!("segfault at {}", address))
+        }
+        e => PyRuntimeError::new_err(format!("{:?}", e)),
+    }
+}
+
+#[pymodule]
+fn intcode_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<IntcodeProcess>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_process() {
+        let mut process = IntcodeProcess::from_text("3,9,4,9,104,7,99,0,0,0").unwrap();
+        process.add_input(5);
+
+        assert_eq!(process.run_to_output().unwrap(), Some(5));
+        assert_eq!(process.load(9).unwrap(), 5);
+        assert_eq!(process.run().unwrap(), vec![5, 7]);
+        assert_eq!(process.run_to_output().unwrap(), None);
+        assert!(process.load(10).is_err());
+    }
+}
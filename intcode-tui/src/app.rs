@@ -0,0 +1,205 @@
+//! The state of the viewer, apart from how it's drawn
+
+use intcode::{IntcodeError, IntcodeProcess};
+use ratatui::crossterm::event::KeyCode;
+use std::time::Instant;
+
+/// The number of recently executed instructions shown above the current one
+pub const HISTORY: usize = 8;
+
+/// The number of memory cells on each row of the memory pane
+pub const CELLS_PER_ROW: usize = 8;
+
+/// The number of instructions executed between checks of the time
+const CHECK_INTERVAL: usize = 1024;
+
+/// The number of rows Page Up and Page Down scroll the memory pane
+const PAGE: usize = 16;
+
+/// Whether the process is running, and if not, why
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum State {
+    /// Running freely
+    Running,
+    /// Paused by the user
+    Paused,
+    /// Waiting for the user to type a line of input
+    WaitingForInput,
+    /// Stopped for good, with the reason
+    Stopped(String),
+}
+
+/// Everything the viewer shows
+pub struct App {
+    pub process: IntcodeProcess,
+    pub state: State,
+    pub steps: u64,
+    /// How outputs are shown: as ASCII text, or as numbers on their own lines
+    ascii: bool,
+    pub outputs: String,
+    pub input_line: String,
+    /// The first row of the memory pane
+    pub memory_scroll: usize,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(mut process: IntcodeProcess, ascii: bool) -> Self {
+        process.set_history_length(HISTORY);
+        App {
+            process,
+            state: State::Running,
+            steps: 0,
+            ascii,
+            outputs: String::new(),
+            input_line: String::new(),
+            memory_scroll: 0,
+            quit: false,
+        }
+    }
+
+    /// Run the process until the deadline, if it's running
+    pub fn run_until(&mut self, deadline: Instant) {
+        while self.state == State::Running {
+            for _ in 0..CHECK_INTERVAL {
+                self.step();
+                if self.state != State::Running {
+                    return;
+                }
+            }
+            if Instant::now() >= deadline {
+                return;
+            }
+        }
+    }
+
+    /// Execute one instruction
+    fn step(&mut self) {
+        match self.process.step() {
+            Ok(output) => {
+                self.steps += 1;
+                if let Some(output) = output {
+                    self.push_output(output);
+                }
+            }
+            Err(IntcodeError::NoInputAvailable) => self.state = State::WaitingForInput,
+            Err(IntcodeError::CatchFire) => self.state = State::Stopped(String::from("halted")),
+            Err(e) => self.state = State::Stopped(format!("{:?}", e)),
+        }
+    }
+
+    fn push_output(&mut self, output: i64) {
+        match output {
+            0..=127 if self.ascii => self.outputs.push(output as u8 as char),
+            _ => self.outputs.push_str(&format!("{}\n", output)),
+        }
+    }
+
+    /// Respond to a key press
+    pub fn handle_key(&mut self, key: KeyCode) {
+        if self.state == State::WaitingForInput {
+            match key {
+                KeyCode::Char(c) => self.input_line.push(c),
+                KeyCode::Backspace => {
+                    self.input_line.pop();
+                }
+                KeyCode::Enter => self.submit_input(),
+                KeyCode::Esc => self.quit = true,
+                _ => {}
+            }
+            return;
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') => match self.state {
+                State::Running => self.state = State::Paused,
+                State::Paused => self.state = State::Running,
+                _ => {}
+            },
+            KeyCode::Char('s') if self.state == State::Paused => {
+                self.step();
+                if self.state == State::Running {
+                    self.state = State::Paused;
+                }
+            }
+            KeyCode::Up => self.memory_scroll = self.memory_scroll.saturating_sub(1),
+            KeyCode::Down => self.memory_scroll += 1,
+            KeyCode::PageUp => self.memory_scroll = self.memory_scroll.saturating_sub(PAGE),
+            KeyCode::PageDown => self.memory_scroll += PAGE,
+            _ => {}
+        }
+    }
+
+    /// Hand the typed line to the process: as numbers if it's a list of them, or as ASCII
+    fn submit_input(&mut self) {
+        let line = std::mem::take(&mut self.input_line);
+        let numbers = line
+            .split(',')
+            .map(|value| value.trim().parse())
+            .collect::<Result<Vec<i64>, _>>();
+        match numbers {
+            Ok(numbers) => self.process.add_inputs(&numbers),
+            Err(_) => self.process.add_ascii_input(&format!("{}\n", line)),
+        }
+        if self.ascii {
+            self.outputs.push_str(&line);
+            self.outputs.push('\n');
+        }
+        self.state = State::Running;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn run(app: &mut App) {
+        app.run_until(Instant::now() + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_app() {
+        // Double two numbers
+        let program = vec![3, 11, 1002, 11, 2, 11, 4, 11, 1105, 1, 0, 0];
+        let mut app = App::new(IntcodeProcess::from_vec(program), false);
+        run(&mut app);
+        assert_eq!(app.state, State::WaitingForInput);
+
+        for key in "5, 7".chars() {
+            app.handle_key(KeyCode::Char(key));
+        }
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.state, State::Paused);
+        for _ in 0..4 {
+            app.handle_key(KeyCode::Char('s'));
+        }
+        assert_eq!(app.outputs, "10\n");
+        assert_eq!(app.steps, 4);
+
+        app.handle_key(KeyCode::Char(' '));
+        run(&mut app);
+        assert_eq!(app.outputs, "10\n14\n");
+        assert_eq!(app.state, State::WaitingForInput);
+        app.handle_key(KeyCode::Char('q'));
+        assert!(!app.quit);
+        app.handle_key(KeyCode::Esc);
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_ascii() {
+        // Echo a line
+        let program = vec![3, 7, 4, 7, 1105, 1, 0, 0];
+        let mut app = App::new(IntcodeProcess::from_vec(program), true);
+        run(&mut app);
+        for key in "hi".chars() {
+            app.handle_key(KeyCode::Char(key));
+        }
+        app.handle_key(KeyCode::Enter);
+        run(&mut app);
+        assert_eq!(app.outputs, "hi\nhi\n");
+    }
+}
@@ -0,0 +1,68 @@
+//! A live view of a running intcode program
+//!
+//! Shows the disassembly around the instruction counter, the registers, a scrolling view of
+//! memory, and the program's output while it runs, and can pause it and step through it.
+
+mod app;
+mod ui;
+
+use app::App;
+use intcode::{IntcodeProcess, Program};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::env;
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: intcode-tui <program> [--ascii] [--speed <steps per second>]";
+
+/// How long each frame is shown before the next one is drawn
+const FRAME: Duration = Duration::from_millis(33);
+
+fn main() {
+    let mut program_path = None;
+    let mut ascii = false;
+    let mut speed = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ascii" => ascii = true,
+            "--speed" => {
+                let steps = args.next().expect("--speed requires a number of steps");
+                speed = Some(steps.parse().expect(USAGE));
+            }
+            _ => program_path = Some(arg),
+        }
+    }
+
+    let program_path = program_path.expect(USAGE);
+    let program: Program = fs::read_to_string(program_path).unwrap().parse().unwrap();
+
+    let mut process = IntcodeProcess::from_vec(program.as_slice().to_vec());
+    process.set_steps_per_second(speed);
+    let mut app = App::new(process, ascii);
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    result.unwrap();
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        let deadline = Instant::now() + FRAME;
+        app.run_until(deadline);
+        while event::poll(deadline.saturating_duration_since(Instant::now()))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key.code);
+                }
+            }
+        }
+    }
+    Ok(())
+}
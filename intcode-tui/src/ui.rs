@@ -0,0 +1,141 @@
+//! Drawing the viewer
+
+use crate::app::{App, State, CELLS_PER_ROW};
+use intcode::asm::disassemble_instruction;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+
+const HELP: &str = " space pause/resume · s step · ↑ ↓ PgUp PgDn scroll memory · q quit ";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, outputs] =
+        Layout::vertical([Constraint::Min(10), Constraint::Length(10)]).areas(frame.area());
+    let [code, side] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+    let [registers, memory] =
+        Layout::vertical([Constraint::Length(5), Constraint::Min(3)]).areas(side);
+
+    draw_code(frame, app, code);
+    draw_registers(frame, app, registers);
+    draw_memory(frame, app, memory);
+    draw_outputs(frame, app, outputs);
+}
+
+/// Disassemble the instruction at `address`, or show the value there as data
+fn disassemble(memory: &[i64], address: usize) -> (String, usize) {
+    match disassemble_instruction(memory, address) {
+        Some((line, len)) => (format!("{:>6}: {}", address, line), len),
+        None => match memory.get(address) {
+            Some(value) => (format!("{:>6}: .data {}", address, value), 1),
+            None => (format!("{:>6}: (past the end of memory)", address), 1),
+        },
+    }
+}
+
+/// The recently executed instructions, dimmed, then the current one and the ones after it
+fn draw_code(frame: &mut Frame, app: &App, area: Rect) {
+    let memory = app.process.memory();
+    let dim = Style::new().add_modifier(Modifier::DIM);
+    let mut lines: Vec<Line> = app
+        .process
+        .history()
+        .map(|address| Line::styled(disassemble(memory, address).0, dim))
+        .collect();
+
+    let mut address = app.process.instruction_counter();
+    let (text, len) = disassemble(memory, address);
+    lines.push(Line::styled(
+        text,
+        Style::new().add_modifier(Modifier::REVERSED),
+    ));
+    address += len;
+    while lines.len() < area.height as usize && address < memory.len() {
+        let (text, len) = disassemble(memory, address);
+        lines.push(Line::from(text));
+        address += len;
+    }
+
+    let block = Block::bordered().title(" Code ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_registers(frame: &mut Frame, app: &App, area: Rect) {
+    let state = match &app.state {
+        State::Running => String::from("running"),
+        State::Paused => String::from("paused"),
+        State::WaitingForInput => String::from("waiting for input"),
+        State::Stopped(reason) => format!("stopped: {}", reason),
+    };
+    let lines = vec![
+        Line::from(format!(
+            "IC {:<8} RB {:<8}",
+            app.process.instruction_counter(),
+            app.process.relative_base()
+        )),
+        Line::from(format!("steps {}", app.steps)),
+        Line::from(state),
+    ];
+
+    let block = Block::bordered().title(" Registers ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Rows of memory cells, with the instruction counter and relative base highlighted
+fn draw_memory(frame: &mut Frame, app: &App, area: Rect) {
+    let memory = app.process.memory();
+    let instruction_counter = app.process.instruction_counter();
+    let relative_base = app.process.relative_base();
+    let rows = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = (app.memory_scroll..app.memory_scroll + rows)
+        .map(|row| row * CELLS_PER_ROW)
+        .take_while(|&start| start < memory.len())
+        .map(|start| {
+            let mut spans = vec![format!("{:>6}:", start).into()];
+            let end = (start + CELLS_PER_ROW).min(memory.len());
+            for (address, value) in (start..end).zip(&memory[start..end]) {
+                let text = format!(" {:>6}", value);
+                let style = if address == instruction_counter {
+                    Style::new().add_modifier(Modifier::REVERSED)
+                } else if address as i64 == relative_base {
+                    Style::new().add_modifier(Modifier::UNDERLINED)
+                } else {
+                    Style::new()
+                };
+                spans.push(Span::styled(text, style));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let block = Block::bordered().title(" Memory ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// The last lines of output that fit, and the line being typed when the program wants input
+fn draw_outputs(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = area.height.saturating_sub(2) as usize;
+    let prompt = match app.state {
+        State::WaitingForInput => Some(format!("> {}_", app.input_line)),
+        _ => None,
+    };
+    let output_rows = rows.saturating_sub(prompt.is_some() as usize);
+
+    let mut lines: Vec<Line> = app
+        .outputs
+        .lines()
+        .rev()
+        .take(output_rows)
+        .map(|line| Line::from(line.to_string()))
+        .collect();
+    lines.reverse();
+    lines.extend(prompt.map(Line::from));
+
+    let block = Block::bordered()
+        .title(" Output ")
+        .title_bottom(Line::from(HELP));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
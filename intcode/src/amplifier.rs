@@ -0,0 +1,94 @@
+//! Chains of amplifiers, each running the same program, like day 7
+//!
+//! Each amplifier is given its phase setting as its first input. The first amplifier then gets
+//! an input signal of 0, and every other amplifier gets the output of the one before it.
+
+use crate::{IntcodeError, IntcodeProcess};
+
+/// A chain of amplifiers that all run the same program with their own phase setting
+#[derive(Debug, Clone)]
+pub struct AmplifierChain {
+    program: Vec<i64>,
+    phases: Vec<i64>,
+}
+
+impl AmplifierChain {
+    /// Create a chain with one amplifier per phase setting, in order
+    pub fn new(program: Vec<i64>, phases: &[i64]) -> Self {
+        AmplifierChain {
+            program,
+            phases: phases.to_vec(),
+        }
+    }
+
+    /// Pass the signal through every amplifier once, and return the last amplifier's output
+    pub fn run_once(&self) -> Result<i64, IntcodeError> {
+        let mut signal = 0;
+        for process in self.processes().iter_mut() {
+            process.add_input(signal);
+            signal = process.run_to_output()?;
+        }
+
+        Ok(signal)
+    }
+
+    /// Connect the last amplifier's output back to the first amplifier's input, and pass the
+    /// signal around the loop until the first amplifier halts
+    ///
+    /// Returns the last signal sent by the last amplifier. It is an error for any other
+    /// amplifier to halt first.
+    pub fn run_feedback(&self) -> Result<i64, IntcodeError> {
+        let mut processes = self.processes();
+        let mut signal = 0;
+        loop {
+            for (index, process) in processes.iter_mut().enumerate() {
+                process.add_input(signal);
+                match process.run_to_output() {
+                    Ok(output) => signal = output,
+                    Err(IntcodeError::CatchFire) if index == 0 => return Ok(signal),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+
+    fn processes(&self) -> Vec<IntcodeProcess> {
+        self.phases
+            .iter()
+            .map(|&phase| {
+                let mut process = IntcodeProcess::from_vec(self.program.clone());
+                process.add_input(phase);
+                process
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_once() {
+        let program = vec![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+
+        let chain = AmplifierChain::new(program, &[4, 3, 2, 1, 0]);
+
+        assert_eq!(chain.run_once(), Ok(43210));
+        assert_eq!(chain.run_once(), Ok(43210));
+    }
+
+    #[test]
+    fn test_run_feedback() {
+        let program = vec![
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ];
+
+        let chain = AmplifierChain::new(program, &[9, 8, 7, 6, 5]);
+
+        assert_eq!(chain.run_feedback(), Ok(139629729));
+    }
+}
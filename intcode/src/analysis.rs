@@ -0,0 +1,301 @@
+//! Static analysis of a program's control flow
+//!
+//! Starting at address 0, the analysis follows every path through the program to find which
+//! addresses hold reachable instructions, and which addresses those instructions write to. This
+//! shows which parts of a program are code and which are data, and whether the program ever
+//! writes to its own code, which decides whether it is safe to pre-decode or transpile.
+//!
+//! Jump targets and write addresses that come from memory or the relative base can't be known
+//! without running the program. Instructions that use them are listed separately, and when there
+//! are any, the analysis is incomplete.
+
+use crate::{Instruction, OutputParameter};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Range;
+
+/// The results of analyzing a program
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    len: usize,
+    instructions: BTreeSet<usize>,
+    code: BTreeSet<usize>,
+    writes: BTreeSet<usize>,
+    dynamic_jumps: Vec<usize>,
+    dynamic_writes: Vec<usize>,
+    invalid: Vec<usize>,
+}
+
+/// Analyze the program's control flow, starting at address 0
+pub fn analyze(program: &[i64]) -> Analysis {
+    let mut analysis = Analysis {
+        len: program.len(),
+        instructions: BTreeSet::new(),
+        code: BTreeSet::new(),
+        writes: BTreeSet::new(),
+        dynamic_jumps: Vec::new(),
+        dynamic_writes: Vec::new(),
+        invalid: Vec::new(),
+    };
+
+    let mut pending = vec![0];
+    while let Some(address) = pending.pop() {
+        if analysis.instructions.contains(&address) || analysis.invalid.contains(&address) {
+            continue;
+        }
+
+        let instruction = match program
+            .get(address)
+            .map(|&value| Instruction::decode(value))
+        {
+            Some(Ok(instruction)) => instruction,
+            _ => {
+                analysis.invalid.push(address);
+                analysis.code.insert(address);
+                continue;
+            }
+        };
+        let length = instruction.len();
+        if address + length > program.len() {
+            analysis.invalid.push(address);
+            analysis.code.insert(address);
+            continue;
+        }
+
+        analysis.instructions.insert(address);
+        analysis.code.extend(address..address + length);
+        let parameters = &program[address + 1..address + length];
+
+        match instruction.output() {
+            Some((index, OutputParameter::Position)) => match usize_address(parameters[index]) {
+                Some(target) => {
+                    analysis.writes.insert(target);
+                }
+                None => analysis.dynamic_writes.push(address),
+            },
+            Some((_, OutputParameter::Relative)) => analysis.dynamic_writes.push(address),
+            None => {}
+        }
+
+        match instruction.successors(parameters) {
+            Successors::Next => pending.push(address + length),
+            Successors::Halt => {}
+            Successors::Jump {
+                target,
+                can_fall_through,
+            } => {
+                if can_fall_through {
+                    pending.push(address + length);
+                }
+                match target.and_then(usize_address) {
+                    Some(target) => pending.push(target),
+                    None => analysis.dynamic_jumps.push(address),
+                }
+            }
+        }
+    }
+
+    analysis.dynamic_jumps.sort_unstable();
+    analysis.dynamic_writes.sort_unstable();
+    analysis.invalid.sort_unstable();
+    analysis
+}
+
+fn usize_address(value: i64) -> Option<usize> {
+    usize::try_from(value).ok()
+}
+
+/// Where control can go after an instruction
+enum Successors {
+    Next,
+    Halt,
+    Jump {
+        /// The target, if it is known without running the program
+        target: Option<i64>,
+        can_fall_through: bool,
+    },
+}
+
+impl Instruction {
+    /// The index of the instruction's output parameter and its mode, if it has one
+    fn output(&self) -> Option<(usize, OutputParameter)> {
+        match *self {
+            Instruction::Add(_, _, out)
+            | Instruction::Mul(_, _, out)
+            | Instruction::LessThan(_, _, out)
+            | Instruction::Equals(_, _, out) => Some((2, out)),
+            Instruction::Input(out) => Some((0, out)),
+            _ => None,
+        }
+    }
+
+    fn successors(&self, parameters: &[i64]) -> Successors {
+        use crate::InputParameter::Immediate;
+
+        match *self {
+            Instruction::JumpIfTrue(condition, target)
+            | Instruction::JumpIfFalse(condition, target) => {
+                let jump_if = matches!(self, Instruction::JumpIfTrue(..));
+                let target = match target {
+                    Immediate => Some(parameters[1]),
+                    _ => None,
+                };
+                match condition {
+                    // A constant condition either always jumps or never does
+                    Immediate if (parameters[0] != 0) == jump_if => Successors::Jump {
+                        target,
+                        can_fall_through: false,
+                    },
+                    Immediate => Successors::Next,
+                    _ => Successors::Jump {
+                        target,
+                        can_fall_through: true,
+                    },
+                }
+            }
+            Instruction::Halt => Successors::Halt,
+            _ => Successors::Next,
+        }
+    }
+}
+
+impl Analysis {
+    /// Get the addresses of every reachable instruction
+    pub fn instructions(&self) -> &BTreeSet<usize> {
+        &self.instructions
+    }
+
+    /// Get every address that is part of a reachable instruction, including its parameters
+    ///
+    /// Reachable addresses that don't hold a valid instruction count as code too, since a
+    /// program that writes there is probably generating the instruction at runtime.
+    pub fn code(&self) -> &BTreeSet<usize> {
+        &self.code
+    }
+
+    /// Get every address that a reachable instruction writes to directly
+    pub fn writes(&self) -> &BTreeSet<usize> {
+        &self.writes
+    }
+
+    /// Get the addresses of jump instructions whose target isn't known without running
+    pub fn dynamic_jumps(&self) -> &[usize] {
+        &self.dynamic_jumps[..]
+    }
+
+    /// Get the addresses of instructions that write to an address that isn't known without
+    /// running
+    pub fn dynamic_writes(&self) -> &[usize] {
+        &self.dynamic_writes[..]
+    }
+
+    /// Get the reachable addresses that don't hold a valid instruction
+    pub fn invalid(&self) -> &[usize] {
+        &self.invalid[..]
+    }
+
+    /// Get the ranges of the program that aren't part of any reachable instruction
+    pub fn unreachable(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for address in (0..self.len).filter(|address| !self.code.contains(address)) {
+            match ranges.last_mut() {
+                Some(range) if range.end == address => range.end += 1,
+                _ => ranges.push(address..address + 1),
+            }
+        }
+        ranges
+    }
+
+    /// Get the code addresses that are written to directly
+    pub fn modified_code(&self) -> Vec<usize> {
+        self.writes.intersection(&self.code).copied().collect()
+    }
+
+    /// Whether the program writes to its own code
+    ///
+    /// Only direct writes are considered; check `is_complete` too.
+    pub fn is_self_modifying(&self) -> bool {
+        self.writes.intersection(&self.code).next().is_some()
+    }
+
+    /// Whether every jump target and write address was known, so the analysis covers everything
+    /// the program can do
+    pub fn is_complete(&self) -> bool {
+        self.dynamic_jumps.is_empty() && self.dynamic_writes.is_empty()
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} reachable instructions covering {} of {} addresses",
+            self.instructions.len(),
+            self.code.len(),
+            self.len
+        )?;
+        for range in self.unreachable() {
+            writeln!(f, "unreachable: {}..{}", range.start, range.end)?;
+        }
+        for address in self.modified_code() {
+            writeln!(f, "code written: {}", address)?;
+        }
+        for address in &self.dynamic_jumps {
+            writeln!(f, "dynamic jump: {}", address)?;
+        }
+        for address in &self.dynamic_writes {
+            writeln!(f, "dynamic write: {}", address)?;
+        }
+        for address in &self.invalid {
+            writeln!(f, "invalid instruction: {}", address)?;
+        }
+        match (self.is_self_modifying(), self.is_complete()) {
+            (true, _) => writeln!(f, "self-modifying"),
+            (false, true) => writeln!(f, "not self-modifying"),
+            (false, false) => writeln!(f, "not self-modifying, as far as can be told"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_analyze() {
+        // Reads a number, outputs it until it counts down to 0, then halts. 11..13 is data and
+        // 13..15 is never reached.
+        let program = vec![3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0, 104, 1];
+        let analysis = analyze(&program);
+
+        assert_eq!(
+            analysis.instructions().iter().copied().collect::<Vec<_>>(),
+            vec![0, 2, 4, 8, 11]
+        );
+        assert_eq!(analysis.unreachable(), vec![12..15]);
+        assert_eq!(
+            analysis.writes().iter().copied().collect::<Vec<_>>(),
+            vec![12]
+        );
+        assert!(!analysis.is_self_modifying());
+        assert!(analysis.is_complete());
+    }
+
+    #[test]
+    fn test_analyze_self_modifying() {
+        // Overwrites the target of its own jump, which lands on a truncated instruction
+        let program = vec![1101, 0, 99, 6, 1106, 0, 7, 6, 7];
+        let analysis = analyze(&program);
+
+        assert_eq!(analysis.modified_code(), vec![6]);
+        assert!(analysis.is_self_modifying());
+        assert_eq!(analysis.invalid(), &[7]);
+
+        let program = vec![1105, 1, 3, 6, 8, 8, 21101, 0, 0, 0, 99];
+        let analysis = analyze(&program);
+        assert_eq!(analysis.dynamic_jumps(), &[3]);
+        assert_eq!(analysis.dynamic_writes(), &[6]);
+        assert!(!analysis.is_complete());
+    }
+}
@@ -0,0 +1,1089 @@
+//! Assemble and disassemble a textual form of intcode
+//!
+//! Each line holds one instruction, written as its mnemonic followed by comma separated
+//! operands, or a `.data` directive with raw values. Operands are written by mode:
+//!
+//! - immediate: `5`
+//! - position: `[5]`, the value at address 5
+//! - relative: `[rb+5]` or `[rb-5]`, the value at the relative base plus or minus 5
+//!
+//! Everything after a `;` is a comment.
+//!
+//! Anywhere a number is expected, an expression adding and subtracting numbers and symbols
+//! can be used instead, like `[buffer+2]`. Symbols are defined by:
+//!
+//! - labels: `loop:` at the start of a line names the address of what follows it
+//! - constants: `const SIZE = 10`, which may use constants defined earlier
+//!
+//! `$` is the address of the current instruction or data directive.
+//!
+//! With the `extensions` feature, the non-standard `div`, `mod`, and `rand` instructions from
+//! [`crate::extensions`] can be used too.
+//!
+//! Macros are defined with `macro name param, ...`, followed by their body and `endmacro`.
+//! Using a macro like an instruction inserts its body with the parameters replaced by the
+//! arguments. Labels inside a macro are defined again every time it's used, so avoid them and
+//! use `$` instead.
+//!
+//! Intcode has no stack, so the assembler builds one from the relative base, which points to the
+//! first free slot. Four macros are built in for it:
+//!
+//! - `push value`: store a value in `[rb]` and move the relative base up by one
+//! - `pop target`: copy `[rb-1]` to a target and move the relative base down by one, so a
+//!   target like `[rb+1]` refers to the relative base from before the pop
+//! - `call routine`: store the return address in `[rb]` and jump to a routine
+//! - `ret`: jump back to the return address in `[rb]`
+//!
+//! A call leaves the relative base where it is, so the caller puts arguments in `[rb+1]`,
+//! `[rb+2]`, and so on before calling, and the routine leaves its results in the same places.
+//! A routine may overwrite anything from `[rb]` up to `[rb+8]`, so the caller pushes anything
+//! else it needs first. A routine that calls others or pushes values moves the relative base
+//! past its return address and arguments first, and back again before returning.
+//!
+//! Modules can also be assembled separately into objects, and linked; see [`crate::link`].
+//!
+//! ```
+//! use intcode::asm::{assemble, disassemble};
+//!
+//! let program = assemble("in [9]\nmul [9], 2, [9]\nout [9]\nhalt\n.data 0").unwrap();
+//! assert_eq!(program, vec![3, 9, 1002, 9, 2, 9, 4, 9, 99, 0]);
+//! assert_eq!(assemble(&disassemble(&program)).unwrap(), program);
+//!
+//! let source = "
+//!     macro double x
+//!         mul x, 2, x
+//!     endmacro
+//!
+//!     in [value]
+//!     double [value]
+//!     out [value]
+//!     halt
+//! value: .data 0
+//! ";
+//! assert_eq!(assemble(source).unwrap(), program);
+//! ```
+
+use crate::analysis::analyze;
+use crate::{InputParameter, Instruction, OutputParameter};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Write};
+
+/// The most raw values the disassembler puts on one `.data` line
+const DATA_PER_LINE: usize = 8;
+
+/// The column the disassembler starts comments in
+const COMMENT_COLUMN: usize = 32;
+
+/// How deeply macros may use other macros, to catch macros that use themselves
+const MAX_MACRO_DEPTH: usize = 16;
+
+/// The macros every program can use, for a stack with the relative base as its pointer
+const BUILTIN_MACROS: &str = "
+macro push value
+    add value, 0, [rb]
+    arb 1
+endmacro
+macro pop target
+    add [rb-1], 0, target
+    arb -1
+endmacro
+macro call routine
+    add $+7, 0, [rb]
+    jnz 1, routine
+endmacro
+macro ret
+    jnz 1, [rb]
+endmacro
+";
+
+/// An error from assembling source that isn't valid
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AssembleError {
+    /// The line with the error, counting from 1
+    pub line: usize,
+    /// What is wrong with the line
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/// An assembled program, along with what's needed to relate it back to its source
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Assembly {
+    /// The assembled program
+    pub program: Vec<i64>,
+    /// The value of every label and constant
+    pub symbols: BTreeMap<String, i64>,
+    /// The source line of every instruction and data directive
+    pub source_map: SourceMap,
+}
+
+/// The source line that each part of an assembled program came from
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SourceMap {
+    /// The address each instruction or data directive starts at, and its line, in address order
+    entries: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    /// The line, counting from 1, of the instruction or data directive that covers `address`
+    ///
+    /// For lines that came from a macro, this is the line that used the macro.
+    pub fn line(&self, address: usize) -> Option<usize> {
+        let index = match self.entries.binary_search_by_key(&address, |&(a, _)| a) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        Some(self.entries[index].1)
+    }
+
+    /// The first address assembled from `line`, if the line produced anything
+    pub fn address(&self, line: usize) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|&&(_, l)| l == line)
+            .map(|&(address, _)| address)
+    }
+}
+
+/// Something that evaluates to a number: numbers and symbols added or subtracted together
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Expr {
+    terms: Vec<(bool, Term)>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Term {
+    Number(i64),
+    Symbol(String),
+    Here,
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Expr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut terms = Vec::new();
+        let mut negative = false;
+        let mut current = String::new();
+        let mut push = |negative: bool, text: &str| -> Result<(), String> {
+            let text = text.trim();
+            let term = match text.parse() {
+                Ok(number) => Term::Number(number),
+                Err(_) if text == "$" => Term::Here,
+                Err(_) if is_identifier(text) => Term::Symbol(text.to_string()),
+                Err(_) if text.is_empty() => return Err(String::from("missing value")),
+                Err(_) => return Err(format!("invalid value {:?}", text)),
+            };
+            terms.push((negative, term));
+            Ok(())
+        };
+
+        for c in text.chars() {
+            if c == '+' || c == '-' {
+                if !current.trim().is_empty() {
+                    push(negative, &current)?;
+                    current.clear();
+                    negative = false;
+                }
+                if c == '-' {
+                    negative = !negative;
+                }
+            } else {
+                current.push(c);
+            }
+        }
+        push(negative, &current)?;
+        Ok(Expr { terms })
+    }
+
+    /// Evaluate the expression, where the current address is `here`
+    ///
+    /// For an object, an undefined symbol is left for the linker, so it's returned instead of
+    /// being an error.
+    fn evaluate(&self, symbols: &Symbols, here: usize, object: bool) -> Result<Evaluated, String> {
+        let mut evaluated = Evaluated {
+            value: 0,
+            relocations: 0,
+            import: None,
+        };
+        for (negative, term) in &self.terms {
+            let sign = if *negative { -1 } else { 1 };
+            let value = match term {
+                Term::Number(number) => *number,
+                Term::Here => {
+                    evaluated.relocations += sign;
+                    here as i64
+                }
+                Term::Symbol(name) => match symbols.values.get(name) {
+                    Some(&value) => {
+                        if symbols.relocatable.contains(name) {
+                            evaluated.relocations += sign;
+                        }
+                        value
+                    }
+                    None if !object => return Err(format!("undefined symbol {:?}", name)),
+                    None if *negative => {
+                        return Err(format!(
+                            "{} is from another module, so can't be subtracted",
+                            name
+                        ))
+                    }
+                    None if evaluated.import.is_some() => {
+                        return Err(String::from(
+                            "only one symbol from another module can be used in a value",
+                        ))
+                    }
+                    None => {
+                        evaluated.import = Some(name.clone());
+                        0
+                    }
+                },
+            };
+            evaluated.value = match negative {
+                true => evaluated.value.wrapping_sub(value),
+                false => evaluated.value.wrapping_add(value),
+            };
+        }
+        if object && evaluated.relocations != 0 && evaluated.relocations != 1 {
+            return Err(String::from(
+                "an address can only be added once, or subtracted from another address",
+            ));
+        }
+        Ok(evaluated)
+    }
+}
+
+/// The value of an expression, and how it depends on where its module ends up
+struct Evaluated {
+    value: i64,
+    /// 1 when the value is an address in its module, which moves with it, and 0 otherwise
+    relocations: i64,
+    /// The symbol from another module whose value is added, left for the linker
+    import: Option<String>,
+}
+
+/// The labels and constants defined so far
+#[derive(Default)]
+struct Symbols {
+    values: BTreeMap<String, i64>,
+    /// The symbols that are addresses in their module, as opposed to plain numbers
+    relocatable: BTreeSet<String>,
+}
+
+/// The mode an operand is written in
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Mode {
+    Immediate,
+    Position,
+    Relative,
+}
+
+/// An operand as written in the source, before it's checked against the instruction
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Operand {
+    mode: Mode,
+    value: Expr,
+}
+
+impl Operand {
+    fn parse(text: &str) -> Result<Self, String> {
+        let inner = match text.strip_prefix('[') {
+            Some(rest) => rest
+                .strip_suffix(']')
+                .ok_or_else(|| format!("missing ] in {:?}", text))?
+                .trim(),
+            None => {
+                return Ok(Operand {
+                    mode: Mode::Immediate,
+                    value: Expr::parse(text)?,
+                })
+            }
+        };
+        let offset = inner
+            .strip_prefix("rb")
+            .filter(|rest| rest.trim().is_empty() || rest.trim().starts_with(&['+', '-'][..]));
+        Ok(match offset {
+            Some(offset) if offset.trim().is_empty() => Operand {
+                mode: Mode::Relative,
+                value: Expr::parse("0")?,
+            },
+            Some(offset) => Operand {
+                mode: Mode::Relative,
+                value: Expr::parse(offset)?,
+            },
+            None => Operand {
+                mode: Mode::Position,
+                value: Expr::parse(inner)?,
+            },
+        })
+    }
+
+    fn input(&self) -> InputParameter {
+        match self.mode {
+            Mode::Immediate => InputParameter::Immediate,
+            Mode::Position => InputParameter::Position,
+            Mode::Relative => InputParameter::Relative,
+        }
+    }
+
+    fn output(&self, text: &str) -> Result<OutputParameter, String> {
+        match self.mode {
+            Mode::Immediate => Err(format!(
+                "{} can't be written to; write [{}] for the value at that address",
+                text, text
+            )),
+            Mode::Position => Ok(OutputParameter::Position),
+            Mode::Relative => Ok(OutputParameter::Relative),
+        }
+    }
+}
+
+/// The number of operands the instruction with the given mnemonic takes
+fn operand_count(mnemonic: &str) -> Option<usize> {
+    match mnemonic {
+        "add" | "mul" | "lt" | "eq" => Some(3),
+        "jnz" | "jz" => Some(2),
+        "in" | "out" | "arb" => Some(1),
+        "halt" => Some(0),
+        _ => None,
+    }
+}
+
+/// Build the instruction with the given mnemonic from its operands
+fn instruction(
+    mnemonic: &str,
+    texts: &[&str],
+    operands: &[Operand],
+) -> Result<Instruction, String> {
+    let expected =
+        operand_count(mnemonic).ok_or_else(|| format!("unknown instruction {:?}", mnemonic))?;
+    if operands.len() != expected {
+        return Err(format!(
+            "{} takes {} operands, but has {}",
+            mnemonic,
+            expected,
+            operands.len()
+        ));
+    }
+
+    let o = operands;
+    let out = |i: usize| o[i].output(texts[i]);
+    Ok(match mnemonic {
+        "add" => Instruction::Add(o[0].input(), o[1].input(), out(2)?),
+        "mul" => Instruction::Mul(o[0].input(), o[1].input(), out(2)?),
+        "lt" => Instruction::LessThan(o[0].input(), o[1].input(), out(2)?),
+        "eq" => Instruction::Equals(o[0].input(), o[1].input(), out(2)?),
+        "jnz" => Instruction::JumpIfTrue(o[0].input(), o[1].input()),
+        "jz" => Instruction::JumpIfFalse(o[0].input(), o[1].input()),
+        "in" => Instruction::Input(out(0)?),
+        "out" => Instruction::Output(o[0].input()),
+        "arb" => Instruction::RelativeMode(o[0].input()),
+        _ => Instruction::Halt,
+    })
+}
+
+/// Encode a non-standard instruction from [`crate::extensions`], if `mnemonic` is one
+///
+/// The last operand of each of them is written to.
+#[cfg(feature = "extensions")]
+fn extended_instruction(
+    mnemonic: &str,
+    texts: &[&str],
+    operands: &[Operand],
+) -> Option<Result<i64, String>> {
+    use crate::extensions::{DIV, MOD, RAND};
+
+    let (opcode, expected) = match mnemonic {
+        "div" => (DIV, 3),
+        "mod" => (MOD, 3),
+        "rand" => (RAND, 2),
+        _ => return None,
+    };
+    if operands.len() != expected {
+        return Some(Err(format!(
+            "{} takes {} operands, but has {}",
+            mnemonic,
+            expected,
+            operands.len()
+        )));
+    }
+    if let Err(e) = operands[expected - 1].output(texts[expected - 1]) {
+        return Some(Err(e));
+    }
+    let mut encoded = i64::from(opcode);
+    for (index, operand) in operands.iter().enumerate() {
+        let mode = match operand.mode {
+            Mode::Position => 0,
+            Mode::Immediate => 1,
+            Mode::Relative => 2,
+        };
+        encoded += mode * 10_i64.pow(index as u32 + 2);
+    }
+    Some(Ok(encoded))
+}
+
+/// Split comma separated text, treating empty text as an empty list
+fn split_list(text: &str) -> Vec<&str> {
+    if text.trim().is_empty() {
+        Vec::new()
+    } else {
+        text.split(',').map(str::trim).collect()
+    }
+}
+
+/// Split a line into its first word and the rest
+fn split_word(line: &str) -> (&str, &str) {
+    match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim()),
+        None => (line, ""),
+    }
+}
+
+/// A macro's parameters and body
+struct Macro {
+    params: Vec<String>,
+    body: Vec<String>,
+}
+
+/// Replace every identifier in `line` that is a macro parameter with its argument
+fn substitute(line: &str, args: &HashMap<&str, &str>) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        out.push_str(args.get(word.as_str()).copied().unwrap_or(word));
+        word.clear();
+    };
+    for c in line.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// Remove comments and macro definitions from `source`, adding the definitions to `macros`, and
+/// return the remaining lines with their line numbers
+fn define_macros(
+    source: &str,
+    macros: &mut HashMap<String, Macro>,
+) -> Result<Vec<(usize, String)>, AssembleError> {
+    let mut lines = Vec::new();
+    let mut defining: Option<(usize, String, Macro)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let line = line.split(';').next().unwrap().trim();
+        let (word, rest) = split_word(line);
+
+        if let Some((start, name, mut definition)) = defining.take() {
+            if word == "endmacro" {
+                macros.insert(name, definition);
+            } else {
+                definition.body.push(line.to_string());
+                defining = Some((start, name, definition));
+            }
+        } else if word == "macro" {
+            let (name, params) = split_word(rest);
+            if !is_identifier(name) {
+                return Err(AssembleError {
+                    line: number,
+                    message: format!("invalid macro name {:?}", name),
+                });
+            }
+            let definition = Macro {
+                params: split_list(params).into_iter().map(String::from).collect(),
+                body: Vec::new(),
+            };
+            defining = Some((number, name.to_string(), definition));
+        } else if !line.is_empty() {
+            lines.push((number, line.to_string()));
+        }
+    }
+    if let Some((line, name, _)) = defining {
+        return Err(AssembleError {
+            line,
+            message: format!("macro {} has no endmacro", name),
+        });
+    }
+    Ok(lines)
+}
+
+/// Remove comments and macro definitions, and expand macro uses, keeping the line number each
+/// resulting line came from
+fn expand_macros(source: &str) -> Result<Vec<(usize, String)>, AssembleError> {
+    let mut macros = HashMap::new();
+    define_macros(BUILTIN_MACROS, &mut macros).expect("the built in macros are valid");
+    let lines = define_macros(source, &mut macros)?;
+
+    fn expand(
+        macros: &HashMap<String, Macro>,
+        number: usize,
+        line: String,
+        depth: usize,
+        out: &mut Vec<(usize, String)>,
+    ) -> Result<(), AssembleError> {
+        let error = |message| AssembleError {
+            line: number,
+            message,
+        };
+        // A label can come before a macro use, so look for the macro after it
+        let (label, rest) = match line.find(':') {
+            Some(i) => (&line[..=i], line[i + 1..].trim()),
+            None => ("", line.as_str()),
+        };
+        let (word, args) = split_word(rest);
+        let definition = match macros.get(word) {
+            Some(definition) => definition,
+            None => {
+                out.push((number, line));
+                return Ok(());
+            }
+        };
+        if depth == MAX_MACRO_DEPTH {
+            return Err(error(format!("macro {} is nested too deeply", word)));
+        }
+
+        let args = split_list(args);
+        if args.len() != definition.params.len() {
+            return Err(error(format!(
+                "macro {} takes {} arguments, but has {}",
+                word,
+                definition.params.len(),
+                args.len()
+            )));
+        }
+        let args: HashMap<&str, &str> = definition
+            .params
+            .iter()
+            .map(String::as_str)
+            .zip(args)
+            .collect();
+
+        if !label.is_empty() {
+            out.push((number, label.to_string()));
+        }
+        for body in &definition.body {
+            expand(macros, number, substitute(body, &args), depth + 1, out)?;
+        }
+        Ok(())
+    }
+
+    let mut expanded = Vec::new();
+    for (number, line) in lines {
+        expand(&macros, number, line, 0, &mut expanded)?;
+    }
+    Ok(expanded)
+}
+
+/// Define a label or constant
+fn define(symbols: &mut Symbols, name: &str, value: i64, relocatable: bool) -> Result<(), String> {
+    if !is_identifier(name) || name == "rb" {
+        return Err(format!("invalid symbol name {:?}", name));
+    }
+    if symbols.values.insert(name.to_string(), value).is_some() {
+        return Err(format!("{} is defined more than once", name));
+    }
+    if relocatable {
+        symbols.relocatable.insert(name.to_string());
+    }
+    Ok(())
+}
+
+/// What a line assembles to, before symbols are resolved
+enum Item {
+    Instruction(Instruction, Vec<Operand>),
+    /// A non-standard instruction, already encoded with its parameter modes
+    #[cfg(feature = "extensions")]
+    Extended(i64, Vec<Operand>),
+    Data(Vec<Expr>),
+}
+
+/// A module assembled on its own, to be combined with others by [`crate::link`]
+///
+/// The code is assembled as though it starts at address 0. The linker moves it by adding its
+/// real start to every value listed in `relocations`, and fills in symbols from other modules.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Object {
+    /// The assembled code
+    pub code: Vec<i64>,
+    /// The symbols the module makes available to others with `.global`
+    pub exports: BTreeMap<String, Export>,
+    /// The offsets of values that are addresses in this module
+    pub relocations: Vec<usize>,
+    /// The offsets of values that use a symbol from another module, and the symbol, whose value
+    /// the linker adds to them
+    pub imports: Vec<(usize, String)>,
+    /// The source line of every instruction and data directive, by offset
+    pub source_map: SourceMap,
+}
+
+/// A symbol a module exports
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Export {
+    /// A label, at this offset from the start of its module
+    Address(usize),
+    /// A constant
+    Constant(i64),
+}
+
+/// Assemble source text into a program
+pub fn assemble(source: &str) -> Result<Vec<i64>, AssembleError> {
+    Ok(assemble_at(source, 0)?.program)
+}
+
+/// Assemble source text into a program that will be loaded at `origin`, keeping its symbols
+/// and source map
+///
+/// Labels are addresses in memory, so they count from `origin`.
+pub fn assemble_at(source: &str, origin: usize) -> Result<Assembly, AssembleError> {
+    let assembled = assemble_lines(source, origin, false)?;
+    Ok(Assembly {
+        program: assembled.program,
+        symbols: assembled.symbols.values,
+        source_map: assembled.source_map,
+    })
+}
+
+/// Assemble source text into an object, for linking with other modules
+///
+/// Symbols that aren't defined are expected to come from other modules, and a `.global name, ...`
+/// line makes symbols available to other modules.
+pub fn assemble_object(source: &str) -> Result<Object, AssembleError> {
+    let assembled = assemble_lines(source, 0, true)?;
+    let mut exports = BTreeMap::new();
+    for (line, name) in assembled.globals {
+        let value = *assembled
+            .symbols
+            .values
+            .get(&name)
+            .ok_or_else(|| AssembleError {
+                line,
+                message: format!("global symbol {} isn't defined", name),
+            })?;
+        let export = match assembled.symbols.relocatable.contains(&name) {
+            true => Export::Address(value as usize),
+            false => Export::Constant(value),
+        };
+        exports.insert(name, export);
+    }
+    Ok(Object {
+        code: assembled.program,
+        exports,
+        relocations: assembled.relocations,
+        imports: assembled.imports,
+        source_map: assembled.source_map,
+    })
+}
+
+/// Everything the two passes of the assembler produce
+struct Assembled {
+    program: Vec<i64>,
+    symbols: Symbols,
+    source_map: SourceMap,
+    globals: Vec<(usize, String)>,
+    relocations: Vec<usize>,
+    imports: Vec<(usize, String)>,
+}
+
+fn assemble_lines(source: &str, origin: usize, object: bool) -> Result<Assembled, AssembleError> {
+    let lines = expand_macros(source)?;
+
+    // The first pass finds the address of every label, and the value of every constant
+    let mut symbols = Symbols::default();
+    let mut globals = Vec::new();
+    let mut items = Vec::new();
+    let mut address = origin;
+    for (number, line) in &lines {
+        let error = |message| AssembleError {
+            line: *number,
+            message,
+        };
+        let mut line = line.as_str();
+        while let Some(i) = line.find(':') {
+            define(&mut symbols, line[..i].trim(), address as i64, true).map_err(error)?;
+            line = line[i + 1..].trim();
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let (word, rest) = split_word(line);
+        match word {
+            "const" => {
+                let (name, value) = match rest.find('=') {
+                    Some(i) => (rest[..i].trim(), &rest[i + 1..]),
+                    None => return Err(error(String::from("const needs a value, after ="))),
+                };
+                let value = Expr::parse(value)
+                    .and_then(|value| value.evaluate(&symbols, address, object))
+                    .map_err(error)?;
+                if let Some(import) = value.import {
+                    return Err(error(format!(
+                        "undefined symbol {:?}; constants can't use symbols from other modules",
+                        import
+                    )));
+                }
+                define(&mut symbols, name, value.value, value.relocations == 1).map_err(error)?;
+            }
+            ".global" => {
+                for name in split_list(rest) {
+                    globals.push((*number, name.to_string()));
+                }
+            }
+            ".data" => {
+                let values = split_list(rest)
+                    .into_iter()
+                    .map(Expr::parse)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+                let len = values.len();
+                items.push((*number, address, Item::Data(values)));
+                address += len;
+            }
+            mnemonic => {
+                let texts = split_list(rest);
+                let operands = texts
+                    .iter()
+                    .map(|text| Operand::parse(text))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+                #[cfg(feature = "extensions")]
+                if let Some(encoded) = extended_instruction(mnemonic, &texts, &operands) {
+                    let len = operands.len() + 1;
+                    let item = Item::Extended(encoded.map_err(error)?, operands);
+                    items.push((*number, address, item));
+                    address += len;
+                    continue;
+                }
+                let instruction = instruction(mnemonic, &texts, &operands).map_err(error)?;
+                items.push((*number, address, Item::Instruction(instruction, operands)));
+                address += instruction.len();
+            }
+        }
+    }
+
+    // The second pass resolves every operand now that every symbol is known
+    let mut program = Vec::with_capacity(address - origin);
+    let mut entries = Vec::with_capacity(items.len());
+    let mut relocations = Vec::new();
+    let mut imports = Vec::new();
+    for (number, address, item) in items {
+        let error = |message| AssembleError {
+            line: number,
+            message,
+        };
+        entries.push((address, number));
+        let values = match item {
+            Item::Instruction(instruction, operands) => {
+                program.push(instruction.encode());
+                operands.into_iter().map(|operand| operand.value).collect()
+            }
+            #[cfg(feature = "extensions")]
+            Item::Extended(encoded, operands) => {
+                program.push(encoded);
+                operands.into_iter().map(|operand| operand.value).collect()
+            }
+            Item::Data(values) => values,
+        };
+        for value in values {
+            let value = value.evaluate(&symbols, address, object).map_err(error)?;
+            if value.relocations == 1 {
+                relocations.push(program.len());
+            }
+            if let Some(import) = value.import {
+                imports.push((program.len(), import));
+            }
+            program.push(value.value);
+        }
+    }
+
+    Ok(Assembled {
+        program,
+        symbols,
+        source_map: SourceMap { entries },
+        globals,
+        relocations,
+        imports,
+    })
+}
+
+/// Write an operand in the source syntax
+fn write_operand(out: &mut String, mode: i64, value: i64) {
+    match mode {
+        0 => write!(out, "[{}]", value),
+        1 => write!(out, "{}", value),
+        _ if value == 0 => write!(out, "[rb]"),
+        _ if value < 0 => write!(out, "[rb{}]", value),
+        _ => write!(out, "[rb+{}]", value),
+    }
+    .unwrap()
+}
+
+/// Write a line followed by a comment with its address
+fn write_line(out: &mut String, line: &str, address: usize, reachable: bool) {
+    let note = if reachable { "" } else { " (unreachable)" };
+    writeln!(
+        out,
+        "{:<width$}; {}{}",
+        line,
+        address,
+        note,
+        width = COMMENT_COLUMN
+    )
+    .unwrap();
+}
+
+/// Disassemble the instruction at `address`, returning its text and length
+///
+/// Returns `None` if the value there isn't exactly the encoding of an instruction, or if the
+/// instruction's parameters run past the end of the program.
+pub fn disassemble_instruction(program: &[i64], address: usize) -> Option<(String, usize)> {
+    let value = *program.get(address)?;
+    let instruction = Instruction::decode(value).ok()?;
+    if instruction.encode() != value || address + instruction.len() > program.len() {
+        return None;
+    }
+
+    let mut line = String::from(instruction.mnemonic());
+    for position in 1..instruction.len() {
+        line.push_str(if position == 1 { " " } else { ", " });
+        let mode = value / 10_i64.pow(position as u32 + 1) % 10;
+        write_operand(&mut line, mode, program[address + position]);
+    }
+    Some((line, instruction.len()))
+}
+
+/// Disassemble a program into source text, annotated with addresses
+///
+/// Values that the control flow analysis shows are data, or that aren't valid instructions, are
+/// written as `.data` lines, so assembling the text gives back exactly the same program.
+pub fn disassemble(program: &[i64]) -> String {
+    let analysis = analyze(program);
+    // When the analysis is incomplete, unreachable values might still be code, so decode them
+    let reachable = |address| analysis.code().contains(&address);
+    let instruction = |address: usize| {
+        if analysis.instructions().contains(&address) || !analysis.is_complete() {
+            disassemble_instruction(program, address)
+        } else {
+            None
+        }
+    };
+
+    let mut out = String::new();
+    let mut address = 0;
+    while address < program.len() {
+        let start = address;
+        let line = if let Some((line, len)) = instruction(address) {
+            address += len;
+            line
+        } else {
+            let mut line = String::from(".data ");
+            loop {
+                write!(line, "{}", program[address]).unwrap();
+                address += 1;
+                if address == program.len()
+                    || address - start == DATA_PER_LINE
+                    || reachable(address) != reachable(start)
+                    || instruction(address).is_some()
+                {
+                    break;
+                }
+                line.push_str(", ");
+            }
+            line
+        };
+        write_line(&mut out, &line, start, reachable(start));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Program;
+
+    #[test]
+    fn test_assemble() {
+        let source = "
+            ; Add the relative base to an input
+            arb -3
+            in [rb+10]        ; store at 7
+            add [rb+ 10], [rb], [rb-1]
+            halt
+        ";
+        assert_eq!(
+            assemble(source),
+            Ok(vec![109, -3, 203, 10, 22201, 10, 0, -1, 99])
+        );
+
+        assert_eq!(
+            assemble("add 1, 2, 3"),
+            Err(AssembleError {
+                line: 1,
+                message: "3 can't be written to; write [3] for the value at that address"
+                    .to_string()
+            })
+        );
+        assert_eq!(
+            assemble("halt\nout [1], [2]").unwrap_err().to_string(),
+            "line 2: out takes 1 operands, but has 2"
+        );
+        assert_eq!(
+            assemble("jump 5").unwrap_err().message,
+            "unknown instruction \"jump\""
+        );
+    }
+
+    #[test]
+    fn test_symbols_and_macros() {
+        let source = "\
+const LIMIT = 3
+macro count_to n, done
+    add [counter], 1, [counter]
+    lt [counter], n, [flag]
+    jz [flag], done
+endmacro
+
+loop: out [counter]
+      count_to LIMIT, end
+      jnz 1, loop
+end:  halt
+counter: .data 0
+flag:    .data 0 ; set while counting
+";
+        let assembly = assemble_at(source, 100).unwrap();
+        assert_eq!(
+            assembly.program,
+            vec![
+                4, 117, // loop
+                1001, 117, 1, 117, 1007, 117, 3, 118, 1006, 118, 116, // count_to
+                1105, 1, 100, // jnz
+                99, 0, 0, // end, counter, flag
+            ]
+        );
+        assert_eq!(assembly.symbols["loop"], 100);
+        assert_eq!(assembly.symbols["end"], 116);
+        assert_eq!(assembly.symbols["flag"], 118);
+        assert_eq!(assembly.symbols["LIMIT"], 3);
+
+        // Lines from a macro map to the line that used it
+        assert_eq!(assembly.source_map.line(100), Some(8));
+        assert_eq!(assembly.source_map.line(108), Some(9));
+        assert_eq!(assembly.source_map.line(118), Some(13));
+        assert_eq!(assembly.source_map.line(99), None);
+        assert_eq!(assembly.source_map.address(10), Some(113));
+        assert_eq!(assembly.source_map.address(1), None);
+
+        assert_eq!(
+            assemble("jnz 1, nowhere").unwrap_err().message,
+            "undefined symbol \"nowhere\""
+        );
+        assert_eq!(
+            assemble("a: halt\na: halt").unwrap_err().to_string(),
+            "line 2: a is defined more than once"
+        );
+        assert_eq!(
+            assemble("macro m\nhalt").unwrap_err().to_string(),
+            "line 1: macro m has no endmacro"
+        );
+        assert_eq!(
+            assemble("macro m\nm\nendmacro\nm").unwrap_err().message,
+            "macro m is nested too deeply"
+        );
+    }
+
+    #[test]
+    fn test_calling_convention() {
+        let source = "
+            arb stack
+            in [rb+1]
+            push [rb+1]                 ; keep n across the call
+            add [rb-1], 0, [rb+1]
+            call factorial
+            out [rb+1]
+            pop [rb+1]                  ; n replaces the factorial, and rb moves down
+            out [rb+2]
+            halt
+
+        ; Replace [rb+1] with its factorial
+        factorial:
+            jnz [rb+1], recurse
+            add 1, 0, [rb+1]
+            ret
+        recurse:
+            arb 2                       ; keep the return address and n below the stack
+            add [rb-1], -1, [rb+1]
+            call factorial
+            mul [rb-1], [rb+1], [rb-1]
+            arb -2
+            ret
+        stack:
+        ";
+        let program = assemble(source).unwrap();
+        assert_eq!(crate::run(&program, &[5]).unwrap(), vec![120, 5]);
+        assert_eq!(crate::run(&program, &[0]).unwrap(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_pop_relative() {
+        let source = "
+            arb stack
+            push 7
+            push 8
+            pop [rb+1]                  ; [stack+3]
+            pop [rb+1]                  ; [stack+2]
+            out [stack+3]
+            out [stack+2]
+            halt
+        stack:
+        ";
+        let program = assemble(source).unwrap();
+        assert_eq!(crate::run(&program, &[]).unwrap(), vec![8, 7]);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = vec![1101, 2, 3, 9, 4, 9, 99, 12345, -1, 0];
+        assert_eq!(
+            disassemble(&program),
+            "add 2, 3, [9]                   ; 0\n\
+             out [9]                         ; 4\n\
+             halt                            ; 6\n\
+             .data 12345, -1, 0              ; 7 (unreachable)\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            include_str!("../../day-02/input.txt"),
+            include_str!("../../day-05/input.txt"),
+            include_str!("../../day-07/input.txt"),
+            include_str!("../../day-09/input.txt"),
+        ];
+        for input in inputs.iter() {
+            let program: Program = input.parse().unwrap();
+            let text = disassemble(program.as_slice());
+            assert_eq!(assemble(&text).unwrap(), program.as_slice());
+        }
+    }
+}
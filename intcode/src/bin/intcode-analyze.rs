@@ -0,0 +1,18 @@
+use intcode::analysis::analyze;
+use std::env;
+use std::fs;
+
+fn main() {
+    let program_path = env::args()
+        .nth(1)
+        .expect("Usage: intcode-analyze <program>");
+    let input = fs::read_to_string(program_path).unwrap();
+
+    let program: Vec<i64> = input
+        .trim()
+        .split(',')
+        .map(|s| s.parse::<i64>().unwrap())
+        .collect();
+
+    print!("{}", analyze(&program));
+}
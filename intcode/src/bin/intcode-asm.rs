@@ -0,0 +1,42 @@
+use intcode::asm::assemble;
+use intcode::link::link_sources;
+use intcode::stdlib;
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let with_stdlib = args.iter().any(|arg| arg == "--stdlib");
+    args.retain(|arg| arg != "--stdlib");
+    if args.is_empty() {
+        eprintln!("Usage: intcode-asm [--stdlib] <source>...");
+        process::exit(1);
+    }
+    let sources: Vec<String> = args
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+
+    // A single file assembles on its own, and more than one are linked as separate modules
+    let result = if sources.len() == 1 && !with_stdlib {
+        assemble(&sources[0]).map_err(|e| e.to_string())
+    } else {
+        let mut modules: Vec<&str> = sources.iter().map(String::as_str).collect();
+        if with_stdlib {
+            modules.push(stdlib::SOURCE);
+        }
+        link_sources(&modules).map_err(|e| e.to_string())
+    };
+
+    match result {
+        Ok(program) => {
+            let values: Vec<String> = program.iter().map(|value| value.to_string()).collect();
+            println!("{}", values.join(","));
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use intcode::asm::{assemble_at, disassemble_instruction, Assembly};
+use intcode::debugger::{Debugger, Stop};
+use intcode::stream::write_output;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Addresses can be labels when debugging an assembly (.asm) file.
+commands:
+  break <address>       stop before executing the instruction at an address
+  delete <address>      remove a breakpoint
+  watch @<address>      stop after any instruction that writes to an address
+  unwatch @<address>    remove a watchpoint
+  run, continue         run until a breakpoint, a watchpoint, or an error
+  step [count]          execute one or more instructions
+  print @<address>      show the value at an address
+  print ic, print rb    show the instruction counter or the relative base
+  disas [address] [n]   disassemble n instructions, at the instruction counter by default
+  backtrace             show the most recently executed instructions
+  input <values...>     queue numeric inputs
+  input \"<text>\"        queue a line of ASCII input
+  quit";
+
+/// The program being debugged, and its source when it was assembled by the debugger
+struct Session {
+    debugger: Debugger,
+    source: Option<(Assembly, Vec<String>)>,
+}
+
+impl Session {
+    /// Parse an address written as `120`, `@120`, or a label like `loop`
+    fn parse_address(&self, arg: Option<&str>) -> Result<usize, String> {
+        let arg = arg.ok_or("missing address")?;
+        let name = arg.trim_start_matches('@');
+        let symbol = self
+            .source
+            .as_ref()
+            .and_then(|(assembly, _)| assembly.symbols.get(name).copied());
+        match symbol {
+            Some(value) => Ok(value as usize),
+            None => name
+                .parse()
+                .map_err(|_| format!("invalid address {:?}", arg)),
+        }
+    }
+
+    /// Show the source line of the instruction at `address`, if there is source
+    fn show_source(&self, address: usize) {
+        if let Some((assembly, lines)) = &self.source {
+            if let Some(line) = assembly.source_map.line(address) {
+                println!("  line {}: {}", line, lines[line - 1].trim());
+            }
+        }
+    }
+}
+
+fn describe(stop: &Stop<i64>) -> String {
+    match stop {
+        Stop::Stepped => String::new(),
+        Stop::Breakpoint(address) => format!("breakpoint at {}", address),
+        Stop::Watchpoint {
+            instruction,
+            address,
+            old,
+            new,
+        } => format!(
+            "watchpoint @{}: {} -> {} (instruction {})",
+            address, old, new, instruction
+        ),
+        Stop::Error(IntcodeError::CatchFire) => String::from("halted"),
+        Stop::Error(IntcodeError::NoInputAvailable) => {
+            String::from("waiting for input; queue some with `input`")
+        }
+        Stop::Error(e) => format!("error: {:?}", e),
+    }
+}
+
+fn disassemble(memory: &[i64], mut address: usize, count: usize) {
+    for _ in 0..count {
+        if address >= memory.len() {
+            break;
+        }
+        match disassemble_instruction(memory, address) {
+            Some((line, len)) => {
+                println!("{:>6}: {}", address, line);
+                address += len;
+            }
+            None => {
+                println!("{:>6}: .data {}", address, memory[address]);
+                address += 1;
+            }
+        }
+    }
+}
+
+fn execute(session: &mut Session, command: &str, args: &[&str]) -> Result<(), String> {
+    let address = session.parse_address(args.first().copied());
+    let debugger = &mut session.debugger;
+    match command {
+        "break" | "b" => debugger.add_breakpoint(address?),
+        "delete" | "d" => {
+            if !debugger.remove_breakpoint(address?) {
+                return Err(String::from("no such breakpoint"));
+            }
+        }
+        "watch" | "w" => debugger.add_watchpoint(address?),
+        "unwatch" => {
+            if !debugger.remove_watchpoint(address?) {
+                return Err(String::from("no such watchpoint"));
+            }
+        }
+        "run" | "r" | "continue" | "c" => {
+            println!("{}", describe(&debugger.resume()));
+            session.show_source(session.debugger.process().instruction_counter());
+        }
+        "step" | "s" => {
+            let count = match args.first() {
+                Some(count) => count
+                    .parse()
+                    .map_err(|_| format!("invalid count {:?}", count))?,
+                None => 1,
+            };
+            for _ in 0..count {
+                match debugger.step() {
+                    Stop::Stepped => {}
+                    stop => {
+                        println!("{}", describe(&stop));
+                        break;
+                    }
+                }
+            }
+            let process = session.debugger.process();
+            disassemble(process.memory(), process.instruction_counter(), 1);
+            session.show_source(process.instruction_counter());
+        }
+        "print" | "p" => {
+            let process = debugger.process();
+            match args.first().copied() {
+                Some("ic") => println!("ic = {}", process.instruction_counter()),
+                Some("rb") => println!("rb = {}", process.relative_base()),
+                _ => {
+                    let address = address?;
+                    match process.memory().get(address) {
+                        Some(value) => println!("@{} = {}", address, value),
+                        None => return Err(format!("@{} is outside of memory", address)),
+                    }
+                }
+            }
+        }
+        "disas" => {
+            let process = debugger.process();
+            let address = match args.first() {
+                Some(_) => address?,
+                None => process.instruction_counter(),
+            };
+            let count = match args.get(1) {
+                Some(count) => count
+                    .parse()
+                    .map_err(|_| format!("invalid count {:?}", count))?,
+                None => 10,
+            };
+            disassemble(process.memory(), address, count);
+        }
+        "backtrace" | "bt" => {
+            let memory = debugger.process().memory();
+            for address in debugger.backtrace().into_iter().rev() {
+                disassemble(memory, address, 1);
+            }
+        }
+        "input" | "i" => {
+            let process = debugger.process_mut();
+            let line = args.join(" ");
+            match line.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                Some(text) => process.add_ascii_input(&format!("{}\n", text)),
+                None => {
+                    let values = args
+                        .iter()
+                        .map(|value| {
+                            value
+                                .parse()
+                                .map_err(|_| format!("invalid input {:?}", value))
+                        })
+                        .collect::<Result<Vec<i64>, _>>()?;
+                    process.add_inputs(&values);
+                }
+            }
+        }
+        "help" | "h" => println!("{}", HELP),
+        _ => return Err(format!("unknown command {:?}; try `help`", command)),
+    }
+    Ok(())
+}
+
+fn main() {
+    let program_path = env::args()
+        .nth(1)
+        .expect("Usage: intcode-dbg <program or .asm file>");
+    let input = fs::read_to_string(&program_path).unwrap();
+
+    let (program, source) = if program_path.ends_with(".asm") {
+        let assembly = assemble_at(&input, 0).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        let lines = input.lines().map(String::from).collect();
+        (assembly.program.clone(), Some((assembly, lines)))
+    } else {
+        let program: Program = input.parse().unwrap();
+        (program.as_slice().to_vec(), None)
+    };
+
+    let mut process = IntcodeProcess::from_vec(program);
+    process.on_output(write_output(io::stdout()));
+    let mut session = Session {
+        debugger: Debugger::new(process),
+        source,
+    };
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(dbg) ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            None => break,
+        };
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.split_first() {
+            None => {}
+            Some((&"quit", _)) | Some((&"q", _)) => break,
+            Some((command, args)) => {
+                if let Err(e) = execute(&mut session, command, args) {
+                    println!("{}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use intcode::asm::disassemble;
+use intcode::Program;
+use std::env;
+use std::fs;
+
+fn main() {
+    let program_path = env::args().nth(1).expect("Usage: intcode-disasm <program>");
+    let input = fs::read_to_string(program_path).unwrap();
+    let program: Program = input.parse().unwrap();
+
+    print!("{}", disassemble(program.as_slice()));
+}
@@ -0,0 +1,65 @@
+use intcode::terminal::run_interactive;
+use intcode::transcript::Transcript;
+use intcode::IntcodeProcess;
+use std::cell::RefCell;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::rc::Rc;
+
+fn main() {
+    let mut program_path = None;
+    let mut transcript_path = None;
+    let mut log_path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--transcript" => {
+                transcript_path = Some(args.next().expect("--transcript requires a file path"))
+            }
+            "--log" => log_path = Some(args.next().expect("--log requires a file path")),
+            _ => program_path = Some(arg),
+        }
+    }
+
+    let program_path =
+        program_path.expect("Usage: intcode-play <program> [--transcript <file>] [--log <file>]");
+    let input = fs::read_to_string(program_path).unwrap();
+
+    let program: Vec<i64> = input
+        .trim()
+        .split(',')
+        .map(|s| s.parse::<i64>().unwrap())
+        .collect();
+
+    let mut transcript = transcript_path.map(|path| File::create(path).unwrap());
+
+    let mut process = IntcodeProcess::from_vec(program);
+    let log = log_path.map(|path| {
+        let log = Rc::new(RefCell::new(Transcript::new(
+            File::create(path).unwrap(),
+            true,
+        )));
+        process.add_observer(log.clone());
+        log
+    });
+    let stdin = io::stdin();
+    let result = run_interactive(
+        &mut process,
+        stdin.lock(),
+        io::stdout(),
+        transcript.as_mut().map(|file| file as &mut dyn Write),
+    );
+
+    if let Some(e) = log
+        .as_ref()
+        .and_then(|log| log.borrow().error().map(|e| e.to_string()))
+    {
+        eprintln!("Writing the log failed: {}", e);
+    }
+    if let Err(e) = result {
+        eprintln!("{:?}", e);
+        std::process::exit(1);
+    }
+}
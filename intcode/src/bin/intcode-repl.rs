@@ -0,0 +1,34 @@
+use intcode::repl::Repl;
+use intcode::Program;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+fn main() {
+    let memory = match env::args().nth(1) {
+        Some(path) => {
+            let input = fs::read_to_string(path).unwrap();
+            let program: Program = input.parse().unwrap();
+            program.as_slice().to_vec()
+        }
+        None => Vec::new(),
+    };
+    let mut repl = Repl::new(memory);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            None => break,
+        };
+
+        match repl.eval(&line) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => println!("{}", output),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use intcode::heatmap::Heatmap;
+use intcode::stream::{write_output, ReadInput};
+use intcode::transcript::Transcript;
+use intcode::{IntcodeError, IntcodeProcess, Program};
+use std::cell::RefCell;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::rc::Rc;
+
+/// The number of slices of the run the heatmap shows
+const HEATMAP_ROWS: usize = 512;
+
+fn main() {
+    let mut program_path = None;
+    let mut coverage = false;
+    let mut log_path = None;
+    let mut heatmap_path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--coverage" => coverage = true,
+            "--heatmap" => {
+                heatmap_path = Some(args.next().expect("--heatmap requires a file path"))
+            }
+            "--log" => log_path = Some(args.next().expect("--log requires a file path")),
+            _ => program_path = Some(arg),
+        }
+    }
+
+    let program_path = program_path
+        .expect("Usage: intcode-run <program> [--coverage] [--log <file>] [--heatmap <file.ppm>]");
+    let program: Program = fs::read_to_string(program_path).unwrap().parse().unwrap();
+
+    let mut process = IntcodeProcess::new(&program);
+    process.set_input_provider(ReadInput::new(io::stdin()));
+    process.on_output(write_output(io::stdout()));
+    if coverage {
+        process.start_coverage();
+    }
+    if heatmap_path.is_some() {
+        process.start_tracing();
+    }
+    let log = log_path.map(|path| {
+        let log = Rc::new(RefCell::new(Transcript::new(
+            File::create(path).unwrap(),
+            true,
+        )));
+        process.add_observer(log.clone());
+        log
+    });
+
+    let result = process.run().unwrap_err();
+
+    if let (Some(path), Some(trace)) = (heatmap_path, process.take_trace()) {
+        let file = io::BufWriter::new(File::create(path).unwrap());
+        Heatmap::from_trace(&trace, HEATMAP_ROWS)
+            .write_ppm(file)
+            .unwrap();
+    }
+    if let Some(coverage) = process.coverage() {
+        eprint!("{}", coverage);
+    }
+    if let Some(e) = log
+        .as_ref()
+        .and_then(|log| log.borrow().error().map(|e| e.to_string()))
+    {
+        eprintln!("Writing the log failed: {}", e);
+    }
+    if result != IntcodeError::CatchFire {
+        eprintln!("{:?}", result);
+        std::process::exit(1);
+    }
+}
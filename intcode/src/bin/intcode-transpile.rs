@@ -0,0 +1,28 @@
+use intcode::transpile::transpile;
+use std::env;
+use std::fs;
+
+fn main() {
+    let mut program_path = None;
+    let mut name = String::from("run");
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--name" => name = args.next().expect("--name requires a function name"),
+            _ => program_path = Some(arg),
+        }
+    }
+
+    let program_path =
+        program_path.expect("Usage: intcode-transpile <program> [--name <function>]");
+    let input = fs::read_to_string(program_path).unwrap();
+
+    let program: Vec<i64> = input
+        .trim()
+        .split(',')
+        .map(|s| s.parse::<i64>().unwrap())
+        .collect();
+
+    print!("{}", transpile(&program, &name));
+}
@@ -0,0 +1,204 @@
+use crate::observer::OutputCallback;
+use crate::{InputProvider, IntcodeError, IntcodeProcess, Observer, Value};
+
+/// Fluently configure an `IntcodeProcess` before running it
+pub struct IntcodeProcessBuilder<T = i64> {
+    program: Vec<T>,
+    inputs: Vec<T>,
+    patches: Vec<(T, T)>,
+    memory_limit: Option<usize>,
+    checked_arithmetic: bool,
+    strict_memory: bool,
+    decode_cache: bool,
+    observers: Vec<Box<dyn Observer<T>>>,
+    input_provider: Option<Box<dyn InputProvider<T>>>,
+    default_input: Option<T>,
+    steps_per_second: Option<u32>,
+}
+
+impl<T: Value> IntcodeProcessBuilder<T> {
+    /// Create a builder with an empty program
+    pub fn new() -> Self {
+        IntcodeProcessBuilder {
+            program: Vec::new(),
+            inputs: Vec::new(),
+            patches: Vec::new(),
+            memory_limit: None,
+            checked_arithmetic: false,
+            strict_memory: false,
+            decode_cache: false,
+            observers: Vec::new(),
+            input_provider: None,
+            default_input: None,
+            steps_per_second: None,
+        }
+    }
+
+    /// Set the program, which becomes the initial memory
+    pub fn program(mut self, program: Vec<T>) -> Self {
+        self.program = program;
+        self
+    }
+
+    /// Queue an input
+    pub fn input(mut self, value: T) -> Self {
+        self.inputs.push(value);
+        self
+    }
+
+    /// Queue several inputs
+    pub fn inputs(mut self, values: impl IntoIterator<Item = T>) -> Self {
+        self.inputs.extend(values);
+        self
+    }
+
+    /// Store a value into the program's memory before it starts, like day 2's noun and verb
+    pub fn patch(mut self, address: T, value: T) -> Self {
+        self.patches.push((address, value));
+        self
+    }
+
+    /// Limit how large the memory space may grow
+    pub fn memory_limit(mut self, limit: usize) -> Self {
+        self.memory_limit = Some(limit);
+        self
+    }
+
+    /// Choose whether the add and multiply instructions check for overflow
+    pub fn checked_arithmetic(mut self, checked: bool) -> Self {
+        self.checked_arithmetic = checked;
+        self
+    }
+
+    /// Choose whether accessing memory outside of the program is an error, instead of growing
+    /// the address space
+    pub fn strict_memory(mut self, strict: bool) -> Self {
+        self.strict_memory = strict;
+        self
+    }
+
+    /// Choose whether decoded instructions are cached by address
+    pub fn decode_cache(mut self, enabled: bool) -> Self {
+        self.decode_cache = enabled;
+        self
+    }
+
+    /// Register a callback that is called with every output as it is produced
+    pub fn on_output(mut self, callback: impl FnMut(&T) + 'static) -> Self {
+        self.observers.push(Box::new(OutputCallback(callback)));
+        self
+    }
+
+    /// Register an observer that is told about everything the process does
+    pub fn observer(mut self, observer: impl Observer<T> + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Set a provider that is asked for input whenever the input queue is empty
+    pub fn input_provider(mut self, provider: impl InputProvider<T> + 'static) -> Self {
+        self.input_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Set the value the input instruction reads when no input is available
+    pub fn default_input(mut self, default: Option<T>) -> Self {
+        self.default_input = default;
+        self
+    }
+
+    /// Limit how many instructions the process executes every second, for watching it run
+    pub fn steps_per_second(mut self, steps: u32) -> Self {
+        self.steps_per_second = Some(steps);
+        self
+    }
+
+    /// Build the process
+    ///
+    /// Fails if a patch is outside of the program's memory.
+    pub fn build(self) -> Result<IntcodeProcess<T>, IntcodeError<T>> {
+        let mut process = IntcodeProcess::with_memory(self.program);
+        process.patch(&self.patches)?;
+        process.extend_inputs(self.inputs);
+        process.set_memory_limit(self.memory_limit);
+        process.set_checked_arithmetic(self.checked_arithmetic);
+        process.set_strict_memory(self.strict_memory);
+        process.set_decode_cache(self.decode_cache);
+        process.observers = self.observers;
+        process.input_provider = self.input_provider;
+        process.set_default_input(self.default_input);
+        process.set_steps_per_second(self.steps_per_second);
+
+        Ok(process)
+    }
+}
+
+impl<T: Value> Default for IntcodeProcessBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let mut process = IntcodeProcess::builder()
+            .program(vec![3, 11, 3, 12, 1, 11, 12, 13, 4, 13, 99, 0, 0, 0])
+            .input(5)
+            .inputs(vec![6])
+            .build()
+            .unwrap();
+
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(process.outputs(), &[11]);
+    }
+
+    #[test]
+    fn test_builder_patch() {
+        let result = IntcodeProcess::builder()
+            .program(vec![1, 0, 0, 0, 99])
+            .patch(5, 1)
+            .build();
+
+        assert_eq!(result.err(), Some(IntcodeError::SegfaultWrite(5)));
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let mut process = IntcodeProcess::builder()
+            .program(vec![1102, i64::MAX, 2, 0, 99])
+            .checked_arithmetic(true)
+            .build()
+            .unwrap();
+        assert_eq!(process.run(), Err(IntcodeError::Overflow { address: 0 }));
+
+        let mut process = IntcodeProcess::builder()
+            .program(vec![4, 100, 99])
+            .memory_limit(10)
+            .build()
+            .unwrap();
+        assert_eq!(
+            process.run(),
+            Err(IntcodeError::MemoryLimitExceeded { address: 100 })
+        );
+
+        let mut process = IntcodeProcess::builder()
+            .program(vec![3, 0, 4, 0, 99])
+            .input_provider(|| Some(7))
+            .build()
+            .unwrap();
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(process.outputs(), &[7]);
+
+        let mut process = IntcodeProcess::builder()
+            .program(vec![3, 0, 4, 0, 99])
+            .default_input(Some(-1))
+            .build()
+            .unwrap();
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(process.outputs(), &[-1]);
+    }
+}
@@ -0,0 +1,79 @@
+//! Errors with enough context to find where in the program they happened
+
+use crate::IntcodeError;
+use std::fmt;
+
+/// An error along with the state of the process when it happened, created by
+/// `IntcodeProcess::error_context`
+#[derive(Debug, Eq, PartialEq)]
+pub struct ErrorContext<T> {
+    /// The error itself
+    pub error: IntcodeError<T>,
+    /// The instruction counter when the error happened, which is the failing instruction unless
+    /// the process halted
+    pub instruction_counter: usize,
+    /// The relative base when the error happened
+    pub relative_base: T,
+    /// The most recently executed instructions, oldest first, as addresses and the values
+    /// currently stored there
+    ///
+    /// Empty unless history was enabled with `IntcodeProcess::set_history_length`.
+    pub recent: Vec<(usize, T)>,
+}
+
+impl<T: fmt::Debug + fmt::Display> fmt::Display for ErrorContext<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at instruction {} (relative base {})",
+            self.error, self.instruction_counter, self.relative_base
+        )?;
+        if !self.recent.is_empty() {
+            write!(f, "; recent instructions:")?;
+            for (address, value) in &self.recent {
+                write!(f, " {}={}", address, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntcodeProcess;
+
+    #[test]
+    fn test_error_context() {
+        let mut process = IntcodeProcess::from_vec(vec![109, 10, 1101, 1, 2, 7, 203, 0]);
+        process.set_history_length(2);
+        let error = process
+            .run()
+            .map_err(|e| process.error_context(e))
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            ErrorContext {
+                error: IntcodeError::NoInputAvailable,
+                instruction_counter: 6,
+                relative_base: 10,
+                recent: vec![(2, 1101), (6, 203)],
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "NoInputAvailable at instruction 6 (relative base 10); recent instructions: 2=1101 6=203"
+        );
+
+        let mut process = IntcodeProcess::from_vec(vec![203]);
+        let error = process
+            .run()
+            .map_err(|e| process.error_context(e))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "NoInputAvailable at instruction 0 (relative base 0)"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Track which instructions a process has executed
+//!
+//! Coverage shows which parts of a puzzle program a run actually used, and whether a test
+//! program exercises every branch. Compare with [`crate::analysis`], which finds every
+//! instruction that could ever run.
+
+use std::fmt;
+use std::ops::Range;
+
+/// The instructions a process has executed since coverage started
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Coverage {
+    /// The length of the program when coverage started
+    len: usize,
+    /// Whether an instruction started at each address
+    instructions: Vec<bool>,
+    /// Whether each address was part of an executed instruction, including its parameters
+    covered: Vec<bool>,
+}
+
+impl Coverage {
+    /// Start tracking coverage of a program with `len` addresses
+    pub fn new(len: usize) -> Self {
+        Coverage {
+            len,
+            instructions: vec![false; len],
+            covered: vec![false; len],
+        }
+    }
+
+    /// Mark the instruction at `address`, which takes up `len` addresses, as executed
+    pub(crate) fn mark(&mut self, address: usize, len: usize) {
+        let end = address + len;
+        if self.covered.len() < end {
+            self.instructions.resize(end, false);
+            self.covered.resize(end, false);
+        }
+        self.instructions[address] = true;
+        for covered in &mut self.covered[address..end] {
+            *covered = true;
+        }
+    }
+
+    /// Whether an instruction at `address` has executed
+    pub fn is_executed(&self, address: usize) -> bool {
+        self.instructions.get(address).copied().unwrap_or(false)
+    }
+
+    /// Whether `address` is part of an executed instruction, including its parameters
+    pub fn is_covered(&self, address: usize) -> bool {
+        self.covered.get(address).copied().unwrap_or(false)
+    }
+
+    /// Get the addresses of the executed instructions, in order
+    pub fn instructions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, &executed)| executed)
+            .map(|(address, _)| address)
+    }
+
+    /// Get a bitmap of the addresses that are part of an executed instruction
+    ///
+    /// The bitmap may be longer than the program, if instructions outside of it executed.
+    pub fn bitmap(&self) -> &[bool] {
+        &self.covered[..]
+    }
+
+    /// Get the ranges of the program that were never part of an executed instruction
+    pub fn uncovered(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for address in (0..self.len).filter(|&address| !self.is_covered(address)) {
+            match ranges.last_mut() {
+                Some(range) if range.end == address => range.end += 1,
+                _ => ranges.push(address..address + 1),
+            }
+        }
+        ranges
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let covered = (0..self.len).filter(|&a| self.is_covered(a)).count();
+        writeln!(
+            f,
+            "{} executed instructions covering {} of {} addresses",
+            self.instructions().count(),
+            covered,
+            self.len
+        )?;
+        for range in self.uncovered() {
+            writeln!(f, "never executed: {}..{}", range.start, range.end)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_coverage() {
+        // Jump over an output if the input is zero
+        let program = vec![3, 11, 1006, 11, 7, 104, 1, 104, 2, 99, 5, 0];
+        let mut process = IntcodeProcess::from_vec(program.clone());
+        process.start_coverage();
+        process.add_input(0);
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+
+        let coverage = process.coverage().unwrap();
+        assert_eq!(
+            coverage.instructions().collect::<Vec<_>>(),
+            vec![0, 2, 7, 9]
+        );
+        assert!(coverage.is_executed(7));
+        assert!(!coverage.is_executed(8));
+        assert!(coverage.is_covered(8));
+        assert_eq!(coverage.uncovered(), vec![5..7, 10..12]);
+        assert_eq!(
+            coverage.to_string(),
+            "4 executed instructions covering 8 of 12 addresses\n\
+             never executed: 5..7\n\
+             never executed: 10..12\n"
+        );
+
+        let mut process = IntcodeProcess::from_vec(program);
+        process.start_coverage();
+        process.add_input(1);
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        let coverage = process.take_coverage().unwrap();
+        assert_eq!(coverage.uncovered(), vec![10..12]);
+        assert!(process.coverage().is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Breakpoints and watchpoints for stepping through a process, like a debugger
+//!
+//! A [`Debugger`] owns a process and runs it until it reaches a breakpoint, writes to a watched
+//! address, or stops with an error. The `intcode-dbg` binary is a command line front end for it.
+
+use crate::{IntcodeError, IntcodeProcess, Observer, Value};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+/// The number of instructions the debugger remembers for `backtrace`, unless the process was
+/// already remembering more
+const BACKTRACE_LENGTH: usize = 16;
+
+/// Why the debugger stopped running the process
+#[derive(Debug, Eq, PartialEq)]
+pub enum Stop<T> {
+    /// A single step finished without hitting anything
+    Stepped,
+    /// The process reached the breakpoint at <address>
+    Breakpoint(usize),
+    /// The instruction at `instruction` wrote to a watched address
+    Watchpoint {
+        /// The address of the instruction that wrote
+        instruction: usize,
+        /// The watched address
+        address: usize,
+        /// The value before the write
+        old: T,
+        /// The value after the write
+        new: T,
+    },
+    /// The process stopped with an error, including halting
+    Error(IntcodeError<T>),
+}
+
+/// The watched addresses, and the first watched write of the current step
+struct Watches<T> {
+    addresses: BTreeSet<usize>,
+    instruction: usize,
+    hit: Option<Stop<T>>,
+}
+
+impl<T: Clone> Observer<T> for Watches<T> {
+    fn on_step(&mut self, address: usize) {
+        self.instruction = address;
+    }
+
+    fn on_memory_write(&mut self, address: usize, old: &T, new: &T) {
+        if self.hit.is_none() && self.addresses.contains(&address) {
+            self.hit = Some(Stop::Watchpoint {
+                instruction: self.instruction,
+                address,
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+    }
+}
+
+/// A process with breakpoints and watchpoints
+pub struct Debugger<T = i64> {
+    process: IntcodeProcess<T>,
+    breakpoints: BTreeSet<usize>,
+    watches: Rc<RefCell<Watches<T>>>,
+}
+
+impl<T: Value + 'static> Debugger<T> {
+    /// Debug the given process
+    ///
+    /// The process remembers its recent instructions from now on, for `backtrace`.
+    pub fn new(mut process: IntcodeProcess<T>) -> Self {
+        let watches = Rc::new(RefCell::new(Watches {
+            addresses: BTreeSet::new(),
+            instruction: 0,
+            hit: None,
+        }));
+        process.add_observer(watches.clone());
+        if process.history_length() < BACKTRACE_LENGTH {
+            process.set_history_length(BACKTRACE_LENGTH);
+        }
+        Debugger {
+            process,
+            breakpoints: BTreeSet::new(),
+            watches,
+        }
+    }
+
+    /// Get the process being debugged
+    pub fn process(&self) -> &IntcodeProcess<T> {
+        &self.process
+    }
+
+    /// Get the process being debugged, for changing it, like adding inputs
+    pub fn process_mut(&mut self) -> &mut IntcodeProcess<T> {
+        &mut self.process
+    }
+
+    /// Stop before executing the instruction at `address`
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    /// Remove a breakpoint, returning whether there was one
+    pub fn remove_breakpoint(&mut self, address: usize) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    /// Get the addresses of the breakpoints
+    pub fn breakpoints(&self) -> &BTreeSet<usize> {
+        &self.breakpoints
+    }
+
+    /// Stop after any instruction that writes to `address`
+    pub fn add_watchpoint(&mut self, address: usize) {
+        self.watches.borrow_mut().addresses.insert(address);
+    }
+
+    /// Remove a watchpoint, returning whether there was one
+    pub fn remove_watchpoint(&mut self, address: usize) -> bool {
+        self.watches.borrow_mut().addresses.remove(&address)
+    }
+
+    /// Get the watched addresses
+    pub fn watchpoints(&self) -> BTreeSet<usize> {
+        self.watches.borrow().addresses.clone()
+    }
+
+    /// Execute one instruction
+    pub fn step(&mut self) -> Stop<T> {
+        self.watches.borrow_mut().hit = None;
+        if let Err(e) = self.process.step() {
+            return Stop::Error(e);
+        }
+        if let Some(hit) = self.watches.borrow_mut().hit.take() {
+            return hit;
+        }
+        if self
+            .breakpoints
+            .contains(&self.process.instruction_counter())
+        {
+            return Stop::Breakpoint(self.process.instruction_counter());
+        }
+        Stop::Stepped
+    }
+
+    /// Run until a breakpoint, a watchpoint, or an error
+    ///
+    /// At least one instruction executes, so resuming from a breakpoint moves past it.
+    pub fn resume(&mut self) -> Stop<T> {
+        loop {
+            match self.step() {
+                Stop::Stepped => {}
+                stop => return stop,
+            }
+        }
+    }
+
+    /// Get the addresses of the most recently executed instructions, oldest first
+    ///
+    /// Intcode has no call stack, so this is the closest thing to a backtrace.
+    pub fn backtrace(&self) -> Vec<usize> {
+        self.process.history().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_debugger() {
+        // Count down from 3, storing the counter at 20 and outputting it
+        let program = vec![
+            1101, 3, 0, 20, // 0: [20] = 3
+            4, 20, // 4: out [20]
+            1001, 20, -1, 20, // 6: [20] -= 1
+            1005, 20, 4,  // 10: jnz [20], 4
+            99, // 13: halt
+        ];
+        let mut debugger = Debugger::new(IntcodeProcess::from_vec(program));
+        debugger.add_breakpoint(6);
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(6));
+        assert_eq!(debugger.process().outputs(), &[3]);
+        assert_eq!(debugger.resume(), Stop::Breakpoint(6));
+        assert_eq!(debugger.backtrace(), vec![0, 4, 6, 10, 4]);
+
+        assert!(debugger.remove_breakpoint(6));
+        debugger.add_watchpoint(20);
+        assert_eq!(
+            debugger.resume(),
+            Stop::Watchpoint {
+                instruction: 6,
+                address: 20,
+                old: 2,
+                new: 1
+            }
+        );
+        assert_eq!(debugger.step(), Stop::Stepped);
+
+        debugger.remove_watchpoint(20);
+        assert_eq!(debugger.resume(), Stop::Error(IntcodeError::CatchFire));
+        assert_eq!(debugger.process().outputs(), &[3, 2, 1]);
+    }
+}
@@ -0,0 +1,376 @@
+//! Devices that are mapped into the memory space of a process
+//!
+//! A device owns a range of addresses: when an instruction reads or writes one of them, the
+//! device handles it instead of memory, which turns a process into a small machine with
+//! peripherals. Devices are mapped with `IntcodeProcess::map_device`. Four are provided: a
+//! [`Console`] for text, a [`Clock`] for telling time, a [`Timer`] that counts down
+//! instructions, and a [`FrameBuffer`] of pixels.
+//!
+//! Only instructions go through devices. The host's `load`, `store`, and `peek_instruction`, as
+//! well as memory diffs and rewinding, all see the memory underneath, and rewinding doesn't undo
+//! anything a device did.
+//!
+//! ```
+//! use intcode::device::Console;
+//! use intcode::IntcodeProcess;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! // Write "hi" to the console at address 100
+//! let mut process = IntcodeProcess::from_vec(vec![1101, 104, 0, 100, 1101, 105, 0, 100, 99]);
+//! let console = Rc::new(RefCell::new(Console::new()));
+//! process.map_device(100, console.clone());
+//! process.run().unwrap_err();
+//! assert_eq!(console.borrow().output(), "hi");
+//! ```
+
+use crate::Value;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Something that handles the reads and writes to a range of addresses
+///
+/// To inspect a device after giving it to a process, share it as an `Rc<RefCell<_>>`, which is a
+/// device too.
+pub trait Device<T> {
+    /// The number of addresses the device uses
+    fn size(&self) -> usize;
+
+    /// An instruction read the address `offset` past the start of the device
+    fn read(&mut self, offset: usize) -> T;
+
+    /// An instruction wrote `value` to the address `offset` past the start of the device
+    fn write(&mut self, offset: usize, value: T);
+
+    /// Called before every instruction the process executes
+    fn tick(&mut self) {}
+}
+
+impl<T, D: Device<T>> Device<T> for Rc<RefCell<D>> {
+    fn size(&self) -> usize {
+        self.borrow().size()
+    }
+
+    fn read(&mut self, offset: usize) -> T {
+        self.borrow_mut().read(offset)
+    }
+
+    fn write(&mut self, offset: usize, value: T) {
+        self.borrow_mut().write(offset, value)
+    }
+
+    fn tick(&mut self) {
+        self.borrow_mut().tick()
+    }
+}
+
+/// A device mapped into a process, see `IntcodeProcess::map_device`
+pub(crate) struct MappedDevice<T> {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) device: Box<dyn Device<T>>,
+}
+
+/// A text console with two addresses
+///
+/// Writing a character to the first address prints it, and reading the first address gets the
+/// next character of input, or 0 if there is none. Reading the second address gets the number
+/// of characters of input waiting. Values that aren't characters are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct Console {
+    input: VecDeque<u8>,
+    output: String,
+}
+
+impl Console {
+    /// Create a console with no input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add text for the program to read
+    pub fn push_input(&mut self, input: &str) {
+        self.input.extend(input.bytes());
+    }
+
+    /// Get everything the program has printed
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Take everything the program has printed since the last time
+    pub fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+}
+
+impl<T: Value> Device<T> for Console {
+    fn size(&self) -> usize {
+        2
+    }
+
+    fn read(&mut self, offset: usize) -> T {
+        match offset {
+            0 => T::from(self.input.pop_front().unwrap_or(0)),
+            _ => T::saturating_from_count(self.input.len()),
+        }
+    }
+
+    fn write(&mut self, offset: usize, value: T) {
+        let character = value
+            .to_i64()
+            .and_then(|value| u32::try_from(value).ok())
+            .and_then(std::char::from_u32);
+        if let (0, Some(character)) = (offset, character) {
+            self.output.push(character);
+        }
+    }
+}
+
+/// A clock with two addresses
+///
+/// Reading the first address gets the number of instructions executed since the clock was
+/// mapped, and reading the second gets the number of milliseconds since the clock was created.
+/// Writes are ignored. Programs can use it to pace themselves, like an arcade cabinet that
+/// shouldn't draw faster than the screen can show.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    steps: u64,
+    started: Instant,
+}
+
+impl Clock {
+    /// Create a clock that starts now
+    pub fn new() -> Self {
+        Clock {
+            steps: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Get the number of instructions executed since the clock was mapped
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Get the number of milliseconds since the clock was created
+    pub fn millis(&self) -> u64 {
+        u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::new()
+    }
+}
+
+impl<T: Value> Device<T> for Clock {
+    fn size(&self) -> usize {
+        2
+    }
+
+    fn read(&mut self, offset: usize) -> T {
+        let value = match offset {
+            0 => self.steps,
+            _ => self.millis(),
+        };
+        T::saturating_from_count(usize::try_from(value).unwrap_or(usize::MAX))
+    }
+
+    fn write(&mut self, _offset: usize, _value: T) {}
+
+    fn tick(&mut self) {
+        self.steps += 1;
+    }
+}
+
+/// A countdown timer with one address
+///
+/// Writing a number to the address starts counting down from it, by one before every
+/// instruction, and reading it gets the number of instructions left, which is 0 once the timer
+/// has run out.
+#[derive(Debug, Default, Clone)]
+pub struct Timer {
+    remaining: u64,
+}
+
+impl Timer {
+    /// Create a timer that isn't running
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of instructions left before the timer runs out
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+impl<T: Value> Device<T> for Timer {
+    fn size(&self) -> usize {
+        1
+    }
+
+    fn read(&mut self, _offset: usize) -> T {
+        T::saturating_from_count(usize::try_from(self.remaining).unwrap_or(usize::MAX))
+    }
+
+    fn write(&mut self, _offset: usize, value: T) {
+        self.remaining = value
+            .to_i64()
+            .map_or(0, |value| u64::try_from(value).unwrap_or(0));
+    }
+
+    fn tick(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+}
+
+/// A grid of pixels, with one address per pixel, row by row
+///
+/// Displaying a frame buffer draws pixels that are 0 as spaces and the rest as `#`.
+#[derive(Debug, Clone)]
+pub struct FrameBuffer<T> {
+    width: usize,
+    height: usize,
+    pixels: Vec<T>,
+}
+
+impl<T: Value> FrameBuffer<T> {
+    /// Create a frame buffer with every pixel 0
+    pub fn new(width: usize, height: usize) -> Self {
+        FrameBuffer {
+            width,
+            height,
+            pixels: vec![T::from(0); width * height],
+        }
+    }
+
+    /// Get the number of pixels in each row
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the pixel in column `x` of row `y`
+    pub fn pixel(&self, x: usize, y: usize) -> &T {
+        &self.pixels[y * self.width + x]
+    }
+}
+
+impl<T: Value> Device<T> for FrameBuffer<T> {
+    fn size(&self) -> usize {
+        self.pixels.len()
+    }
+
+    fn read(&mut self, offset: usize) -> T {
+        self.pixels[offset].clone()
+    }
+
+    fn write(&mut self, offset: usize, value: T) {
+        self.pixels[offset] = value;
+    }
+}
+
+impl<T: Value> fmt::Display for FrameBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for pixel in row {
+                let c = if *pixel == T::from(0) { ' ' } else { '#' };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::asm::assemble;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_console() {
+        // Print every character of input, shifted up by one
+        let source = "
+            const CONSOLE = 1000
+        loop:
+            add [CONSOLE], 0, [char]
+            jz [char], done
+            add [char], 1, [CONSOLE]
+            jnz 1, loop
+        done:
+            halt
+        char: .data 0
+        ";
+        let mut process = IntcodeProcess::from_vec(assemble(source).unwrap());
+        process.set_strict_memory(true);
+        let console = Rc::new(RefCell::new(Console::new()));
+        process.map_device(1000, console.clone());
+        console.borrow_mut().push_input("HAL");
+
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(console.borrow_mut().take_output(), "IBM");
+        assert_eq!(console.borrow().output(), "");
+    }
+
+    #[test]
+    fn test_clock() {
+        // Output the step count at two points, and the time once
+        let source = "
+            const CLOCK = 50
+            out [CLOCK]
+            add 0, 0, [0]
+            out [CLOCK]
+            out [CLOCK+1]
+            halt
+        ";
+        let mut process = IntcodeProcess::from_vec(assemble(source).unwrap());
+        let clock = Rc::new(RefCell::new(Clock::new()));
+        process.map_device(50, clock.clone());
+
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(&process.outputs()[..2], &[1, 3]);
+        assert!(process.outputs()[2] <= clock.borrow().millis() as i64);
+        assert_eq!(clock.borrow().steps(), 5);
+    }
+
+    #[test]
+    fn test_timer_and_frame_buffer() {
+        let source = "
+            const TIMER = 500
+            const FRAME = 600
+            add 5, 0, [TIMER]
+        wait:
+            jnz [TIMER], wait
+            add 1, 0, [FRAME+2]
+            halt
+        ";
+        let mut process = IntcodeProcess::from_vec(assemble(source).unwrap());
+        let timer = Rc::new(RefCell::new(Timer::new()));
+        let frame = Rc::new(RefCell::new(FrameBuffer::new(3, 2)));
+        process.map_device(500, timer.clone());
+        process.map_device(600, frame.clone());
+
+        let mut steps = 0;
+        while process.step() != Err(IntcodeError::CatchFire) {
+            steps += 1;
+        }
+        assert_eq!(steps, 7);
+        assert_eq!(timer.borrow().remaining(), 0);
+        assert_eq!(frame.borrow().pixel(2, 0), &1);
+        assert_eq!(frame.borrow().to_string(), "  #\n   \n");
+        assert!(process.memory().len() < 500);
+    }
+}
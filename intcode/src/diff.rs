@@ -0,0 +1,112 @@
+//! Compare the memory of two processes, or of a process and its program
+
+use crate::Value;
+use std::fmt;
+
+/// An address whose value differs between two memory spaces
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryChange<T> {
+    /// The address that differs
+    pub address: usize,
+    /// The value on the left, or `None` if the address is outside of the left memory space
+    pub left: Option<T>,
+    /// The value on the right, or `None` if the address is outside of the right memory space
+    pub right: Option<T>,
+}
+
+/// Every address whose value differs between two memory spaces, created by
+/// `IntcodeProcess::diff` and `IntcodeProcess::diff_program`
+///
+/// It displays one line per change, like `12: 1 -> 2` for the left value then the right.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryDiff<T> {
+    changes: Vec<MemoryChange<T>>,
+}
+
+impl<T: Value> MemoryDiff<T> {
+    /// Compare two memory spaces given their lengths and a way to read each address
+    pub(crate) fn between(
+        left_len: usize,
+        left: impl Fn(usize) -> T,
+        right_len: usize,
+        right: impl Fn(usize) -> T,
+    ) -> Self {
+        let changes = (0..left_len.max(right_len))
+            .filter_map(|address| {
+                let left = Some(address).filter(|&a| a < left_len).map(&left);
+                let right = Some(address).filter(|&a| a < right_len).map(&right);
+                match left == right {
+                    true => None,
+                    false => Some(MemoryChange {
+                        address,
+                        left,
+                        right,
+                    }),
+                }
+            })
+            .collect();
+
+        MemoryDiff { changes }
+    }
+}
+
+impl<T> MemoryDiff<T> {
+    /// Get the addresses that differ, in order
+    pub fn changes(&self) -> &[MemoryChange<T>] {
+        &self.changes[..]
+    }
+
+    /// Whether the memory spaces are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MemoryDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            write!(f, "{}: ", change.address)?;
+            match &change.left {
+                Some(value) => write!(f, "{}", value)?,
+                None => write!(f, "-")?,
+            }
+            write!(f, " -> ")?;
+            match &change.right {
+                Some(value) => writeln!(f, "{}", value)?,
+                None => writeln!(f, "-")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntcodeProcess;
+
+    #[test]
+    fn test_diff() {
+        let program = vec![1, 0, 0, 0, 99];
+        let mut first = IntcodeProcess::from_vec(program.clone());
+        let second = IntcodeProcess::from_vec(program.clone());
+        assert!(first.diff(&second).is_empty());
+
+        first.run().unwrap_err();
+        let diff = first.diff_program(&program);
+        assert_eq!(
+            diff.changes(),
+            &[MemoryChange {
+                address: 0,
+                left: Some(1),
+                right: Some(2)
+            }]
+        );
+        // The program changed 1 to 2, so the diff reads from the original to the current value
+        assert_eq!(diff.to_string(), "0: 1 -> 2\n");
+
+        let third = IntcodeProcess::from_vec(vec![2, 0, 0, 0, 99, 7]);
+        assert_eq!(first.diff(&third).to_string(), "5: - -> 7\n");
+        assert_eq!(third.diff(&first).to_string(), "5: 7 -> -\n");
+    }
+}
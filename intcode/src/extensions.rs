@@ -0,0 +1,168 @@
+//! Non-standard instructions for division, remainders, and random numbers
+//!
+//! These are not part of intcode, and programs that use them only run on processes that have
+//! them enabled with [`enable`]. They use opcodes that intcode leaves unused, and take parameter
+//! modes like the standard instructions:
+//!
+//! - `div a, b, target` (opcode 10): divide `a` by `b`, rounding towards zero
+//! - `mod a, b, target` (opcode 11): the remainder of dividing `a` by `b`, with the sign of `a`
+//! - `rand bound, target` (opcode 12): a random number from 0 up to, but not including, `bound`,
+//!   or any number that isn't negative when `bound` isn't positive
+//!
+//! Dividing by zero stops the process with `IntcodeError::DivisionByZero`. The random numbers
+//! come from a small generator seeded by the host, so a run can be repeated exactly.
+//!
+//! ```
+//! use intcode::extensions::{self, DIV, MOD};
+//! use intcode::IntcodeProcess;
+//!
+//! let program = vec![1110, 17, 5, 9, 1111, 17, 5, 10, 99, 0, 0];
+//! assert_eq!(DIV, 10);
+//! assert_eq!(MOD, 11);
+//! let mut process = IntcodeProcess::from_vec(program);
+//! extensions::enable(&mut process, 0);
+//! process.run().unwrap_err();
+//! assert_eq!(&process.memory()[9..], &[3, 2]);
+//! ```
+
+use crate::memory::MemoryBackend;
+use crate::opcode::CustomInstruction;
+use crate::{IntcodeError, IntcodeProcess};
+
+/// The opcode for integer division
+pub const DIV: u8 = 10;
+
+/// The opcode for the remainder of integer division
+pub const MOD: u8 = 11;
+
+/// The opcode for a random number
+pub const RAND: u8 = 12;
+
+/// A xorshift generator, which is small and plenty random for toy programs
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Xorshift gets stuck at zero, so avoid starting there
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Divide or find the remainder, depending on `operation`
+fn divide<M: MemoryBackend<i64>>(
+    instruction: &mut CustomInstruction<'_, i64, M>,
+    operation: fn(i64, i64) -> Option<i64>,
+) -> Result<(), IntcodeError> {
+    let a = instruction.read(0)?;
+    let b = instruction.read(1)?;
+    let address = instruction.address();
+    let result = match operation(a, b) {
+        Some(result) => result,
+        None if b == 0 => return Err(IntcodeError::DivisionByZero { address }),
+        None => return Err(IntcodeError::Overflow { address }),
+    };
+    instruction.write(2, result)
+}
+
+/// Enable the extended instructions on a process, with the random numbers seeded by `seed`
+///
+/// This registers handlers for [`DIV`], [`MOD`], and [`RAND`], replacing any handlers that were
+/// already registered for those opcodes.
+pub fn enable<M: MemoryBackend<i64> + 'static>(process: &mut IntcodeProcess<i64, M>, seed: u64) {
+    process.register_opcode(DIV, 3, |instruction| divide(instruction, i64::checked_div));
+    process.register_opcode(MOD, 3, |instruction| divide(instruction, i64::checked_rem));
+
+    let mut rng = Rng::new(seed);
+    process.register_opcode(RAND, 2, move |instruction| {
+        let bound = instruction.read(0)?;
+        let value = (rng.next() >> 1) as i64;
+        let value = if bound > 0 { value % bound } else { value };
+        instruction.write(1, value)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::asm::assemble;
+
+    fn run(source: &str, inputs: &[i64], seed: u64) -> Result<Vec<i64>, IntcodeError> {
+        let mut process = IntcodeProcess::from_vec(assemble(source).unwrap());
+        enable(&mut process, seed);
+        process.add_inputs(inputs);
+        match process.run().unwrap_err() {
+            IntcodeError::CatchFire => Ok(process.outputs().to_vec()),
+            e => Err(e),
+        }
+    }
+
+    #[test]
+    fn test_division() {
+        let source = "
+            in [a]
+            in [b]
+            div [a], [b], [c]
+            out [c]
+            mod [a], [b], [c]
+            out [c]
+            halt
+        a: .data 0
+        b: .data 0
+        c: .data 0
+        ";
+        assert_eq!(run(source, &[17, 5], 0), Ok(vec![3, 2]));
+        assert_eq!(run(source, &[-17, 5], 0), Ok(vec![-3, -2]));
+        assert_eq!(
+            run(source, &[1, 0], 0),
+            Err(IntcodeError::DivisionByZero { address: 4 })
+        );
+        assert_eq!(
+            run(source, &[i64::MIN, -1], 0),
+            Err(IntcodeError::Overflow { address: 4 })
+        );
+
+        // Modulo divides too, so it fails the same way
+        assert_eq!(
+            run("mod 1, 0, [0]\nhalt", &[], 0),
+            Err(IntcodeError::DivisionByZero { address: 0 })
+        );
+        assert_eq!(
+            assemble("div 1, 2, 3").unwrap_err().message,
+            "3 can't be written to; write [3] for the value at that address"
+        );
+    }
+
+    #[test]
+    fn test_rand() {
+        let source = "
+        roll:
+            rand 6, [value]
+            out [value]
+            jnz 1, roll
+        value: .data 0
+        ";
+        let rolls = |seed| {
+            let mut process = IntcodeProcess::from_vec(assemble(source).unwrap());
+            enable(&mut process, seed);
+            (0..1000)
+                .map(|_| loop {
+                    if let Some(roll) = process.step().unwrap() {
+                        break roll;
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = rolls(7);
+        assert!(first.iter().all(|roll| (0..6).contains(roll)));
+        assert!((0..6).all(|face| first.contains(&face)));
+        assert_eq!(rolls(7), first);
+        assert_ne!(rolls(8), first);
+    }
+}
@@ -0,0 +1,127 @@
+//! Render how often each address is read and written over time, as an image
+//!
+//! A [`Heatmap`] counts the accesses in a [`MemoryTrace`]. Each column of the image is an
+//! address, and each row is a slice of the run, from the first step at the top to the last at
+//! the bottom. Writes are drawn in red and reads in green, brighter for more accesses, so
+//! addresses the program keeps coming back to stand out: the stack, a grid, a score.
+
+use crate::trace::{AccessKind, MemoryTrace};
+use std::io::{self, Write};
+
+/// The number of reads and writes of every address, in every slice of a run
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    reads: Vec<u32>,
+    writes: Vec<u32>,
+}
+
+impl Heatmap {
+    /// Count the accesses in a trace, splitting the run into `rows` slices of equal length
+    pub fn from_trace<T>(trace: &MemoryTrace<T>, rows: usize) -> Self {
+        let width = trace
+            .accesses()
+            .iter()
+            .map(|access| access.address + 1)
+            .max()
+            .unwrap_or(0);
+        let height = rows.min(trace.steps()).max(1);
+        let mut heatmap = Heatmap {
+            width,
+            height,
+            reads: vec![0; width * height],
+            writes: vec![0; width * height],
+        };
+
+        for access in trace.accesses() {
+            let row = access.step * height / trace.steps().max(1);
+            let index = row * width + access.address;
+            match access.kind {
+                AccessKind::Read => heatmap.reads[index] += 1,
+                AccessKind::Write => heatmap.writes[index] += 1,
+            }
+        }
+        heatmap
+    }
+
+    /// Get the number of columns, one for every address up to the highest one accessed
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows, one for every slice of the run
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the number of times `address` was read during the slice `row`
+    pub fn reads(&self, address: usize, row: usize) -> u32 {
+        self.reads[row * self.width + address]
+    }
+
+    /// Get the number of times `address` was written during the slice `row`
+    pub fn writes(&self, address: usize, row: usize) -> u32 {
+        self.writes[row * self.width + address]
+    }
+
+    /// Write the heatmap as a binary PPM image, with one pixel per address and slice
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        let max_reads = self.reads.iter().copied().max().unwrap_or(0);
+        let max_writes = self.writes.iter().copied().max().unwrap_or(0);
+
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let mut pixels = Vec::with_capacity(self.reads.len() * 3);
+        for (&reads, &writes) in self.reads.iter().zip(&self.writes) {
+            pixels.extend(&[
+                brightness(writes, max_writes),
+                brightness(reads, max_reads),
+                0,
+            ]);
+        }
+        writer.write_all(&pixels)
+    }
+}
+
+/// How bright to draw a count, where any access at all is visible
+fn brightness(count: u32, max: u32) -> u8 {
+    match count {
+        0 => 0,
+        _ => (64 + u64::from(count) * 191 / u64::from(max)) as u8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_heatmap() {
+        // Add 2 to address 9 twice, then halt
+        let program = vec![1001, 9, 2, 9, 1001, 9, 2, 9, 99, 5];
+        let mut process = IntcodeProcess::from_vec(program);
+        process.start_tracing();
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        let trace = process.take_trace().unwrap();
+
+        let heatmap = Heatmap::from_trace(&trace, 2);
+        assert_eq!((heatmap.width(), heatmap.height()), (10, 2));
+        assert_eq!(heatmap.reads(9, 0), 2);
+        assert_eq!(heatmap.writes(9, 0), 2);
+        assert_eq!(heatmap.reads(9, 1), 0);
+        assert_eq!(heatmap.reads(8, 1), 1);
+
+        let mut ppm = Vec::new();
+        heatmap.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n10 2\n255\n"));
+        assert_eq!(ppm.len(), 12 + 10 * 2 * 3);
+        let pixel = |address: usize, row: usize| {
+            let start = 12 + (row * 10 + address) * 3;
+            &ppm[start..start + 3]
+        };
+        assert_eq!(pixel(9, 0), &[255, 255, 0]);
+        assert_eq!(pixel(8, 1), &[0, 159, 0]);
+        assert_eq!(pixel(9, 1), &[0, 0, 0]);
+    }
+}
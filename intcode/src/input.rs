@@ -0,0 +1,35 @@
+//! Sources of input that are consulted when the input queue runs dry
+
+/// A source of input values that is asked for a value whenever the input instruction executes
+/// and the input queue is empty
+///
+/// Any `FnMut() -> Option<T>` closure is an input provider, so a closure can prompt the user,
+/// read a joystick position, and so on.
+pub trait InputProvider<T> {
+    /// Provide the next input, or `None` if no input is available
+    ///
+    /// Returning `None` makes the input instruction fail with `IntcodeError::NoInputAvailable`,
+    /// just like it would without a provider.
+    fn next_input(&mut self) -> Option<T>;
+}
+
+impl<T, F> InputProvider<T> for F
+where
+    F: FnMut() -> Option<T>,
+{
+    fn next_input(&mut self) -> Option<T> {
+        self()
+    }
+}
+
+/// An input provider that always provides the same value
+///
+/// For example, day 23's network interface receives `-1` when no packet is waiting.
+#[derive(Debug, Clone)]
+pub struct Constant<T>(pub T);
+
+impl<T: Clone> InputProvider<T> for Constant<T> {
+    fn next_input(&mut self) -> Option<T> {
+        Some(self.0.clone())
+    }
+}
@@ -0,0 +1,304 @@
+//! Decoding and encoding of intcode instructions
+//!
+//! An instruction is stored as a single value: the last two digits are the opcode, and each
+//! digit above them is the mode of one parameter, starting with the hundreds digit for the first
+//! parameter. The parameters themselves follow the instruction in memory.
+//!
+//! ```
+//! use intcode::instruction::{InputParameter, Instruction, OutputParameter};
+//!
+//! let instruction = Instruction::decode(1002).unwrap();
+//! assert_eq!(
+//!     instruction,
+//!     Instruction::Mul(
+//!         InputParameter::Position,
+//!         InputParameter::Immediate,
+//!         OutputParameter::Position
+//!     )
+//! );
+//! assert_eq!(instruction.len(), 4);
+//! assert_eq!(instruction.encode(), 1002);
+//! ```
+
+use crate::IntcodeError;
+
+/// The mode of a parameter that is read
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum InputParameter {
+    /// Position mode means the parameter refers to a location in the memory space
+    Position,
+    /// Immediate mode means the parameter refers to the value that should be used
+    Immediate,
+    /// Like position mode, but relative to the relative offset register
+    Relative,
+}
+
+/// The mode of a parameter that is written to
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum OutputParameter {
+    /// Position mode means the parameter refers to a location in the memory space
+    Position,
+    /// Like position mode, but relative to the relative offset register
+    Relative,
+}
+
+/// A decoded instruction, with the modes of its parameters
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Instruction {
+    /// Opcode 1: store the sum of the first two parameters in the third
+    Add(InputParameter, InputParameter, OutputParameter),
+    /// Opcode 2: store the product of the first two parameters in the third
+    Mul(InputParameter, InputParameter, OutputParameter),
+    /// Opcode 3: store an input in the parameter
+    Input(OutputParameter),
+    /// Opcode 4: output the parameter
+    Output(InputParameter),
+    /// Opcode 5: jump to the second parameter if the first is non-zero
+    JumpIfTrue(InputParameter, InputParameter),
+    /// Opcode 6: jump to the second parameter if the first is zero
+    JumpIfFalse(InputParameter, InputParameter),
+    /// Opcode 7: store 1 in the third parameter if the first is less than the second, else 0
+    LessThan(InputParameter, InputParameter, OutputParameter),
+    /// Opcode 8: store 1 in the third parameter if the first two are equal, else 0
+    Equals(InputParameter, InputParameter, OutputParameter),
+    /// Opcode 9: add the parameter to the relative base
+    RelativeMode(InputParameter),
+    /// Opcode 99: halt
+    Halt,
+}
+
+/// Why an instruction couldn't be decoded
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The opcode doesn't exist
+    UnknownOpcode,
+    /// A parameter has a mode that doesn't exist, or isn't allowed for the parameter
+    InvalidParameterMode {
+        /// The opcode of the instruction
+        opcode: i64,
+        /// The parameter with the invalid mode, counting from 1
+        position: u32,
+        /// The invalid mode
+        mode: i64,
+    },
+}
+
+impl DecodeError {
+    pub(crate) fn into_intcode_error<T>(self, instruction: T) -> IntcodeError<T> {
+        match self {
+            DecodeError::UnknownOpcode => IntcodeError::UnknownOpcode(instruction),
+            DecodeError::InvalidParameterMode {
+                opcode,
+                position,
+                mode,
+            } => IntcodeError::InvalidParameterMode {
+                opcode,
+                position,
+                mode,
+            },
+        }
+    }
+}
+
+impl Instruction {
+    /// Decode an instruction from the value stored in memory
+    ///
+    /// Digits above the modes of the instruction's parameters are ignored, so `10099` decodes as
+    /// `Halt` just like the process executes it.
+    pub fn decode(instruction: i64) -> Result<Self, DecodeError> {
+        let instruction = match instruction % 100 {
+            1 => Instruction::Add(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+                Self::decode_output_mode(instruction, 4)?,
+            ),
+            2 => Instruction::Mul(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+                Self::decode_output_mode(instruction, 4)?,
+            ),
+            3 => Instruction::Input(Self::decode_output_mode(instruction, 2)?),
+            4 => Instruction::Output(Self::decode_input_mode(instruction, 2)?),
+            5 => Instruction::JumpIfTrue(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+            ),
+            6 => Instruction::JumpIfFalse(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+            ),
+            7 => Instruction::LessThan(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+                Self::decode_output_mode(instruction, 4)?,
+            ),
+            8 => Instruction::Equals(
+                Self::decode_input_mode(instruction, 2)?,
+                Self::decode_input_mode(instruction, 3)?,
+                Self::decode_output_mode(instruction, 4)?,
+            ),
+            9 => Instruction::RelativeMode(Self::decode_input_mode(instruction, 2)?),
+            99 => Instruction::Halt,
+            _ => Err(DecodeError::UnknownOpcode)?,
+        };
+
+        Ok(instruction)
+    }
+
+    /// Encode the instruction as the value stored in memory
+    ///
+    /// Position mode parameters are encoded as zero digits, so `decode` and `encode` round-trip
+    /// every instruction, but not every value.
+    pub fn encode(&self) -> i64 {
+        use Instruction::*;
+        match self {
+            Add(in2, in3, out4) => {
+                1 + Self::encode_input_mode(in2, 2)
+                    + Self::encode_input_mode(in3, 3)
+                    + Self::encode_output_mode(out4, 4)
+            }
+            Mul(in2, in3, out4) => {
+                2 + Self::encode_input_mode(in2, 2)
+                    + Self::encode_input_mode(in3, 3)
+                    + Self::encode_output_mode(out4, 4)
+            }
+            Input(out2) => 3 + Self::encode_output_mode(out2, 2),
+            Output(in2) => 4 + Self::encode_input_mode(in2, 2),
+            JumpIfTrue(in2, in3) => {
+                5 + Self::encode_input_mode(in2, 2) + Self::encode_input_mode(in3, 3)
+            }
+            JumpIfFalse(in2, in3) => {
+                6 + Self::encode_input_mode(in2, 2) + Self::encode_input_mode(in3, 3)
+            }
+            LessThan(in2, in3, out4) => {
+                7 + Self::encode_input_mode(in2, 2)
+                    + Self::encode_input_mode(in3, 3)
+                    + Self::encode_output_mode(out4, 4)
+            }
+            Equals(in2, in3, out4) => {
+                8 + Self::encode_input_mode(in2, 2)
+                    + Self::encode_input_mode(in3, 3)
+                    + Self::encode_output_mode(out4, 4)
+            }
+            RelativeMode(in2) => 9 + Self::encode_input_mode(in2, 2),
+            Halt => 99,
+        }
+    }
+
+    /// The opcode of the instruction, which is the last two digits of its encoding
+    pub fn opcode(&self) -> i64 {
+        self.encode() % 100
+    }
+
+    /// The number of addresses the instruction takes up, including its parameters
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        match self {
+            Instruction::Add(..)
+            | Instruction::Mul(..)
+            | Instruction::LessThan(..)
+            | Instruction::Equals(..) => 4,
+            Instruction::JumpIfTrue(..) | Instruction::JumpIfFalse(..) => 3,
+            Instruction::Input(..) | Instruction::Output(..) | Instruction::RelativeMode(..) => 2,
+            Instruction::Halt => 1,
+        }
+    }
+
+    /// A short name for the instruction, like `add` or `jnz`
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Add(..) => "add",
+            Instruction::Mul(..) => "mul",
+            Instruction::Input(..) => "in",
+            Instruction::Output(..) => "out",
+            Instruction::JumpIfTrue(..) => "jnz",
+            Instruction::JumpIfFalse(..) => "jz",
+            Instruction::LessThan(..) => "lt",
+            Instruction::Equals(..) => "eq",
+            Instruction::RelativeMode(..) => "arb",
+            Instruction::Halt => "halt",
+        }
+    }
+
+    pub(crate) fn decode_input_mode(
+        instruction: i64,
+        position: u32,
+    ) -> Result<InputParameter, DecodeError> {
+        let value = instruction / 10_i64.pow(position) % 10;
+        match value {
+            0 => Ok(InputParameter::Position),
+            1 => Ok(InputParameter::Immediate),
+            2 => Ok(InputParameter::Relative),
+            _ => Err(Self::invalid_mode(instruction, position, value)),
+        }
+    }
+
+    pub(crate) fn decode_output_mode(
+        instruction: i64,
+        position: u32,
+    ) -> Result<OutputParameter, DecodeError> {
+        let value = instruction / 10_i64.pow(position) % 10;
+        match value {
+            0 => Ok(OutputParameter::Position),
+            2 => Ok(OutputParameter::Relative),
+            _ => Err(Self::invalid_mode(instruction, position, value)),
+        }
+    }
+
+    /// The parameter whose mode is the digit at 10^`position` has the invalid mode `mode`
+    fn invalid_mode(instruction: i64, position: u32, mode: i64) -> DecodeError {
+        DecodeError::InvalidParameterMode {
+            opcode: instruction % 100,
+            position: position - 1,
+            mode,
+        }
+    }
+
+    fn encode_input_mode(mode: &InputParameter, position: u32) -> i64 {
+        10_i64.pow(position)
+            * match mode {
+                InputParameter::Position => 0,
+                InputParameter::Immediate => 1,
+                InputParameter::Relative => 2,
+            }
+    }
+
+    fn encode_output_mode(mode: &OutputParameter, position: u32) -> i64 {
+        10_i64.pow(position)
+            * match mode {
+                OutputParameter::Position => 0,
+                OutputParameter::Relative => 2,
+            }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for value in 0..100_000 {
+            if let Ok(instruction) = Instruction::decode(value) {
+                assert_eq!(Instruction::decode(instruction.encode()), Ok(instruction));
+                assert_eq!(instruction.opcode(), value % 100);
+            }
+        }
+
+        assert_eq!(Instruction::decode(21107).unwrap().encode(), 21107);
+        assert_eq!(Instruction::decode(10099).unwrap(), Instruction::Halt);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(Instruction::decode(42), Err(DecodeError::UnknownOpcode));
+        assert_eq!(
+            Instruction::decode(10103),
+            Err(DecodeError::InvalidParameterMode {
+                opcode: 3,
+                position: 1,
+                mode: 1
+            })
+        );
+    }
+}
@@ -1,288 +1,995 @@
 //! Intcode processor that runs intcode for questions for multiple days
 #![deny(missing_docs)]
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+pub mod amplifier;
+pub mod analysis;
+pub mod asm;
+mod builder;
+pub mod context;
+pub mod coverage;
+pub mod debugger;
+pub mod device;
+pub mod diff;
+#[cfg(feature = "extensions")]
+pub mod extensions;
+pub mod heatmap;
+pub mod input;
+pub mod instruction;
+pub mod link;
+pub mod memory;
+pub mod observer;
+pub mod opcode;
+pub mod peek;
+pub mod pipeline;
+pub mod program;
+#[cfg(test)]
+mod properties;
+#[cfg(test)]
+mod reference;
+pub mod repl;
+pub mod replay;
+mod rewind;
+pub mod scheduler;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod stdlib;
+pub mod stream;
+pub mod terminal;
+pub mod throttle;
+pub mod trace;
+pub mod transcript;
+pub mod transpile;
+mod value;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use amplifier::AmplifierChain;
+pub use builder::IntcodeProcessBuilder;
+use context::ErrorContext;
+use coverage::Coverage;
+use device::{Device, MappedDevice};
+use diff::MemoryDiff;
+pub use input::InputProvider;
+pub use instruction::{DecodeError, InputParameter, Instruction, OutputParameter};
+use memory::{DenseMemory, MemoryBackend};
+pub use observer::Observer;
+use observer::OutputCallback;
+use opcode::{CustomInstruction, CustomOpcode};
+use peek::{Mode, Operand, PeekedInstruction};
+pub use program::Program;
+use replay::{IoEvent, Recording};
+use rewind::{Delta, Rewind};
+use throttle::Throttle;
+use trace::{AccessKind, MemoryTrace};
+pub use value::Value;
 
 /// An error that can occur from running an intcode process
 #[derive(Debug, Eq, PartialEq)]
-pub enum IntcodeError {
-    /// The instruction found at <location> was unknown or unexpected
-    UnknownInstruction(isize),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntcodeError<T = i64> {
+    /// The instruction <instruction> has an opcode that doesn't exist
+    UnknownOpcode(T),
+    /// An instruction has a parameter mode that doesn't exist, or isn't allowed for the parameter
+    InvalidParameterMode {
+        /// The opcode of the instruction
+        opcode: i64,
+        /// The parameter with the invalid mode, counting from 1
+        position: u32,
+        /// The invalid mode
+        mode: i64,
+    },
     /// Instruction 99 (halt and catch fire) was executed
     CatchFire,
-    /// An instruction tried to access memory at <location> which is outside of the memory space
-    Segfault(isize),
+    /// An instruction tried to read memory at <location> which is outside of the memory space
+    SegfaultRead(T),
+    /// An instruction tried to write memory at <location> which is outside of the memory space
+    SegfaultWrite(T),
+    /// A jump instruction tried to jump to <target>, which is not an address
+    InvalidJumpTarget(T),
     /// The input instruction was executed, but no inputs were available
+    ///
+    /// The input instruction has no effect when this happens: the instruction counter still
+    /// points at it and memory is untouched, so the process can be resumed by adding an input
+    /// and running it again.
     NoInputAvailable,
+    /// The instruction at <address> overflowed
+    ///
+    /// Computing a relative address or adjusting the relative base is always checked. The add
+    /// and multiply instructions are only checked with checked arithmetic enabled.
+    Overflow {
+        /// The address of the instruction that overflowed
+        address: usize,
+    },
+    /// An instruction tried to grow the memory space past the memory limit to include <address>
+    MemoryLimitExceeded {
+        /// The address that was accessed
+        address: usize,
+    },
+    /// A trap handler suspended the process at the instruction at <address>
+    ///
+    /// The process has already moved past the trap, so running it again resumes normally.
+    Trapped {
+        /// The address of the trap instruction
+        address: usize,
+    },
+    /// The process ran longer than the timeout given to `IntcodeProcess::run_with_timeout`
+    ///
+    /// The process stops between two instructions, so running it again resumes normally.
+    Timeout,
+    /// The process used up the steps it was given with `IntcodeProcess::preempt_after`
+    ///
+    /// The process stops before executing another instruction, so running it again resumes
+    /// normally.
+    Preempted,
+    /// The extended division or modulo instruction at <address> divided by zero
+    ///
+    /// Only the non-standard instructions in the `extensions` module divide.
+    DivisionByZero {
+        /// The address of the instruction
+        address: usize,
+    },
 }
 
-/// The type of the input parameter
-#[derive(Debug)]
-enum InputParameter {
-    /// Position mode means the parameter refers to a location in the memory space
-    Position,
-    /// Immediate mode means the parameter refers to the value that should be used
-    Immediate,
-    /// Like position mode, but relative to the relative offset register
-    Relative,
+/// Run a program with the given inputs until it halts, and return its outputs
+///
+/// Halting is success; any other error, like running out of input, is returned.
+pub fn run<T: Value>(program: &[T], inputs: &[T]) -> Result<Vec<T>, IntcodeError<T>> {
+    let mut process = IntcodeProcess::with_memory(program.to_vec());
+    process.add_inputs(inputs);
+    match process.run().unwrap_err() {
+        IntcodeError::CatchFire => Ok(process.outputs),
+        e => Err(e),
+    }
 }
 
-/// The type of the output parameter
-#[derive(Debug)]
-enum OutputParameter {
-    /// Position mode means the parameter refers to a location in the memory space
-    Position,
-    /// Like position mode, but relative to the relative offset register
-    Relative,
+/// Patch a program's memory, run it until it halts, and return its final memory
+///
+/// The patches are `(address, value)` pairs, like day 2's noun and verb. Halting is success;
+/// any other error is returned.
+pub fn run_to_memory<T: Value>(
+    program: &[T],
+    patches: &[(T, T)],
+) -> Result<Vec<T>, IntcodeError<T>> {
+    let mut process = IntcodeProcess::with_memory(program.to_vec());
+    process.patch(patches)?;
+    match process.run().unwrap_err() {
+        IntcodeError::CatchFire => Ok(process.memory.into()),
+        e => Err(e),
+    }
 }
 
-#[derive(Debug)]
-enum Instruction {
-    Add(InputParameter, InputParameter, OutputParameter),
-    Mul(InputParameter, InputParameter, OutputParameter),
-    Input(OutputParameter),
-    Output(InputParameter),
-    JumpIfTrue(InputParameter, InputParameter),
-    JumpIfFalse(InputParameter, InputParameter),
-    LessThan(InputParameter, InputParameter, OutputParameter),
-    Equals(InputParameter, InputParameter, OutputParameter),
-    RelativeMode(InputParameter),
-    Halt,
-}
+/// The number of inputs a new process has room for before its input queue has to grow
+const INPUT_CAPACITY: usize = 64;
 
-impl Instruction {
-    pub fn decode(instruction: isize) -> Result<Self, ()> {
-        let instruction = match instruction % 100 {
-            1 => Instruction::Add(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-                Self::decode_output_mode(instruction, 4)?,
-            ),
-            2 => Instruction::Mul(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-                Self::decode_output_mode(instruction, 4)?,
-            ),
-            3 => Instruction::Input(Self::decode_output_mode(instruction, 2)?),
-            4 => Instruction::Output(Self::decode_input_mode(instruction, 2)?),
-            5 => Instruction::JumpIfTrue(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-            ),
-            6 => Instruction::JumpIfFalse(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-            ),
-            7 => Instruction::LessThan(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-                Self::decode_output_mode(instruction, 4)?,
-            ),
-            8 => Instruction::Equals(
-                Self::decode_input_mode(instruction, 2)?,
-                Self::decode_input_mode(instruction, 3)?,
-                Self::decode_output_mode(instruction, 4)?,
-            ),
-            9 => Instruction::RelativeMode(Self::decode_input_mode(instruction, 2)?),
-            99 => Instruction::Halt,
-            _ => Err(())?,
-        };
+/// The number of instructions `run_with_timeout` executes between checks of the time
+const TIMEOUT_CHECK_INTERVAL: usize = 4096;
 
-        Ok(instruction)
-    }
+/// The root processor object that runs the intcode
+///
+/// The processor is generic over the type of value stored in each memory cell. By default this
+/// is `i64`, which is large enough for every puzzle so far; see [`Value`] for the alternatives.
+/// It is also generic over how the memory space is stored; see the [`memory`] module.
+///
+/// With the `serde` feature, the full state of the process can be serialized and deserialized,
+/// except for registered callbacks, observers, and input providers, which must be registered
+/// again.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T: serde::Serialize, M: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>, M: serde::Deserialize<'de>"
+    ))
+)]
+pub struct IntcodeProcess<T = i64, M = DenseMemory<T>> {
+    memory: M,
+    instruction_counter: usize,
+    relative_base: T,
+    inputs: VecDeque<T>,
+    outputs: Vec<T>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Vec<Box<dyn Observer<T>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    input_provider: Option<Box<dyn InputProvider<T>>>,
+    default_input: Option<T>,
+    checked_arithmetic: bool,
+    strict_memory: bool,
+    memory_limit: Option<usize>,
+    recording: Option<Recording<T>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    trace: Option<MemoryTrace<T>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: VecDeque<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history_length: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    custom_opcodes: HashMap<i64, CustomOpcode<T, M>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    trap_opcode: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    decode_cache: Option<Vec<Option<Instruction>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rewind: Option<Rewind<T, M>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    coverage: Option<Coverage>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    devices: Vec<MappedDevice<T>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    steps_until_preempted: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    throttle: Option<Throttle>,
+}
 
-    pub fn encode(&self) -> isize {
-        use Instruction::*;
-        match self {
-            Add(in2, in3, out4) => {
-                1 + Self::encode_input_mode(in2, 2)
-                    + Self::encode_input_mode(in3, 3)
-                    + Self::encode_output_mode(out4, 4)
-            }
-            Mul(in2, in3, out4) => {
-                2 + Self::encode_input_mode(in2, 2)
-                    + Self::encode_input_mode(in3, 3)
-                    + Self::encode_output_mode(out4, 4)
-            }
-            Input(out2) => 3 + Self::encode_output_mode(out2, 2),
-            Output(in2) => 4 + Self::encode_input_mode(in2, 2),
-            JumpIfTrue(in2, in3) => {
-                5 + Self::encode_input_mode(in2, 2) + Self::encode_input_mode(in3, 3)
-            }
-            JumpIfFalse(in2, in3) => {
-                6 + Self::encode_input_mode(in2, 2) + Self::encode_input_mode(in3, 3)
-            }
-            LessThan(in2, in3, out4) => {
-                7 + Self::encode_input_mode(in2, 2)
-                    + Self::encode_input_mode(in3, 3)
-                    + Self::encode_output_mode(out4, 4)
-            }
-            Equals(in2, in3, out4) => {
-                8 + Self::encode_input_mode(in2, 2)
-                    + Self::encode_input_mode(in3, 3)
-                    + Self::encode_output_mode(out4, 4)
-            }
-            RelativeMode(in2) => 9 + Self::encode_input_mode(in2, 2),
-            Halt => 99,
-        }
+impl IntcodeProcess {
+    /// Create a new process with the given memory
+    pub fn from_vec(memory: Vec<i64>) -> Self {
+        Self::with_memory(memory)
     }
 
-    fn decode_input_mode(instruction: isize, position: u32) -> Result<InputParameter, ()> {
-        let position = 10_isize.pow(position);
-        let value = instruction / position % 10;
-        match value {
-            0 => Ok(InputParameter::Position),
-            1 => Ok(InputParameter::Immediate),
-            2 => Ok(InputParameter::Relative),
-            _ => Err(()),
-        }
+    /// Start building a process
+    pub fn builder() -> IntcodeProcessBuilder {
+        IntcodeProcessBuilder::new()
     }
+}
 
-    fn decode_output_mode(instruction: isize, position: u32) -> Result<OutputParameter, ()> {
-        let position = 10_isize.pow(position);
-        let value = instruction / position % 10;
-        match value {
-            0 => Ok(OutputParameter::Position),
-            2 => Ok(OutputParameter::Relative),
-            _ => Err(()),
-        }
+impl<T: Value> IntcodeProcess<T> {
+    /// Create a new process with the given memory, using any supported value type
+    pub fn with_memory(memory: Vec<T>) -> Self {
+        Self::with_backend(DenseMemory::from(memory))
     }
 
-    fn encode_input_mode(mode: &InputParameter, position: u32) -> isize {
-        10_isize.pow(position)
-            * match mode {
-                InputParameter::Position => 0,
-                InputParameter::Immediate => 1,
-                InputParameter::Relative => 2,
-            }
+    /// Get the current state of the memory
+    pub fn memory(&self) -> &[T] {
+        self.memory.as_slice()
     }
 
-    fn encode_output_mode(mode: &OutputParameter, position: u32) -> isize {
-        10_isize.pow(position)
-            * match mode {
-                OutputParameter::Position => 0,
-                OutputParameter::Relative => 2,
-            }
+    /// Get the memory for changing it in place
+    ///
+    /// Any cached decoded instructions are discarded, since any of them might change.
+    pub fn memory_mut(&mut self) -> &mut [T] {
+        if let Some(cache) = self.decode_cache.as_mut() {
+            cache.clear();
+        }
+        self.memory.as_mut_slice()
     }
 }
 
-/// The root processor object that runs the intcode
-pub struct IntcodeProcess {
-    memory: Vec<isize>,
-    instruction_counter: usize,
-    relative_base: isize,
-    inputs: VecDeque<isize>,
-    outputs: Vec<isize>,
-}
-
-impl IntcodeProcess {
-    /// Create a new process with the given memory
-    pub fn from_vec(memory: Vec<isize>) -> Self {
+impl<T: Value, M: MemoryBackend<T>> IntcodeProcess<T, M> {
+    /// Create a new process using the given storage for its memory space
+    pub fn with_backend(memory: M) -> Self {
         IntcodeProcess {
             memory,
             instruction_counter: 0,
-            relative_base: 0,
-            inputs: VecDeque::new(),
+            relative_base: T::from(0),
+            inputs: VecDeque::with_capacity(INPUT_CAPACITY),
             outputs: Vec::new(),
+            observers: Vec::new(),
+            input_provider: None,
+            default_input: None,
+            checked_arithmetic: false,
+            strict_memory: false,
+            memory_limit: None,
+            recording: None,
+            trace: None,
+            history: VecDeque::new(),
+            history_length: 0,
+            custom_opcodes: HashMap::new(),
+            trap_opcode: None,
+            decode_cache: None,
+            rewind: None,
+            coverage: None,
+            devices: Vec::new(),
+            steps_until_preempted: None,
+            throttle: None,
         }
     }
 
+    /// Choose whether the add and multiply instructions check for overflow
+    ///
+    /// When enabled, an overflowing instruction fails with `IntcodeError::Overflow` instead of
+    /// wrapping (or panicking in debug builds). Disabled by default.
+    pub fn set_checked_arithmetic(&mut self, checked: bool) {
+        self.checked_arithmetic = checked;
+    }
+
+    /// Choose whether decoded instructions are cached by address
+    ///
+    /// Tight loops execute the same instructions over and over, so caching saves decoding them
+    /// every time. Writing to an address invalidates its cached instruction, so self-modifying
+    /// programs still behave correctly. Disabled by default.
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.decode_cache = match enabled {
+            true => Some(Vec::new()),
+            false => None,
+        };
+    }
+
     /// Get the current instruction counter
     pub fn instruction_counter(&self) -> usize {
         self.instruction_counter
     }
 
-    /// Get the current state of the memory
-    pub fn memory(&self) -> &[isize] {
-        &self.memory[..]
+    /// Move the instruction counter, so the instruction at `address` executes next
+    pub fn set_instruction_counter(&mut self, address: usize) {
+        self.instruction_counter = address;
+    }
+
+    /// Get the storage backing the memory space
+    pub fn backend(&self) -> &M {
+        &self.memory
     }
 
     /// Get the current relative base
-    pub fn relative_base(&self) -> isize {
-        self.relative_base
+    pub fn relative_base(&self) -> T {
+        self.relative_base.clone()
+    }
+
+    /// List the addresses whose values differ between this process and another
+    ///
+    /// This process is the left side of the diff, and `other` is the right.
+    pub fn diff<N: MemoryBackend<T>>(&self, other: &IntcodeProcess<T, N>) -> MemoryDiff<T> {
+        MemoryDiff::between(
+            self.memory.len(),
+            |address| self.memory.get(address),
+            other.memory.len(),
+            |address| other.memory.get(address),
+        )
+    }
+
+    /// List the addresses whose values differ between this process and a program, such as the
+    /// one it started with
+    ///
+    /// The program is the left side of the diff, and this process is the right, so each change
+    /// reads from the program's value to the process's.
+    pub fn diff_program(&self, program: &[T]) -> MemoryDiff<T> {
+        MemoryDiff::between(
+            program.len(),
+            |address| program[address].clone(),
+            self.memory.len(),
+            |address| self.memory.get(address),
+        )
     }
 
     /// Retrieve a value from memory at the given address
-    pub fn load(&self, address: isize) -> Result<isize, IntcodeError> {
-        if address < 0 {
-            Err(IntcodeError::Segfault(address))?;
-        }
-        let address_u = address as usize;
-        if address_u >= self.memory.len() {
-            Err(IntcodeError::Segfault(address))?;
+    pub fn load(&self, address: T) -> Result<T, IntcodeError<T>> {
+        match address.to_address() {
+            Some(address_u) if address_u < self.memory.len() => Ok(self.memory.get(address_u)),
+            _ => Err(IntcodeError::SegfaultRead(address)),
         }
+    }
 
-        Ok(self.memory[address_u])
+    /// Retrieve a value from memory at the given address, growing the address space to include
+    /// it unless strict memory is enabled
+    pub fn load_with_resize(&mut self, address: T) -> Result<T, IntcodeError<T>> {
+        let address = Self::read_address(address)?;
+        self.grow_to_include(address, false)?;
+        Ok(self.memory.get(address))
     }
 
-    /// Retrieve a value from  memory at the given address, resizing the address space if necessary
-    fn load_with_resize(&mut self, address: isize) -> Result<isize, IntcodeError> {
-        if address < 0 {
-            Err(IntcodeError::Segfault(address))?;
+    /// Read memory for an instruction, growing the address space if necessary
+    fn read_memory(&mut self, address: usize) -> Result<T, IntcodeError<T>> {
+        if let Some(mapped) = self.device_at(address) {
+            return Ok(mapped.device.read(address - mapped.start));
         }
-        let address_u = address as usize;
-        if address_u >= self.memory.len() {
-            self.memory.resize(address_u + 1, 0);
+        self.grow_to_include(address, false)?;
+        let value = self.memory.get(address);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(
+                self.instruction_counter,
+                address,
+                AccessKind::Read,
+                value.clone(),
+            );
         }
-
-        Ok(self.memory[address_u])
+        Ok(value)
     }
 
     /// Put a value into memory at the given address
-    pub fn store(&mut self, address: isize, value: isize) -> Result<(), IntcodeError> {
-        if address < 0 {
-            Err(IntcodeError::Segfault(address))?;
+    pub fn store(&mut self, address: T, value: T) -> Result<(), IntcodeError<T>> {
+        match address.to_address() {
+            Some(address_u) if address_u < self.memory.len() => {
+                self.invalidate_decoded(address_u);
+                self.memory.set(address_u, value);
+                Ok(())
+            }
+            _ => Err(IntcodeError::SegfaultWrite(address)),
         }
-        let address_u = address as usize;
-        if address_u >= self.memory.len() {
-            Err(IntcodeError::Segfault(address))?;
+    }
+
+    /// Put several values into memory, like `store` for each `(address, value)` pair
+    ///
+    /// Every address is checked before anything is stored, so if any of them is outside of the
+    /// memory space, memory is left unchanged.
+    pub fn patch(&mut self, patches: &[(T, T)]) -> Result<(), IntcodeError<T>> {
+        let len = self.memory.len();
+        for (address, _) in patches {
+            match address.to_address() {
+                Some(address) if address < len => {}
+                _ => Err(IntcodeError::SegfaultWrite(address.clone()))?,
+            }
+        }
+        for (address, value) in patches {
+            self.store(address.clone(), value.clone())?;
         }
+        Ok(())
+    }
 
-        self.memory[address_u] = value;
+    /// Put a value into memory at the given address, growing the address space to include it
+    /// unless strict memory is enabled
+    pub fn store_with_resize(&mut self, address: T, value: T) -> Result<(), IntcodeError<T>> {
+        let address = Self::write_address(address)?;
+        self.grow_to_include(address, true)?;
+        self.invalidate_decoded(address);
+        self.memory.set(address, value);
         Ok(())
     }
 
-    /// Put a value into memory at the given address
-    fn store_with_resize(&mut self, address: isize, value: isize) -> Result<(), IntcodeError> {
-        if address < 0 {
-            Err(IntcodeError::Segfault(address))?;
+    /// Write memory for an instruction, growing the address space if necessary
+    fn write_memory(&mut self, address: usize, value: T) -> Result<(), IntcodeError<T>> {
+        if let Some(mapped) = self.device_at(address) {
+            mapped.device.write(address - mapped.start, value);
+            return Ok(());
+        }
+        self.grow_to_include(address, true)?;
+        self.invalidate_decoded(address);
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.record_write(address, self.memory.get(address));
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(
+                self.instruction_counter,
+                address,
+                AccessKind::Write,
+                value.clone(),
+            );
+        }
+        if !self.observers.is_empty() {
+            let old = self.memory.get(address);
+            for observer in self.observers.iter_mut() {
+                observer.on_memory_write(address, &old, &value);
+            }
+        }
+        self.memory.set(address, value);
+        Ok(())
+    }
+
+    /// Find the device mapped at the given address, if there is one
+    fn device_at(&mut self, address: usize) -> Option<&mut MappedDevice<T>> {
+        self.devices
+            .iter_mut()
+            .find(|mapped| (mapped.start..mapped.end).contains(&address))
+    }
+
+    /// The error for accessing an address outside of strict memory
+    ///
+    /// An address too big to be a value can only come from the instruction's own parameters
+    /// running past the largest value, so that's reported as the instruction overflowing.
+    fn segfault(&self, address: usize, write: bool) -> IntcodeError<T> {
+        match (T::from_address(address), write) {
+            (Some(address), true) => IntcodeError::SegfaultWrite(address),
+            (Some(address), false) => IntcodeError::SegfaultRead(address),
+            (None, _) => IntcodeError::Overflow {
+                address: self.instruction_counter,
+            },
         }
-        let address_u = address as usize;
-        if address_u >= self.memory.len() {
-            self.memory.resize(address_u + 1, 0);
+    }
+
+    /// Forget the cached instruction at the given address, if there is one
+    fn invalidate_decoded(&mut self, address: usize) {
+        if let Some(entry) = self
+            .decode_cache
+            .as_mut()
+            .and_then(|cache| cache.get_mut(address))
+        {
+            *entry = None;
         }
+    }
 
-        self.memory[address_u] = value;
+    /// Grow the address space to include the given address, unless memory is strict or that
+    /// exceeds the memory limit
+    fn grow_to_include(&mut self, address: usize, write: bool) -> Result<(), IntcodeError<T>> {
+        if address >= self.memory.len() && self.device_at(address).is_none() {
+            if self.strict_memory {
+                Err(self.segfault(address, write))?;
+            }
+            if let Some(limit) = self.memory_limit {
+                if address >= limit {
+                    Err(IntcodeError::MemoryLimitExceeded { address })?;
+                }
+            }
+            let len = address
+                .checked_add(1)
+                .ok_or(IntcodeError::MemoryLimitExceeded { address })?;
+            self.memory.grow(len);
+        }
         Ok(())
     }
 
+    /// Set the value the input instruction reads when no input is available, or `None` to fail
+    ///
+    /// The default input is only used when the input queue is empty and the input provider, if
+    /// any, has nothing to give. Day 23's network expects `Some(-1)`. There is no default input
+    /// by default, so the input instruction fails with `IntcodeError::NoInputAvailable`.
+    pub fn set_default_input(&mut self, default: Option<T>) {
+        self.default_input = default;
+    }
+
+    /// Choose whether accessing memory outside of the address space is an error
+    ///
+    /// With strict memory, every access past the end of memory segfaults, like day 2 expects.
+    /// Otherwise, instructions and `load_with_resize` and `store_with_resize` grow the address
+    /// space as needed, like day 9 expects. Disabled by default.
+    pub fn set_strict_memory(&mut self, strict: bool) {
+        self.strict_memory = strict;
+    }
+
+    /// Limit how large the address space may grow, or remove the limit with `None`
+    ///
+    /// Accessing an address at or beyond the limit fails with
+    /// `IntcodeError::MemoryLimitExceeded`. There is no limit by default.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.memory_limit = limit;
+    }
+
+    /// Convert a value used as an address to read from into an index into the memory space
+    fn read_address(value: T) -> Result<usize, IntcodeError<T>> {
+        value.to_address().ok_or(IntcodeError::SegfaultRead(value))
+    }
+
+    /// Convert a value used as an address to write to into an index into the memory space
+    fn write_address(value: T) -> Result<usize, IntcodeError<T>> {
+        value.to_address().ok_or(IntcodeError::SegfaultWrite(value))
+    }
+
+    /// Convert a value used as a jump target into an index into the memory space
+    fn jump_target(value: T) -> Result<usize, IntcodeError<T>> {
+        value
+            .to_address()
+            .ok_or(IntcodeError::InvalidJumpTarget(value))
+    }
+
+    /// Add the relative base to a relative mode parameter
+    fn relative_address(&self, parameter: T) -> Result<T, IntcodeError<T>> {
+        parameter
+            .checked_add(&self.relative_base)
+            .ok_or(IntcodeError::Overflow {
+                address: self.instruction_counter,
+            })
+    }
+
     /// Add a parameter to the input to be used by the input instruction
-    pub fn add_input(&mut self, value: isize) {
+    ///
+    /// This is how a process that stopped with `IntcodeError::NoInputAvailable` is resumed: add
+    /// the input, then keep running it.
+    pub fn add_input(&mut self, value: T) {
         self.inputs.push_back(value);
     }
 
-    /// Get a list of the outputs
-    pub fn outputs(&self) -> &[isize] {
+    /// Set a provider that is asked for input whenever the input queue is empty
+    ///
+    /// Queued inputs are always used first. Replaces any previously set provider.
+    pub fn set_input_provider(&mut self, provider: impl InputProvider<T> + 'static) {
+        self.input_provider = Some(Box::new(provider));
+    }
+
+    /// Remove the input provider, if any
+    pub fn clear_input_provider(&mut self) {
+        self.input_provider = None;
+    }
+
+    /// Add a string to the input, one character at a time
+    pub fn add_ascii_input(&mut self, input: &str) {
+        self.extend_inputs(input.bytes().map(T::from));
+    }
+
+    /// Add several parameters to the input, in order
+    pub fn add_inputs(&mut self, values: &[T]) {
+        self.extend_inputs(values.iter().cloned());
+    }
+
+    /// Add every parameter from an iterator to the input, in order
+    ///
+    /// The input queue is a ring buffer, so consumed inputs make room for new ones, and the
+    /// queue grows at most once per call.
+    pub fn extend_inputs(&mut self, values: impl IntoIterator<Item = T>) {
+        let values = values.into_iter();
+        self.inputs.reserve(values.size_hint().0);
+        self.inputs.extend(values);
+    }
+
+    /// Get the inputs that are queued and haven't been consumed yet, oldest first
+    pub fn queued_inputs(&self) -> impl Iterator<Item = &T> + '_ {
+        self.inputs.iter()
+    }
+
+    /// Get a list of the outputs that haven't been drained
+    pub fn outputs(&self) -> &[T] {
         &self.outputs[..]
     }
 
+    /// Register a callback that is called with every output as it is produced
+    ///
+    /// This makes it possible to stream outputs while using `run`, instead of only inspecting
+    /// them after the process stops. Outputs are still collected as usual.
+    pub fn on_output(&mut self, callback: impl FnMut(&T) + 'static) {
+        self.add_observer(OutputCallback(callback));
+    }
+
+    /// Register an observer that is told about every step, input, output, memory write, and halt
+    pub fn add_observer(&mut self, observer: impl Observer<T> + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Start recording every input consumed and output produced, discarding any earlier recording
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new());
+    }
+
+    /// Stop recording and return what was recorded, if recording was started
+    pub fn take_recording(&mut self) -> Option<Recording<T>> {
+        self.recording.take()
+    }
+
+    /// Remember the addresses of the last `length` executed instructions, for `error_context`
+    ///
+    /// Disabled (a length of 0) by default.
+    pub fn set_history_length(&mut self, length: usize) {
+        self.history_length = length;
+        while self.history.len() > length {
+            self.history.pop_front();
+        }
+    }
+
+    /// The number of executed instructions the process remembers
+    pub fn history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Get the addresses of the most recently executed instructions, oldest first
+    ///
+    /// Empty unless history was enabled with `set_history_length`.
+    pub fn history(&self) -> impl Iterator<Item = usize> + '_ {
+        self.history.iter().copied()
+    }
+
+    /// Attach the current state of the process to an error it returned
+    ///
+    /// ```text
+    /// process.run().map_err(|e| process.error_context(e))
+    /// ```
+    pub fn error_context(&self, error: IntcodeError<T>) -> ErrorContext<T> {
+        let recent = self
+            .history
+            .iter()
+            .filter(|&&address| address < self.memory.len())
+            .map(|&address| (address, self.memory.get(address)))
+            .collect();
+
+        ErrorContext {
+            error,
+            instruction_counter: self.instruction_counter,
+            relative_base: self.relative_base.clone(),
+            recent,
+        }
+    }
+
+    /// Decode the next instruction and resolve its operands, without executing it
+    ///
+    /// Fails with the error that executing the instruction would fail with while reading its
+    /// operands, or with `IntcodeError::UnknownOpcode` for opcodes the decoder doesn't know,
+    /// including registered custom opcodes. Nothing about the process changes, not even the size
+    /// of its address space.
+    pub fn peek_instruction(&self) -> Result<PeekedInstruction<T>, IntcodeError<T>> {
+        let address = self.instruction_counter;
+        let raw = self.peek_memory(address)?;
+        let instruction = match raw.to_i64().map(Instruction::decode) {
+            Some(Ok(instruction)) => instruction,
+            Some(Err(e)) => return Err(e.into_intcode_error(raw)),
+            None => return Err(IntcodeError::UnknownOpcode(raw)),
+        };
+
+        let mut operands = Vec::new();
+        for (i, mode) in instruction.modes().into_iter().enumerate() {
+            let parameter = self.peek_memory(address + i + 1)?;
+            let operand = match mode {
+                Mode::Input(InputParameter::Immediate) => Operand::Input {
+                    value: parameter.clone(),
+                    parameter,
+                },
+                Mode::Input(InputParameter::Position) => Operand::Input {
+                    value: self.peek_memory(Self::read_address(parameter.clone())?)?,
+                    parameter,
+                },
+                Mode::Input(InputParameter::Relative) => {
+                    let address = self.relative_address(parameter.clone())?;
+                    Operand::Input {
+                        value: self.peek_memory(Self::read_address(address)?)?,
+                        parameter,
+                    }
+                }
+                Mode::Output(OutputParameter::Position) => Operand::Output {
+                    address: Self::write_address(parameter.clone())?,
+                    parameter,
+                },
+                Mode::Output(OutputParameter::Relative) => Operand::Output {
+                    address: Self::write_address(self.relative_address(parameter.clone())?)?,
+                    parameter,
+                },
+            };
+            operands.push(operand);
+        }
+
+        Ok(PeekedInstruction {
+            address,
+            raw,
+            instruction,
+            operands,
+        })
+    }
+
+    /// Read memory the way an instruction would, but without growing the address space
+    fn peek_memory(&self, address: usize) -> Result<T, IntcodeError<T>> {
+        if address < self.memory.len() {
+            return Ok(self.memory.get(address));
+        }
+        if self.strict_memory {
+            Err(self.segfault(address, false))?;
+        }
+        if let Some(limit) = self.memory_limit {
+            if address >= limit {
+                Err(IntcodeError::MemoryLimitExceeded { address })?;
+            }
+        }
+        Ok(T::from(0))
+    }
+
+    /// Start tracing every memory access the program makes, discarding any earlier trace
+    ///
+    /// Only accesses made by instructions are traced, not calls to `load` or `store`.
+    pub fn start_tracing(&mut self) {
+        self.trace = Some(MemoryTrace::new());
+    }
+
+    /// Stop tracing and return the trace, if tracing was started
+    pub fn take_trace(&mut self) -> Option<MemoryTrace<T>> {
+        self.trace.take()
+    }
+
+    /// Start tracking which instructions execute, discarding any earlier coverage
+    pub fn start_coverage(&mut self) {
+        self.coverage = Some(Coverage::new(self.memory.len()));
+    }
+
+    /// Get the coverage so far, if coverage was started
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// Stop tracking coverage and return it, if coverage was started
+    pub fn take_coverage(&mut self) -> Option<Coverage> {
+        self.coverage.take()
+    }
+
+    /// Remove and return all of the outputs produced since the last drain
+    ///
+    /// Long-running programs can call this periodically to handle new outputs without keeping
+    /// track of how many they've already seen.
+    pub fn drain_outputs(&mut self) -> std::vec::Drain<'_, T> {
+        self.outputs.drain(..)
+    }
+
     /// Execute the next instruction
     ///
     /// If the command was an output, returns the value of the output. Otherwise returns nothing.
-    /// This makes implementing `run_to_output` easier. It's not very generic, but not adding
-    /// something generic until we need it.
-    fn step(&mut self) -> Result<Option<isize>, IntcodeError> {
-        let instruction = self.load_with_resize(self.instruction_counter as isize)?;
-        let instruction_num = instruction;
+    /// Embedders that need to drive the process one instruction at a time can call this directly.
+    ///
+    /// With the `tracing` feature, every step is a `trace` level span named `step`, with the
+    /// instruction's address and mnemonic, and inputs, outputs, and errors are events inside it.
+    /// Any subscriber can collect them, like `tracing-chrome` for a timeline of a run.
+    pub fn step(&mut self) -> Result<Option<T>, IntcodeError<T>> {
+        if let Some(steps) = self.steps_until_preempted.as_mut() {
+            if *steps == 0 {
+                self.steps_until_preempted = None;
+                return Err(IntcodeError::Preempted);
+            }
+            *steps -= 1;
+        }
+        if let Some(throttle) = self.throttle.as_mut() {
+            throttle.wait();
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "step",
+            address = self.instruction_counter,
+            instruction = tracing::field::Empty
+        )
+        .entered();
+
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.begin_step(
+                &self.memory,
+                self.instruction_counter,
+                self.relative_base.clone(),
+            );
+        }
+
+        let result = self.execute();
+
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            tracing::trace!(error = ?e, "stopped");
+        }
+
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.end_step(
+                result.is_err(),
+                self.memory.len(),
+                self.instruction_counter,
+                &self.relative_base,
+            );
+        }
+        result
+    }
+
+    /// Start recording enough about every step to step backwards, discarding anything recorded
+    /// earlier
+    ///
+    /// Every step records the registers, the old value of every cell it writes, and the input
+    /// it consumed or output it produced, and every `checkpoint_interval` steps a clone of the
+    /// memory backend is kept so that rewinding far doesn't have to undo every step in between.
+    /// Rewinding doesn't undo output callbacks, observers, recordings, traces, or drained outputs.
+    pub fn start_rewind(&mut self, checkpoint_interval: usize)
+    where
+        M: Clone,
+    {
+        self.rewind = Some(Rewind::new(checkpoint_interval, M::clone));
+    }
+
+    /// Stop recording steps, discarding everything recorded so far
+    pub fn stop_rewind(&mut self) {
+        self.rewind = None;
+    }
+
+    /// The number of steps that can be undone
+    pub fn rewind_steps(&self) -> usize {
+        self.rewind.as_ref().map_or(0, |rewind| rewind.steps())
+    }
+
+    /// Undo the last step, returning whether there was a step to undo
+    ///
+    /// Steps that failed without changing anything, like running out of input, aren't counted.
+    /// Inputs the step consumed are put back at the front of the input queue.
+    pub fn step_back(&mut self) -> bool {
+        match self.rewind_steps() {
+            0 => false,
+            steps => self.rewind_to(steps - 1),
+        }
+    }
+
+    /// Undo steps until only `step` steps since `start_rewind` remain, returning whether there
+    /// was anything to undo
+    pub fn rewind_to(&mut self, step: usize) -> bool {
+        let mut rewind = match self.rewind.take() {
+            Some(rewind) if step < rewind.steps() => rewind,
+            other => {
+                self.rewind = other;
+                return false;
+            }
+        };
+
+        if let Some(i) = rewind.checkpoint_after(step) {
+            // Checkpoints from this one on are discarded below, so it can be taken
+            let checkpoint = rewind.checkpoints.swap_remove(i);
+            for delta in rewind.deltas.drain(checkpoint.step..).rev() {
+                self.undo_io(&delta);
+            }
+            self.memory = checkpoint.memory;
+            if let Some(cache) = self.decode_cache.as_mut() {
+                cache.clear();
+            }
+            self.instruction_counter = checkpoint.instruction_counter;
+            self.relative_base = checkpoint.relative_base;
+        }
+        while rewind.steps() > step {
+            let delta = rewind.deltas.pop().unwrap();
+            self.undo_step(delta);
+        }
+        rewind
+            .checkpoints
+            .retain(|checkpoint| checkpoint.step < step);
+
+        self.rewind = Some(rewind);
+        true
+    }
+
+    /// Restore the state from before a step
+    fn undo_step(&mut self, delta: Delta<T>) {
+        self.undo_io(&delta);
+        for (address, old) in delta.writes.into_iter().rev() {
+            self.invalidate_decoded(address);
+            self.memory.set(address, old);
+        }
+        if delta.memory_len < self.memory.len() {
+            self.memory.truncate(delta.memory_len);
+            if let Some(cache) = self.decode_cache.as_mut() {
+                cache.truncate(delta.memory_len);
+            }
+        }
+        self.instruction_counter = delta.instruction_counter;
+        self.relative_base = delta.relative_base;
+    }
+
+    /// Put back the input a step consumed, and take back the output it produced
+    fn undo_io(&mut self, delta: &Delta<T>) {
+        if let Some(input) = delta.input.clone() {
+            self.inputs.push_front(input);
+        }
+        if delta.output {
+            self.outputs.pop();
+        }
+    }
+
+    /// Decode and execute the next instruction
+    fn execute(&mut self) -> Result<Option<T>, IntcodeError<T>> {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.begin_step();
+        }
 
-        let instruction = Instruction::decode(instruction)
-            .map_err(|_| IntcodeError::UnknownInstruction(instruction))?;
+        let address = self.instruction_counter;
+        if self.history_length > 0 {
+            if self.history.len() == self.history_length {
+                self.history.pop_front();
+            }
+            self.history.push_back(address);
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_step(address);
+        }
+        for mapped in self.devices.iter_mut() {
+            mapped.device.tick();
+        }
+
+        let cached = self
+            .decode_cache
+            .as_ref()
+            .and_then(|cache| cache.get(address).copied().flatten());
+
+        let instruction = match cached {
+            Some(instruction) => instruction,
+            None => {
+                let instruction = self.read_memory(address)?;
+                let decoded = match instruction.to_i64().map(Instruction::decode) {
+                    Some(Ok(decoded)) => decoded,
+                    Some(Err(DecodeError::UnknownOpcode)) | None => {
+                        return self.custom_instruction(instruction).map(|_| None)
+                    }
+                    Some(Err(e)) => return Err(e.into_intcode_error(instruction)),
+                };
+                if let Some(cache) = self.decode_cache.as_mut() {
+                    if cache.len() <= address {
+                        cache.resize(address + 1, None);
+                    }
+                    cache[address] = Some(decoded);
+                }
+                decoded
+            }
+        };
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.mark(address, instruction.len());
+        }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("instruction", instruction.mnemonic());
 
         match instruction {
             Instruction::Add(in0, in1, out) => self.add(in0, in1, out).map(|_| None),
             Instruction::Mul(in0, in1, out) => self.mul(in0, in1, out).map(|_| None),
             Instruction::Input(out) => self.input(out).map(|_| None),
-            Instruction::Output(in0) => self.output(in0).map(|o| Some(o)),
+            Instruction::Output(in0) => self.output(in0).map(Some),
             Instruction::JumpIfTrue(in0, in1) => self.jump_if_true(in0, in1).map(|_| None),
             Instruction::JumpIfFalse(in0, in1) => self.jump_if_false(in0, in1).map(|_| None),
             Instruction::LessThan(in0, in1, out) => self.less_than(in0, in1, out).map(|_| None),
@@ -292,15 +999,60 @@ impl IntcodeProcess {
         }
     }
 
+    /// Limit how many instructions the process executes every second, or `None` to run at full
+    /// speed
+    ///
+    /// Every step waits its turn, however the process is run, so observers and output callbacks
+    /// can draw what the process is doing at a pace that can be watched. See the [`throttle`]
+    /// module. Full speed by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of steps is 0.
+    pub fn set_steps_per_second(&mut self, steps: Option<u32>) {
+        self.throttle = steps.map(Throttle::new);
+    }
+
+    /// Stop the process with `IntcodeError::Preempted` once it executes `steps` more
+    /// instructions, however it is being run
+    ///
+    /// This lets a scheduler give each process a fair slice of time, even one that would
+    /// otherwise run for a long time without asking for input. The request only applies once:
+    /// after the process is preempted, it runs normally until this is called again.
+    pub fn preempt_after(&mut self, steps: usize) {
+        self.steps_until_preempted = Some(steps);
+    }
+
+    /// Cancel a request to preempt the process that hasn't happened yet
+    pub fn cancel_preemption(&mut self) {
+        self.steps_until_preempted = None;
+    }
+
     /// Execute all remaining instructions until an error is reached
-    pub fn run(&mut self) -> Result<(), IntcodeError> {
+    pub fn run(&mut self) -> Result<(), IntcodeError<T>> {
         loop {
             self.step()?;
         }
     }
 
+    /// Execute all remaining instructions until an error is reached, or until `timeout` passes
+    ///
+    /// The elapsed time is only checked every few thousand instructions, so the run may take
+    /// slightly longer than the timeout before failing with `IntcodeError::Timeout`.
+    pub fn run_with_timeout(&mut self, timeout: Duration) -> Result<(), IntcodeError<T>> {
+        let start = Instant::now();
+        loop {
+            for _ in 0..TIMEOUT_CHECK_INTERVAL {
+                self.step()?;
+            }
+            if start.elapsed() >= timeout {
+                Err(IntcodeError::Timeout)?;
+            }
+        }
+    }
+
     /// Execute instructions until we get an output
-    pub fn run_to_output(&mut self) -> Result<isize, IntcodeError> {
+    pub fn run_to_output(&mut self) -> Result<T, IntcodeError<T>> {
         loop {
             let result = self.step()?;
             if let Some(output) = result {
@@ -309,16 +1061,187 @@ impl IntcodeProcess {
         }
     }
 
+    /// Execute instructions until we get `count` outputs
+    ///
+    /// Useful for programs that produce their outputs in groups, like a tile of `(x, y, id)`.
+    pub fn run_to_outputs(&mut self, count: usize) -> Result<Vec<T>, IntcodeError<T>> {
+        let mut outputs = Vec::with_capacity(count);
+        for _ in 0..count {
+            outputs.push(self.run_to_output()?);
+        }
+        Ok(outputs)
+    }
+
+    /// Iterate over the outputs in groups of `size`
+    ///
+    /// The iterator ends when the process halts between two groups. If the process halts partway
+    /// through a group, or fails with any other error, the error is yielded and the iterator ends.
+    pub fn run_to_output_chunks(&mut self, size: usize) -> OutputChunks<'_, T, M> {
+        OutputChunks {
+            process: self,
+            size,
+            done: false,
+        }
+    }
+
+    /// Execute instructions as long as `predicate` holds
+    ///
+    /// The predicate is checked before every instruction, so nothing runs if it is false to
+    /// begin with. For example, `run_while(|p| p.outputs().len() < 3)` runs until three outputs
+    /// are buffered.
+    pub fn run_while(
+        &mut self,
+        mut predicate: impl FnMut(&Self) -> bool,
+    ) -> Result<(), IntcodeError<T>> {
+        while predicate(self) {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Execute instructions until one of them causes an event that matches `predicate`
+    ///
+    /// Returns the matching event.
+    pub fn run_until(
+        &mut self,
+        mut predicate: impl FnMut(&StepEvent<T>) -> bool,
+    ) -> Result<StepEvent<T>, IntcodeError<T>> {
+        loop {
+            let address = self.instruction_counter;
+            let event = match self.step()? {
+                Some(value) => StepEvent::Output { address, value },
+                None => StepEvent::Executed { address },
+            };
+            if predicate(&event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Register a handler for one of the opcodes that intcode doesn't use
+    ///
+    /// `parameters` is the number of parameters that follow the opcode; unless the handler
+    /// jumps, the process continues with the instruction after them. Registering an opcode again
+    /// replaces its handler. See the [`opcode`] module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opcode` is not between 10 and 98, because the others are already taken.
+    pub fn register_opcode(
+        &mut self,
+        opcode: u8,
+        parameters: usize,
+        handler: impl FnMut(&mut CustomInstruction<'_, T, M>) -> Result<(), IntcodeError<T>> + 'static,
+    ) {
+        assert!(
+            (10..=98).contains(&opcode),
+            "opcode {} is reserved by intcode",
+            opcode
+        );
+        self.custom_opcodes.insert(
+            i64::from(opcode),
+            CustomOpcode {
+                parameters,
+                handler: Box::new(handler),
+            },
+        );
+    }
+
+    /// Map a device into the memory space, starting at `start`
+    ///
+    /// Instructions that read or write the device's addresses are handled by the device instead
+    /// of memory, even past the end of the memory space. See the [`device`] module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device's addresses overlap a device that is already mapped.
+    pub fn map_device(&mut self, start: usize, device: impl Device<T> + 'static) {
+        let end = start + device.size();
+        assert!(
+            self.devices
+                .iter()
+                .all(|mapped| end <= mapped.start || mapped.end <= start),
+            "device at {} overlaps another device",
+            start
+        );
+        self.devices.push(MappedDevice {
+            start,
+            end,
+            device: Box::new(device),
+        });
+    }
+
+    /// Make `opcode` the trap instruction, which hands control to the host whenever it executes
+    ///
+    /// Traps let programs call into host-accelerated routines while staying valid intcode. The
+    /// handler can read the trap's parameters and use the process's memory, and can call
+    /// `suspend` to stop the process with `IntcodeError::Trapped` once the trap is done. Only one
+    /// opcode is the trap at a time: setting a new trap opcode unregisters the old one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opcode` is not between 10 and 98, like `register_opcode`.
+    pub fn set_trap(
+        &mut self,
+        opcode: u8,
+        parameters: usize,
+        handler: impl FnMut(&mut CustomInstruction<'_, T, M>) -> Result<(), IntcodeError<T>> + 'static,
+    ) {
+        self.register_opcode(opcode, parameters, handler);
+        if let Some(old) = self.trap_opcode.replace(opcode) {
+            if old != opcode {
+                self.custom_opcodes.remove(&i64::from(old));
+            }
+        }
+    }
+
+    /// Execute an instruction the decoder didn't recognize, using a registered handler
+    fn custom_instruction(&mut self, instruction: T) -> Result<(), IntcodeError<T>> {
+        let opcode = instruction.to_i64().map(|i| i % 100);
+        let mut custom = match opcode.and_then(|opcode| self.custom_opcodes.remove(&opcode)) {
+            Some(custom) => custom,
+            None => return Err(IntcodeError::UnknownOpcode(instruction)),
+        };
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.mark(self.instruction_counter, custom.parameters + 1);
+        }
+
+        // The handler is taken out of the process while it runs, so it can borrow the process
+        let mut context = CustomInstruction::new(self, instruction);
+        let result = (custom.handler)(&mut context);
+        let jumped = context.jumped;
+        let suspended = context.suspended;
+
+        let address = self.instruction_counter;
+        let parameters = custom.parameters;
+        self.custom_opcodes.insert(opcode.unwrap(), custom);
+        result?;
+        if !jumped {
+            self.instruction_counter += parameters + 1;
+        }
+        if suspended {
+            Err(IntcodeError::Trapped { address })?;
+        }
+
+        Ok(())
+    }
+
     fn load_input(
         &mut self,
         mode: InputParameter,
         parameter_location: usize,
-    ) -> Result<isize, IntcodeError> {
-        let parameter = self.load_with_resize(parameter_location as isize)?;
+    ) -> Result<T, IntcodeError<T>> {
+        let parameter = self.read_memory(parameter_location)?;
         let val = match mode {
-            InputParameter::Position => self.load_with_resize(parameter)?,
+            InputParameter::Position => {
+                let address = Self::read_address(parameter)?;
+                self.read_memory(address)?
+            }
             InputParameter::Immediate => parameter,
-            InputParameter::Relative => self.load_with_resize(parameter + self.relative_base)?,
+            InputParameter::Relative => {
+                let address = Self::read_address(self.relative_address(parameter)?)?;
+                self.read_memory(address)?
+            }
         };
         Ok(val)
     }
@@ -327,28 +1250,42 @@ impl IntcodeProcess {
         &mut self,
         mode: OutputParameter,
         parameter_location: usize,
-        value: isize,
-    ) -> Result<(), IntcodeError> {
-        let parameter = self.load_with_resize(parameter_location as isize)?;
-        match mode {
-            OutputParameter::Position => self.store_with_resize(parameter, value)?,
-            OutputParameter::Relative => {
-                self.store_with_resize(parameter + self.relative_base, value)?
-            }
-        }
+        value: T,
+    ) -> Result<(), IntcodeError<T>> {
+        let address = self.output_address(mode, parameter_location)?;
+        self.write_memory(address, value)?;
 
         Ok(())
     }
 
+    /// Find the address an output parameter refers to
+    fn output_address(
+        &mut self,
+        mode: OutputParameter,
+        parameter_location: usize,
+    ) -> Result<usize, IntcodeError<T>> {
+        let parameter = self.read_memory(parameter_location)?;
+        match mode {
+            OutputParameter::Position => Self::write_address(parameter),
+            OutputParameter::Relative => Self::write_address(self.relative_address(parameter)?),
+        }
+    }
+
     fn add(
         &mut self,
         in0: InputParameter,
         in1: InputParameter,
         out: OutputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
-        self.store_output(out, self.instruction_counter + 3, val0 + val1)?;
+        let result = match self.checked_arithmetic {
+            true => val0.checked_add(&val1).ok_or(IntcodeError::Overflow {
+                address: self.instruction_counter,
+            })?,
+            false => val0 + val1,
+        };
+        self.store_output(out, self.instruction_counter + 3, result)?;
         self.instruction_counter += 4;
 
         Ok(())
@@ -359,29 +1296,84 @@ impl IntcodeProcess {
         in0: InputParameter,
         in1: InputParameter,
         out: OutputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
-        self.store_output(out, self.instruction_counter + 3, val0 * val1)?;
+        let result = match self.checked_arithmetic {
+            true => val0.checked_mul(&val1).ok_or(IntcodeError::Overflow {
+                address: self.instruction_counter,
+            })?,
+            false => val0 * val1,
+        };
+        self.store_output(out, self.instruction_counter + 3, result)?;
         self.instruction_counter += 4;
 
         Ok(())
     }
 
-    fn input(&mut self, out: OutputParameter) -> Result<(), IntcodeError> {
-        let input = self
-            .inputs
-            .pop_front()
-            .ok_or(IntcodeError::NoInputAvailable)?;
-        self.store_output(out, self.instruction_counter + 1, input)?;
+    fn input(&mut self, out: OutputParameter) -> Result<(), IntcodeError<T>> {
+        // Nothing may change before an input is available, so the instruction can be retried
+        let (input, consumed) = match self.inputs.pop_front() {
+            Some(input) => (input, true),
+            None => match self
+                .input_provider
+                .as_mut()
+                .and_then(|provider| provider.next_input())
+            {
+                Some(input) => (input, true),
+                None => (
+                    self.default_input
+                        .clone()
+                        .ok_or(IntcodeError::NoInputAvailable)?,
+                    false,
+                ),
+            },
+        };
+        // Find somewhere to store the input before anything records it, and queue it again if
+        // there isn't, so a failed instruction doesn't consume it
+        let address = self
+            .output_address(out, self.instruction_counter + 1)
+            .and_then(|address| self.grow_to_include(address, true).map(|_| address));
+        let address = match address {
+            Ok(address) => address,
+            Err(e) => {
+                if consumed {
+                    self.inputs.push_front(input);
+                }
+                return Err(e);
+            }
+        };
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(IoEvent::Input(input.clone()));
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_input(&input);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(value = %input, "input");
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.record_input(input.clone());
+        }
+        self.write_memory(address, input)?;
         self.instruction_counter += 2;
 
         Ok(())
     }
 
-    fn output(&mut self, in0: InputParameter) -> Result<isize, IntcodeError> {
+    fn output(&mut self, in0: InputParameter) -> Result<T, IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
-        self.outputs.push(val0);
+        for observer in self.observers.iter_mut() {
+            observer.on_output(&val0);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(value = %val0, "output");
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(IoEvent::Output(val0.clone()));
+        }
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.record_output();
+        }
+        self.outputs.push(val0.clone());
         self.instruction_counter += 2;
 
         Ok(val0)
@@ -391,11 +1383,11 @@ impl IntcodeProcess {
         &mut self,
         in0: InputParameter,
         in1: InputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
-        if val0 != 0 {
-            self.instruction_counter = val1 as usize;
+        if val0 != T::from(0) {
+            self.instruction_counter = Self::jump_target(val1)?;
         } else {
             self.instruction_counter += 3;
         }
@@ -407,11 +1399,11 @@ impl IntcodeProcess {
         &mut self,
         in0: InputParameter,
         in1: InputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
-        if val0 == 0 {
-            self.instruction_counter = val1 as usize;
+        if val0 == T::from(0) {
+            self.instruction_counter = Self::jump_target(val1)?;
         } else {
             self.instruction_counter += 3;
         }
@@ -424,12 +1416,12 @@ impl IntcodeProcess {
         in0: InputParameter,
         in1: InputParameter,
         out: OutputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
         let out_val = match val0 < val1 {
-            true => 1,
-            false => 0,
+            true => T::from(1),
+            false => T::from(0),
         };
         self.store_output(out, self.instruction_counter + 3, out_val)?;
         self.instruction_counter += 4;
@@ -442,12 +1434,12 @@ impl IntcodeProcess {
         in0: InputParameter,
         in1: InputParameter,
         out: OutputParameter,
-    ) -> Result<(), IntcodeError> {
+    ) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
         let val1 = self.load_input(in1, self.instruction_counter + 2)?;
         let out_val = match val0 == val1 {
-            true => 1,
-            false => 0,
+            true => T::from(1),
+            false => T::from(0),
         };
         self.store_output(out, self.instruction_counter + 3, out_val)?;
         self.instruction_counter += 4;
@@ -455,19 +1447,142 @@ impl IntcodeProcess {
         Ok(())
     }
 
-    fn relative_mode(&mut self, in0: InputParameter) -> Result<(), IntcodeError> {
+    fn relative_mode(&mut self, in0: InputParameter) -> Result<(), IntcodeError<T>> {
         let val0 = self.load_input(in0, self.instruction_counter + 1)?;
-        self.relative_base += val0;
+        self.relative_base = self.relative_address(val0)?;
         self.instruction_counter += 2;
 
         Ok(())
     }
 
-    fn halt(&mut self) -> Result<(), IntcodeError> {
+    fn halt(&mut self) -> Result<(), IntcodeError<T>> {
+        for observer in self.observers.iter_mut() {
+            observer.on_halt(self.instruction_counter);
+        }
         Err(IntcodeError::CatchFire)
     }
 }
 
+/// Cloning a process copies its full state, so the copy can run on independently
+///
+/// Registered callbacks, observers, input providers, custom opcodes, and devices can't be cloned,
+/// so the copy starts without any, just like a deserialized process.
+impl<T: Value, M: MemoryBackend<T> + Clone> Clone for IntcodeProcess<T, M> {
+    fn clone(&self) -> Self {
+        IntcodeProcess {
+            memory: self.memory.clone(),
+            instruction_counter: self.instruction_counter,
+            relative_base: self.relative_base.clone(),
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.clone(),
+            observers: Vec::new(),
+            input_provider: None,
+            default_input: self.default_input.clone(),
+            checked_arithmetic: self.checked_arithmetic,
+            strict_memory: self.strict_memory,
+            memory_limit: self.memory_limit,
+            recording: self.recording.clone(),
+            trace: self.trace.clone(),
+            history: self.history.clone(),
+            history_length: self.history_length,
+            custom_opcodes: HashMap::new(),
+            trap_opcode: None,
+            decode_cache: self.decode_cache.clone(),
+            rewind: self.rewind.clone(),
+            coverage: self.coverage.clone(),
+            devices: Vec::new(),
+            steps_until_preempted: self.steps_until_preempted,
+            throttle: self.throttle.clone(),
+        }
+    }
+}
+
+/// Two processes are equal when their state is: memory, instruction counter, relative base, and
+/// queued inputs and outputs
+///
+/// Options like checked arithmetic, and anything registered, are not compared. Memory is
+/// compared by value, so processes with different memory backends can be equal.
+impl<T: Value, M: MemoryBackend<T>, N: MemoryBackend<T>> PartialEq<IntcodeProcess<T, N>>
+    for IntcodeProcess<T, M>
+{
+    fn eq(&self, other: &IntcodeProcess<T, N>) -> bool {
+        self.instruction_counter == other.instruction_counter
+            && self.relative_base == other.relative_base
+            && self.inputs == other.inputs
+            && self.outputs == other.outputs
+            && self.memory.len() == other.memory.len()
+            && (0..self.memory.len()).all(|i| self.memory.get(i) == other.memory.get(i))
+    }
+}
+
+impl<T: Value, M: MemoryBackend<T>> Eq for IntcodeProcess<T, M> {}
+
+/// A one-line summary of the process, for logging
+impl<T: Value, M: MemoryBackend<T>> fmt::Display for IntcodeProcess<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} (relative base {}), {} memory cells, {} queued inputs, {} outputs",
+            self.instruction_counter,
+            self.relative_base,
+            self.memory.len(),
+            self.inputs.len(),
+            self.outputs.len()
+        )
+    }
+}
+
+/// What happened when an instruction executed, as seen by `IntcodeProcess::run_until`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StepEvent<T> {
+    /// The instruction at <address> executed without producing an output
+    Executed {
+        /// The address of the instruction
+        address: usize,
+    },
+    /// The instruction at <address> produced an output
+    Output {
+        /// The address of the instruction
+        address: usize,
+        /// The output
+        value: T,
+    },
+}
+
+/// Iterator over groups of outputs, created by `IntcodeProcess::run_to_output_chunks`
+pub struct OutputChunks<'a, T, M> {
+    process: &'a mut IntcodeProcess<T, M>,
+    size: usize,
+    done: bool,
+}
+
+impl<'a, T: Value, M: MemoryBackend<T>> Iterator for OutputChunks<'a, T, M> {
+    type Item = Result<Vec<T>, IntcodeError<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut chunk = Vec::with_capacity(self.size);
+        while chunk.len() < self.size {
+            match self.process.run_to_output() {
+                Ok(output) => chunk.push(output),
+                Err(IntcodeError::CatchFire) if chunk.is_empty() => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        Some(Ok(chunk))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -479,8 +1594,8 @@ mod test {
         assert_eq!(intcode.load(0), Ok(0));
         assert_eq!(intcode.load(1), Ok(2));
         assert_eq!(intcode.load(4), Ok(8));
-        assert_eq!(intcode.load(5), Err(IntcodeError::Segfault(5)));
-        assert_eq!(intcode.load(-1), Err(IntcodeError::Segfault(-1)));
+        assert_eq!(intcode.load(5), Err(IntcodeError::SegfaultRead(5)));
+        assert_eq!(intcode.load(-1), Err(IntcodeError::SegfaultRead(-1)));
     }
 
     #[test]
@@ -493,8 +1608,41 @@ mod test {
         assert_eq!(intcode.load(1), Ok(2));
         assert_eq!(intcode.store(4, 8), Ok(()));
         assert_eq!(intcode.load(4), Ok(8));
-        assert_eq!(intcode.store(5, 10), Err(IntcodeError::Segfault(5)));
-        assert_eq!(intcode.store(-1, -2), Err(IntcodeError::Segfault(-1)));
+        assert_eq!(intcode.store(5, 10), Err(IntcodeError::SegfaultWrite(5)));
+        assert_eq!(intcode.store(-1, -2), Err(IntcodeError::SegfaultWrite(-1)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut program = IntcodeProcess::from_vec(vec![42]);
+        assert_eq!(program.run(), Err(IntcodeError::UnknownOpcode(42)));
+
+        let mut program = IntcodeProcess::from_vec(vec![10301, 0, 0, 0]);
+        assert_eq!(
+            program.run(),
+            Err(IntcodeError::InvalidParameterMode {
+                opcode: 1,
+                position: 1,
+                mode: 3
+            })
+        );
+
+        let mut program = IntcodeProcess::from_vec(vec![11101, 0, 0, 0]);
+        assert_eq!(
+            program.run(),
+            Err(IntcodeError::InvalidParameterMode {
+                opcode: 1,
+                position: 3,
+                mode: 1
+            })
+        );
+
+        let mut program = IntcodeProcess::from_vec(vec![4, -1]);
+        assert_eq!(program.run(), Err(IntcodeError::SegfaultRead(-1)));
+
+        let mut program = IntcodeProcess::from_vec(vec![3, -1]);
+        program.add_input(1);
+        assert_eq!(program.run(), Err(IntcodeError::SegfaultWrite(-1)));
     }
 
     #[test]
@@ -560,38 +1708,245 @@ mod test {
         assert_eq!(result, Err(IntcodeError::CatchFire));
         assert_eq!(processor.load(0), Ok(3895705));
 
-        let mut processor = IntcodeProcess::from_vec(input.clone());
-        processor.store(1, 64).unwrap();
-        processor.store(2, 17).unwrap();
-        let result = processor.run();
-        assert_eq!(result, Err(IntcodeError::CatchFire));
-        assert_eq!(processor.load(0), Ok(19690720));
+        let mut processor = IntcodeProcess::from_vec(input.clone());
+        processor.store(1, 64).unwrap();
+        processor.store(2, 17).unwrap();
+        let result = processor.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(processor.load(0), Ok(19690720));
+    }
+
+    #[test]
+    fn test_input_output() {
+        let input = vec![3, 5, 4, 5, 99, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_input(421);
+        let result = processor.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(processor.load(5), Ok(421));
+        assert_eq!(processor.outputs(), &[421]);
+
+        let input = vec![3, 5, 4, 5, 99, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        let result = processor.run();
+        assert_eq!(result, Err(IntcodeError::NoInputAvailable));
+
+        let input = vec![3, 9, 4, 9, 3, 10, 4, 10, 99, 0, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_input(421);
+        processor.add_input(500);
+        let result = processor.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(processor.load(9), Ok(421));
+        assert_eq!(processor.load(10), Ok(500));
+        assert_eq!(processor.outputs(), &[421, 500]);
+    }
+
+    #[test]
+    fn test_ascii_input() {
+        let input = vec![3, 9, 4, 9, 3, 9, 4, 9, 99, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_ascii_input("hi");
+        let result = processor.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(processor.outputs(), &[104, 105]);
+    }
+
+    #[test]
+    fn test_add_inputs() {
+        let input = vec![3, 9, 4, 9, 1105, 1, 0, 99, 0, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_inputs(&[1, 2]);
+        processor.extend_inputs(vec![3, 4]);
+        processor.extend_inputs((5..=200).filter(|i| i % 2 == 0));
+
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        let mut expected = vec![1, 2, 3, 4];
+        expected.extend((6..=200).step_by(2));
+        assert_eq!(processor.outputs(), &expected[..]);
+    }
+
+    #[test]
+    fn test_patch() {
+        let input = vec![1, 0, 0, 0, 99];
+        let mut processor = IntcodeProcess::from_vec(input.clone());
+        assert_eq!(
+            processor.patch(&[(1, 4), (5, 1)]),
+            Err(IntcodeError::SegfaultWrite(5))
+        );
+        assert_eq!(processor.memory(), &input[..]);
+
+        assert_eq!(processor.patch(&[(1, 4), (2, 4)]), Ok(()));
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.load(0), Ok(198));
+    }
+
+    #[test]
+    fn test_memory_mut() {
+        let input = vec![104, 1, 1105, 1, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.set_decode_cache(true);
+        assert_eq!(processor.run_to_output(), Ok(1));
+
+        processor.memory_mut()[..2].copy_from_slice(&[99, 0]);
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.outputs(), &[1]);
+    }
+
+    #[test]
+    fn test_clone() {
+        let input = vec![3, 9, 4, 9, 1105, 1, 0, 99, 0, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_input(1);
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+
+        let mut left = processor.clone();
+        let mut right = processor.clone();
+        assert!(left == processor && right == processor);
+
+        left.add_input(2);
+        right.add_input(3);
+        assert_eq!(left.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(right.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(left.outputs(), &[1, 2]);
+        assert_eq!(right.outputs(), &[1, 3]);
+        assert_eq!(processor.outputs(), &[1]);
+        assert!(left != right);
+    }
+
+    #[test]
+    fn test_eq() {
+        use memory::SparseMemory;
+
+        let input = vec![1101, 1, 2, 5, 99, 0];
+        let mut dense = IntcodeProcess::from_vec(input.clone());
+        let mut sparse = IntcodeProcess::with_backend(SparseMemory::from(input));
+        assert!(dense == sparse);
+
+        dense.step().unwrap();
+        assert!(dense != sparse);
+        sparse.step().unwrap();
+        assert!(dense == sparse);
+    }
+
+    #[test]
+    fn test_display() {
+        let input = vec![109, 5, 104, 7, 3, 0, 99];
+        let mut processor = IntcodeProcess::from_vec(input);
+        processor.add_inputs(&[1, 2]);
+        processor.run_to_output().unwrap();
+
+        assert_eq!(
+            processor.to_string(),
+            "instruction 4 (relative base 5), 7 memory cells, 2 queued inputs, 1 outputs"
+        );
+    }
+
+    #[test]
+    fn test_run_helpers() {
+        assert_eq!(
+            run(&[3, 9, 4, 9, 104, 7, 99, 0, 0, 0], &[5]),
+            Ok(vec![5, 7])
+        );
+        assert_eq!(run(&[3, 0, 99], &[]), Err(IntcodeError::NoInputAvailable));
+
+        assert_eq!(
+            run_to_memory(&[1, 0, 0, 0, 99], &[(1, 4), (2, 4)]),
+            Ok(vec![198, 4, 4, 0, 99])
+        );
+        assert_eq!(
+            run_to_memory(&[1, 0, 0, 0, 99], &[(5, 0)]),
+            Err(IntcodeError::SegfaultWrite(5))
+        );
+    }
+
+    #[test]
+    fn test_drain_outputs() {
+        let input = vec![104, 1, 104, 2, 3, 9, 4, 9, 99, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(processor.drain_outputs().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(processor.outputs().is_empty());
+
+        processor.add_input(3);
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.drain_outputs().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(processor.drain_outputs().count(), 0);
+    }
+
+    #[test]
+    fn test_resume_after_no_input() {
+        // Relative mode input, so a retry after moving past the instruction would be visible
+        let input = vec![109, 20, 203, 0, 203, 1, 1, 20, 21, 22, 4, 22, 99];
+        let mut processor = IntcodeProcess::from_vec(input);
+
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(processor.instruction_counter(), 2);
+        assert_eq!(processor.relative_base(), 20);
+        assert_eq!(processor.memory().len(), 13);
+
+        // Failing again doesn't change anything either
+        assert_eq!(processor.step(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(
+            processor.run_to_output(),
+            Err(IntcodeError::NoInputAvailable)
+        );
+        assert_eq!(processor.instruction_counter(), 2);
+
+        processor.add_input(30);
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(processor.instruction_counter(), 4);
+        assert_eq!(processor.load(20), Ok(30));
+
+        processor.add_input(12);
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.outputs(), &[42]);
+    }
+
+    #[test]
+    fn test_on_output() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let input = vec![104, 1, 104, 2, 3, 9, 4, 9, 99, 0];
+        let mut processor = IntcodeProcess::from_vec(input);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+        processor.on_output(move |output| seen_clone.borrow_mut().push(*output));
+
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+
+        processor.add_input(3);
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+        assert_eq!(processor.outputs(), &[1, 2, 3]);
     }
 
     #[test]
-    fn test_input_output() {
-        let input = vec![3, 5, 4, 5, 99, 0];
+    fn test_input_provider() {
+        let input = vec![3, 11, 4, 11, 3, 11, 4, 11, 3, 11, 99, 0];
         let mut processor = IntcodeProcess::from_vec(input);
-        processor.add_input(421);
-        let result = processor.run();
-        assert_eq!(result, Err(IntcodeError::CatchFire));
-        assert_eq!(processor.load(5), Ok(421));
-        assert_eq!(processor.outputs(), &[421]);
+
+        let mut provided = vec![20, 10];
+        processor.add_input(1);
+        processor.set_input_provider(move || provided.pop());
+
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.outputs(), &[1, 10]);
+        assert_eq!(processor.load(11), Ok(20));
 
         let input = vec![3, 5, 4, 5, 99, 0];
         let mut processor = IntcodeProcess::from_vec(input);
-        let result = processor.run();
-        assert_eq!(result, Err(IntcodeError::NoInputAvailable));
+        processor.set_input_provider(|| None);
+        assert_eq!(processor.run(), Err(IntcodeError::NoInputAvailable));
+        assert_eq!(processor.instruction_counter(), 0);
 
-        let input = vec![3, 9, 4, 9, 3, 10, 4, 10, 99, 0, 0];
-        let mut processor = IntcodeProcess::from_vec(input);
-        processor.add_input(421);
-        processor.add_input(500);
-        let result = processor.run();
-        assert_eq!(result, Err(IntcodeError::CatchFire));
-        assert_eq!(processor.load(9), Ok(421));
-        assert_eq!(processor.load(10), Ok(500));
-        assert_eq!(processor.outputs(), &[421, 500]);
+        processor.set_input_provider(input::Constant(-1));
+        assert_eq!(processor.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(processor.outputs(), &[-1]);
     }
 
     #[test]
@@ -622,6 +1977,121 @@ mod test {
         assert_eq!(program.run_to_output(), Err(IntcodeError::CatchFire));
     }
 
+    #[test]
+    fn test_run_to_outputs() {
+        let input = vec![104, 1, 104, 2, 104, 3, 104, 4, 99];
+
+        let mut program = IntcodeProcess::from_vec(input);
+        assert_eq!(program.run_to_outputs(3), Ok(vec![1, 2, 3]));
+        assert_eq!(program.run_to_outputs(0), Ok(vec![]));
+        assert_eq!(program.run_to_outputs(3), Err(IntcodeError::CatchFire));
+        assert_eq!(program.outputs(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_run_to_output_chunks() {
+        let input = vec![104, 1, 104, 2, 104, 3, 104, 4, 99];
+
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        let chunks: Vec<_> = program.run_to_output_chunks(2).collect();
+        assert_eq!(chunks, vec![Ok(vec![1, 2]), Ok(vec![3, 4])]);
+
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        let chunks: Vec<_> = program.run_to_output_chunks(3).collect();
+        assert_eq!(
+            chunks,
+            vec![Ok(vec![1, 2, 3]), Err(IntcodeError::CatchFire)]
+        );
+
+        let input = vec![104, 1, 104, 2, 3, 0, 99];
+        let mut program = IntcodeProcess::from_vec(input);
+        let chunks: Vec<_> = program.run_to_output_chunks(2).collect();
+        assert_eq!(
+            chunks,
+            vec![Ok(vec![1, 2]), Err(IntcodeError::NoInputAvailable)]
+        );
+    }
+
+    #[test]
+    fn test_preempt_after() {
+        // Count up forever
+        let input = vec![1001, 5, 1, 5, 1105, 1, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.start_rewind(4);
+        program.preempt_after(5);
+        assert_eq!(program.run(), Err(IntcodeError::Preempted));
+        assert_eq!(program.instruction_counter(), 4);
+        assert_eq!(program.memory()[5], 4);
+        assert_eq!(program.rewind_steps(), 5);
+
+        // The request is used up, so the process carries on, until it's preempted again
+        assert_eq!(program.step(), Ok(None));
+        program.preempt_after(2);
+        assert_eq!(program.run_to_output(), Err(IntcodeError::Preempted));
+        assert_eq!(program.memory()[5], 5);
+
+        program.preempt_after(0);
+        program.cancel_preemption();
+        assert_eq!(program.step(), Ok(None));
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        // Loop forever
+        let input = vec![1105, 1, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        assert_eq!(
+            program.run_with_timeout(Duration::from_millis(10)),
+            Err(IntcodeError::Timeout)
+        );
+        assert_eq!(program.instruction_counter(), 0);
+
+        let input = vec![104, 1, 99];
+        let mut program = IntcodeProcess::from_vec(input);
+        assert_eq!(
+            program.run_with_timeout(Duration::from_secs(10)),
+            Err(IntcodeError::CatchFire)
+        );
+        assert_eq!(program.outputs(), &[1]);
+    }
+
+    #[test]
+    fn test_run_while() {
+        let input = vec![104, 1, 104, 2, 104, 3, 104, 4, 99];
+        let mut program = IntcodeProcess::from_vec(input);
+
+        assert_eq!(program.run_while(|p| p.outputs().len() < 3), Ok(()));
+        assert_eq!(program.outputs(), &[1, 2, 3]);
+        assert_eq!(program.run_while(|_| false), Ok(()));
+        assert_eq!(program.instruction_counter(), 6);
+        assert_eq!(program.run_while(|_| true), Err(IntcodeError::CatchFire));
+
+        // Run until memory[9] changes
+        let input = vec![1001, 9, 1, 9, 1008, 9, 3, 10, 99, 0, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        assert_eq!(program.run_while(|p| p.memory()[9] == 0), Ok(()));
+        assert_eq!(program.instruction_counter(), 4);
+    }
+
+    #[test]
+    fn test_run_until() {
+        let input = vec![1101, 1, 1, 0, 104, 5, 104, 6, 99];
+        let mut program = IntcodeProcess::from_vec(input);
+
+        assert_eq!(
+            program.run_until(|event| matches!(event, StepEvent::Output { .. })),
+            Ok(StepEvent::Output {
+                address: 4,
+                value: 5
+            })
+        );
+        assert_eq!(
+            program.run_until(|event| *event == StepEvent::Executed { address: 8 }),
+            Err(IntcodeError::CatchFire)
+        );
+        assert_eq!(program.outputs(), &[5, 6]);
+    }
+
     #[test]
     fn test_run_to_output_example() {
         let input = vec![
@@ -669,6 +2139,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn test_relative_mode_output() {
         let input = vec![
             Instruction::RelativeMode(InputParameter::Immediate).encode(),
@@ -774,6 +2245,7 @@ mod test {
         assert_eq!(program.run(), Err(IntcodeError::CatchFire));
     }
 
+    #[test]
     fn test_quine() {
         // A test from day 9
         let input = vec![
@@ -788,6 +2260,24 @@ mod test {
         assert_eq!(program.outputs(), &input[..]);
     }
 
+    #[test]
+    fn test_decode_cache() {
+        let input = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        program.set_decode_cache(true);
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(program.outputs(), &input[..]);
+
+        // Outputs 5, then overwrites its first instruction with a halt and jumps back to it
+        let input = vec![104, 5, 1101, 0, 99, 0, 1105, 1, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_decode_cache(true);
+        assert_eq!(program.run_to_output(), Ok(5));
+        assert_eq!(program.run_to_output(), Err(IntcodeError::CatchFire));
+    }
+
     #[test]
     fn test_day_large_mult() {
         // A test from day 9
@@ -800,6 +2290,43 @@ mod test {
         assert_eq!(program.outputs(), &[1219070632396864]);
     }
 
+    #[test]
+    fn test_checked_arithmetic() {
+        let input = vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_checked_arithmetic(true);
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(program.outputs(), &[1219070632396864]);
+
+        let input = vec![
+            1101,
+            1,
+            2,
+            0,
+            1102,
+            i64::MAX,
+            2,
+            0,
+            Instruction::Halt.encode(),
+        ];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_checked_arithmetic(true);
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::Overflow { address: 4 }));
+        assert_eq!(program.instruction_counter(), 4);
+        assert_eq!(program.load(0), Ok(3));
+
+        let input = vec![1101, i64::MAX, 1, 0, 99];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_checked_arithmetic(true);
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::Overflow { address: 0 }));
+    }
+
     #[test]
     fn test_large_numbers() {
         // A test from day 9
@@ -813,6 +2340,364 @@ mod test {
         assert_eq!(program.outputs(), &[1125899906842624]);
     }
 
+    #[test]
+    fn test_i128_values() {
+        let input: Vec<i128> = vec![1102, 1 << 40, 1 << 40, 7, 4, 7, 99, 0];
+        let mut program = IntcodeProcess::with_memory(input);
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+
+        assert_eq!(program.outputs(), &[1 << 80]);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_values() {
+        use num_bigint::BigInt;
+
+        let big = BigInt::from(1) << 100_u32;
+        let input: Vec<BigInt> = vec![
+            BigInt::from(1102),
+            big.clone(),
+            big.clone(),
+            BigInt::from(7),
+            BigInt::from(4),
+            BigInt::from(7),
+            BigInt::from(99),
+            BigInt::from(0),
+        ];
+        let mut program = IntcodeProcess::with_memory(input);
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+
+        assert_eq!(program.outputs(), &[BigInt::from(1) << 200_u32]);
+    }
+
+    #[test]
+    fn test_sparse_memory() {
+        use memory::SparseMemory;
+
+        let input = vec![
+            Instruction::RelativeMode(InputParameter::Immediate).encode(),
+            1_000_000_000,
+            Instruction::Add(
+                InputParameter::Immediate,
+                InputParameter::Immediate,
+                OutputParameter::Relative,
+            )
+            .encode(),
+            1,
+            2,
+            5,
+            Instruction::Output(InputParameter::Relative).encode(),
+            5,
+            Instruction::Halt.encode(),
+        ];
+
+        let mut program = IntcodeProcess::with_backend(SparseMemory::from(input));
+
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+
+        assert_eq!(program.outputs(), &[3]);
+        assert_eq!(program.load(1_000_000_005), Ok(3));
+        assert!(program.backend().allocated_cells() < 1_000_000);
+    }
+
+    #[test]
+    fn test_pluggable_backends() {
+        use memory::{CopyOnWriteMemory, CountingMemory};
+        use std::sync::Arc;
+
+        let program = Arc::new(vec![1, 0, 0, 0, 99]);
+
+        let mut first = IntcodeProcess::with_backend(CopyOnWriteMemory::new(program.clone()));
+        let mut second = IntcodeProcess::with_backend(CountingMemory::new(CopyOnWriteMemory::new(
+            program.clone(),
+        )));
+
+        assert_eq!(first.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(first.load(0), Ok(2));
+        assert_eq!(program[0], 1);
+
+        assert_eq!(second.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(second.backend().writes(), 1);
+        assert_eq!(second.backend().reads(), 7);
+        assert_eq!(second.load(0), Ok(2));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let input = vec![
+            Instruction::Output(InputParameter::Position).encode(),
+            1000,
+            Instruction::Halt.encode(),
+        ];
+
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        program.set_memory_limit(Some(1000));
+        let result = program.run();
+        assert_eq!(
+            result,
+            Err(IntcodeError::MemoryLimitExceeded { address: 1000 })
+        );
+
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_memory_limit(Some(1001));
+        let result = program.run();
+        assert_eq!(result, Err(IntcodeError::CatchFire));
+        assert_eq!(program.memory().len(), 1001);
+    }
+
+    #[test]
+    fn test_default_input() {
+        let mut program = IntcodeProcess::from_vec(vec![3, 0, 4, 0, 3, 0, 4, 0, 99]);
+        program.set_default_input(Some(-1));
+        program.add_input(5);
+
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(program.outputs(), &[5, -1]);
+
+        let mut program = IntcodeProcess::from_vec(vec![3, 0, 99]);
+        program.set_default_input(Some(-1));
+        program.set_default_input(None);
+        assert_eq!(program.run(), Err(IntcodeError::NoInputAvailable));
+    }
+
+    #[test]
+    fn test_failed_input_store() {
+        // The input is written relative to a base of 0, to address -5
+        let mut program = IntcodeProcess::from_vec(vec![203, -5, 99]);
+        program.start_recording();
+        program.add_input(7);
+        assert_eq!(program.step(), Err(IntcodeError::SegfaultWrite(-5)));
+        assert_eq!(program.queued_inputs().collect::<Vec<_>>(), vec![&7]);
+        assert!(program.take_recording().unwrap().events().is_empty());
+
+        // An input from the provider is queued again too, so it isn't lost
+        let mut program = IntcodeProcess::from_vec(vec![203, -5, 99]);
+        let mut provided = vec![8];
+        program.set_input_provider(move || provided.pop());
+        assert_eq!(program.step(), Err(IntcodeError::SegfaultWrite(-5)));
+        assert_eq!(program.queued_inputs().collect::<Vec<_>>(), vec![&8]);
+    }
+
+    #[test]
+    fn test_invalid_jump_target() {
+        let mut program = IntcodeProcess::from_vec(vec![1105, 1, -1]);
+        assert_eq!(program.run(), Err(IntcodeError::InvalidJumpTarget(-1)));
+        assert_eq!(program.instruction_counter(), 0);
+
+        let mut program = IntcodeProcess::from_vec(vec![1106, 0, -5]);
+        assert_eq!(program.run(), Err(IntcodeError::InvalidJumpTarget(-5)));
+
+        // A jump that isn't taken doesn't check its target
+        let mut program = IntcodeProcess::from_vec(vec![1105, 0, -1, 99]);
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+    }
+
+    #[test]
+    fn test_relative_overflow() {
+        let mut program = IntcodeProcess::from_vec(vec![109, i64::MAX, 109, 1, 99]);
+        assert_eq!(program.run(), Err(IntcodeError::Overflow { address: 2 }));
+        assert_eq!(program.relative_base(), i64::MAX);
+
+        let mut program = IntcodeProcess::from_vec(vec![109, i64::MAX, 204, 1, 99]);
+        assert_eq!(program.run(), Err(IntcodeError::Overflow { address: 2 }));
+        assert_eq!(
+            program.peek_instruction(),
+            Err(IntcodeError::Overflow { address: 2 })
+        );
+
+        let mut program = IntcodeProcess::from_vec(vec![109, i64::MIN, 203, -1, 99]);
+        program.add_input(1);
+        assert_eq!(program.run(), Err(IntcodeError::Overflow { address: 2 }));
+
+        // The memory space can't grow to include the largest address
+        let mut program = IntcodeProcess::<i128>::with_memory(vec![4, usize::MAX as i128, 99]);
+        assert_eq!(
+            program.run(),
+            Err(IntcodeError::MemoryLimitExceeded {
+                address: usize::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn test_rewind() {
+        // Read two inputs into growing memory, add them, and output the sum, then halt
+        let input = vec![
+            3, 20, 3, 21, 1, 20, 21, 22, 4, 22, 21101, 1, 2, 100, 1005, 100, 19, 0, 0, 99,
+        ];
+        for &interval in &[1, 3, 1000] {
+            let mut program = IntcodeProcess::from_vec(input.clone());
+            program.set_decode_cache(true);
+            program.add_inputs(&[4, 5]);
+            program.start_rewind(interval);
+
+            let mut states = vec![program.clone()];
+            while program.step().is_ok() {
+                states.push(program.clone());
+            }
+            assert_eq!(program.outputs(), &[9]);
+            assert_eq!(program.rewind_steps(), states.len() - 1);
+
+            assert!(program.rewind_to(2));
+            assert!(program == states[2]);
+            assert_eq!(program.memory().len(), 22);
+            while program.step_back() {
+                assert!(program == states[program.rewind_steps()]);
+            }
+            assert!(program == states[0]);
+            assert_eq!(program.memory().len(), 20);
+
+            // Running again gives the same result
+            assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+            assert!(program == states[states.len() - 1]);
+            assert!(program.rewind_to(0));
+            assert!(program == states[0]);
+        }
+    }
+
+    #[test]
+    fn test_rewind_sparse_memory() {
+        use memory::SparseMemory;
+
+        // Write to an address a billion cells away, then write near the start
+        let input = vec![109, 1_000_000_000, 21101, 1, 2, 0, 1101, 3, 4, 0, 99];
+        let mut program = IntcodeProcess::with_backend(SparseMemory::from(input));
+        program.start_rewind(1);
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(program.backend().allocated_cells(), 2048);
+        assert_eq!(program.load(0), Ok(7));
+
+        // Checkpoints are copies of the sparse memory, so restoring one doesn't fill the gap
+        assert!(program.rewind_to(2));
+        assert_eq!(program.backend().len(), 1_000_000_001);
+        assert_eq!(program.backend().allocated_cells(), 2048);
+        assert_eq!(program.load(0), Ok(109));
+        assert!(program.rewind_to(0));
+        assert_eq!(program.backend().len(), 11);
+    }
+
+    #[test]
+    fn test_strict_memory() {
+        let input = vec![
+            Instruction::Output(InputParameter::Position).encode(),
+            1000,
+            Instruction::Halt.encode(),
+        ];
+
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        program.set_strict_memory(true);
+        assert_eq!(program.run(), Err(IntcodeError::SegfaultRead(1000)));
+        assert_eq!(
+            program.load_with_resize(3),
+            Err(IntcodeError::SegfaultRead(3))
+        );
+        assert_eq!(
+            program.store_with_resize(3, 1),
+            Err(IntcodeError::SegfaultWrite(3))
+        );
+        assert_eq!(program.memory().len(), 3);
+
+        let mut program = IntcodeProcess::from_vec(input);
+        assert_eq!(program.load_with_resize(5), Ok(0));
+        assert_eq!(program.store_with_resize(10, 1), Ok(()));
+        assert_eq!(program.memory().len(), 11);
+        assert_eq!(
+            program.load_with_resize(-1),
+            Err(IntcodeError::SegfaultRead(-1))
+        );
+    }
+
+    #[test]
+    fn test_register_opcode() {
+        // Opcode 42 squares its first parameter into its second; opcode 43 jumps to address 0
+        // while its parameter is nonzero
+        let input = vec![142, 3, 12, 4, 12, 43, 13, 99, 0, 0, 0, 0, 0, 0];
+
+        let mut program = IntcodeProcess::from_vec(input.clone());
+        assert_eq!(program.run(), Err(IntcodeError::UnknownOpcode(142)));
+
+        let mut program = IntcodeProcess::from_vec(input);
+        program.register_opcode(42, 2, |instruction| {
+            let value = instruction.read(0)?;
+            instruction.write(1, value * value)
+        });
+        program.register_opcode(43, 1, |instruction| {
+            if instruction.read(0)? != 0 {
+                instruction.process().store(13, 0)?;
+                instruction.jump(0);
+            }
+            Ok(())
+        });
+        program.store(13, 1).unwrap();
+
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(program.outputs(), &[9, 9]);
+    }
+
+    #[test]
+    fn test_trap() {
+        // Trap 50 with service 1 doubles the value at the given address; service 2 suspends
+        let input = vec![1150, 1, 9, 4, 9, 1150, 2, 0, 99, 21];
+
+        let mut program = IntcodeProcess::from_vec(input);
+        program.set_trap(60, 2, |_| Ok(()));
+        program.set_trap(50, 2, |trap| {
+            match trap.read(0)? {
+                1 => {
+                    let address = trap.read(1)?;
+                    let value = trap.process().load(address)?;
+                    trap.process().store(address, value * 2)?;
+                }
+                _ => trap.suspend(),
+            }
+            Ok(())
+        });
+
+        assert_eq!(program.run(), Err(IntcodeError::Trapped { address: 5 }));
+        assert_eq!(program.outputs(), &[42]);
+        assert_eq!(program.instruction_counter(), 8);
+        assert_eq!(program.run(), Err(IntcodeError::CatchFire));
+        assert!(!program.custom_opcodes.contains_key(&60));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_builtin_opcode() {
+        let mut program = IntcodeProcess::from_vec(vec![99]);
+        program.register_opcode(1, 3, |_| Ok(()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let input = vec![3, 11, 4, 11, 3, 11, 4, 11, 99, 0, 0, 0];
+        let mut program = IntcodeProcess::from_vec(input);
+        program.add_input(5);
+        assert_eq!(program.run(), Err(IntcodeError::NoInputAvailable));
+
+        let json = serde_json::to_string(&program).unwrap();
+        let mut restored: IntcodeProcess = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.instruction_counter(), 4);
+        assert_eq!(restored.memory(), program.memory());
+        assert_eq!(restored.outputs(), &[5]);
+
+        restored.add_input(6);
+        assert_eq!(restored.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(restored.outputs(), &[5, 6]);
+
+        let error: IntcodeError =
+            serde_json::from_str(&serde_json::to_string(&IntcodeError::SegfaultRead(-1)).unwrap())
+                .unwrap();
+        assert_eq!(error, IntcodeError::SegfaultRead(-1));
+    }
+
     #[test]
     fn test_extra_space() {
         let input = vec![
@@ -850,4 +2735,88 @@ mod test {
 
         assert_eq!(program.outputs(), &[3]);
     }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+        use tracing_core::span::Current;
+
+        /// Collect the fields of every span and event as strings
+        #[derive(Clone, Default)]
+        struct Collect(
+            Arc<Mutex<Vec<String>>>,
+            Arc<Mutex<Option<&'static Metadata<'static>>>>,
+        );
+
+        impl Visit for Collect {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{}={:?}", field.name(), value));
+            }
+        }
+
+        impl Subscriber for Collect {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                *self.1.lock().unwrap() = Some(span.metadata());
+                span.record(&mut self.clone());
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, values: &Record<'_>) {
+                values.record(&mut self.clone());
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut self.clone());
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+
+            fn current_span(&self) -> Current {
+                match *self.1.lock().unwrap() {
+                    Some(metadata) => Current::new(Id::from_u64(1), metadata),
+                    None => Current::none(),
+                }
+            }
+        }
+
+        let collect = Collect::default();
+        tracing::subscriber::with_default(collect.clone(), || {
+            let mut process = IntcodeProcess::from_vec(vec![3, 0, 4, 0, 99]);
+            process.add_input(7);
+            assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        });
+
+        assert_eq!(
+            *collect.0.lock().unwrap(),
+            vec![
+                "address=0",
+                "instruction=\"in\"",
+                "message=input",
+                "value=7",
+                "address=2",
+                "instruction=\"out\"",
+                "message=output",
+                "value=7",
+                "address=4",
+                "instruction=\"halt\"",
+                "message=stopped",
+                "error=CatchFire",
+            ]
+        );
+    }
 }
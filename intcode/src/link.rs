@@ -0,0 +1,165 @@
+//! Combine separately assembled modules into one program
+//!
+//! Each module is assembled on its own into an [`Object`] by [`assemble_object`], which exports
+//! the symbols named by its `.global` lines and leaves any symbols it doesn't define for the
+//! linker. [`link`] places the modules one after another, in the order given, moves the addresses
+//! in each module to where it ends up, and fills in the symbols modules use from each other.
+//!
+//! ```
+//! use intcode::link::link_sources;
+//!
+//! let main = "
+//!     add 21, 0, [value]
+//!     call double
+//!     out [value]
+//!     halt
+//! ";
+//! let library = "
+//!     .global double, value
+//! double:
+//!     mul [value], 2, [value]
+//!     ret
+//! value: .data 0
+//! ";
+//! let stack = ".global stack\nstack:";
+//! let program = link_sources(&["arb stack", main, library, stack]).unwrap();
+//! assert_eq!(intcode::run(&program, &[]).unwrap(), vec![42]);
+//! ```
+
+use crate::asm::{assemble_object, AssembleError, Export, Object};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An error from linking modules
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LinkError {
+    /// A module, counting from 0, didn't assemble
+    Assemble {
+        /// The module with the error
+        module: usize,
+        /// What is wrong with it
+        error: AssembleError,
+    },
+    /// More than one module exports a symbol with this name
+    DuplicateSymbol(String),
+    /// A module uses a symbol that no module exports
+    UndefinedSymbol(String),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkError::Assemble { module, error } => write!(f, "module {}, {}", module, error),
+            LinkError::DuplicateSymbol(name) => {
+                write!(f, "{} is exported by more than one module", name)
+            }
+            LinkError::UndefinedSymbol(name) => write!(f, "undefined symbol {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// Link objects into a program, in the order given
+pub fn link(objects: &[Object]) -> Result<Vec<i64>, LinkError> {
+    let mut starts = Vec::with_capacity(objects.len());
+    let mut symbols = BTreeMap::new();
+    let mut len = 0;
+    for object in objects {
+        starts.push(len);
+        for (name, export) in &object.exports {
+            let value = match *export {
+                Export::Address(offset) => (len + offset) as i64,
+                Export::Constant(value) => value,
+            };
+            if symbols.insert(name.as_str(), value).is_some() {
+                return Err(LinkError::DuplicateSymbol(name.clone()));
+            }
+        }
+        len += object.code.len();
+    }
+
+    let mut program = Vec::with_capacity(len);
+    for (object, start) in objects.iter().zip(starts) {
+        let mut code = object.code.clone();
+        for &offset in &object.relocations {
+            code[offset] += start as i64;
+        }
+        for (offset, name) in &object.imports {
+            let value = symbols
+                .get(name.as_str())
+                .ok_or_else(|| LinkError::UndefinedSymbol(name.clone()))?;
+            code[*offset] += value;
+        }
+        program.extend(code);
+    }
+    Ok(program)
+}
+
+/// Assemble each source as its own module, and link them into a program
+pub fn link_sources(sources: &[&str]) -> Result<Vec<i64>, LinkError> {
+    let objects = sources
+        .iter()
+        .enumerate()
+        .map(|(module, source)| {
+            assemble_object(source).map_err(|error| LinkError::Assemble { module, error })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    link(&objects)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run;
+
+    #[test]
+    fn test_link() {
+        let main = "
+            arb stack
+        loop:
+            out [counter]
+            add [counter], 1, [counter]
+            call under_limit
+            jnz [rb+1], loop
+            halt
+            .global counter, LIMIT
+        counter: .data 0
+            const LIMIT = 3
+        ";
+        let library = "
+            .global under_limit, stack
+        under_limit:
+            lt [counter], LIMIT, [rb+1]
+            ret
+        ; Symbols that aren't global stay in their module
+        loop: .data loop
+        stack:
+        ";
+        let program = link_sources(&[main, library]).unwrap();
+        assert_eq!(program[program.len() - 1], program.len() as i64 - 1);
+        assert_eq!(run(&program, &[]).unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_link_errors() {
+        assert_eq!(
+            link_sources(&[".global a\na: halt", ".global a\na: halt"]),
+            Err(LinkError::DuplicateSymbol("a".into()))
+        );
+        assert_eq!(
+            link_sources(&["jnz 1, nowhere"]),
+            Err(LinkError::UndefinedSymbol("nowhere".into()))
+        );
+        assert_eq!(
+            link_sources(&["halt", "halt\n.global b"])
+                .unwrap_err()
+                .to_string(),
+            "module 1, line 2: global symbol b isn't defined"
+        );
+        assert_eq!(
+            assemble_object("out [10-other]").unwrap_err().message,
+            "other is from another module, so can't be subtracted"
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Storage strategies for the memory space of an intcode process
+//!
+//! An `IntcodeProcess` is parameterized by a [`MemoryBackend`], which defaults to
+//! [`DenseMemory`]. Other backends trade raw speed for memory efficiency ([`SparseMemory`]),
+//! cheap sharing of a common program ([`CopyOnWriteMemory`]), or insight into what the program is
+//! doing ([`CountingMemory`]). Anything implementing the trait can be plugged in.
+
+use crate::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Storage for the memory space of an intcode process
+///
+/// The memory space is a contiguous range of addresses starting at zero. Addresses past `len`
+/// are out of bounds until the space is grown to include them, and newly included cells read as
+/// zero. The process does all bounds checking before calling `get` or `set`.
+pub trait MemoryBackend<T> {
+    /// The number of addresses in the memory space
+    fn len(&self) -> usize;
+
+    /// Whether the memory space is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the value at the given address, which must be less than `len`
+    fn get(&self, address: usize) -> T;
+
+    /// Write the value at the given address, which must be less than `len`
+    fn set(&mut self, address: usize, value: T);
+
+    /// Grow the memory space to contain at least `len` addresses
+    fn grow(&mut self, len: usize);
+
+    /// Shrink the memory space to contain at most `len` addresses
+    ///
+    /// Addresses that are removed read as zero if the space grows to include them again. The
+    /// default implementation can't shrink the space, so it zeroes those addresses instead,
+    /// which keeps rewinding correct for backends that don't implement this.
+    fn truncate(&mut self, len: usize)
+    where
+        T: From<u8>,
+    {
+        for address in len..self.len() {
+            self.set(address, T::from(0));
+        }
+    }
+}
+
+/// Memory stored as one contiguous `Vec`
+///
+/// This is the fastest option for typical programs, but a program that touches a far-away
+/// address forces every address in between to be allocated.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DenseMemory<T> {
+    cells: Vec<T>,
+}
+
+impl<T> DenseMemory<T> {
+    /// Get the memory as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells[..]
+    }
+
+    /// Get the memory as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells[..]
+    }
+}
+
+impl<T> From<Vec<T>> for DenseMemory<T> {
+    fn from(cells: Vec<T>) -> Self {
+        DenseMemory { cells }
+    }
+}
+
+impl<T> From<DenseMemory<T>> for Vec<T> {
+    fn from(memory: DenseMemory<T>) -> Self {
+        memory.cells
+    }
+}
+
+impl<T: Value> MemoryBackend<T> for DenseMemory<T> {
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn get(&self, address: usize) -> T {
+        self.cells[address].clone()
+    }
+
+    fn set(&mut self, address: usize, value: T) {
+        self.cells[address] = value;
+    }
+
+    fn grow(&mut self, len: usize) {
+        if len > self.cells.len() {
+            self.cells.resize(len, T::from(0));
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.cells.truncate(len);
+    }
+}
+
+const PAGE_SIZE: usize = 1024;
+
+/// Memory stored as fixed-size pages that are only allocated once they are written to
+///
+/// Programs that use addresses like 1_000_000_000 (for example, with an extreme relative base)
+/// only pay for the pages they actually write, at the cost of a hash lookup on every access.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SparseMemory<T> {
+    pages: HashMap<usize, Vec<T>>,
+    len: usize,
+}
+
+impl<T> SparseMemory<T> {
+    /// Get the number of cells that have actually been allocated
+    pub fn allocated_cells(&self) -> usize {
+        self.pages.len() * PAGE_SIZE
+    }
+}
+
+impl<T: Value> From<Vec<T>> for SparseMemory<T> {
+    fn from(cells: Vec<T>) -> Self {
+        let mut memory = SparseMemory {
+            pages: HashMap::new(),
+            len: cells.len(),
+        };
+        for (address, value) in cells.into_iter().enumerate() {
+            memory.set(address, value);
+        }
+        memory
+    }
+}
+
+impl<T: Value> MemoryBackend<T> for SparseMemory<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, address: usize) -> T {
+        match self.pages.get(&(address / PAGE_SIZE)) {
+            Some(page) => page[address % PAGE_SIZE].clone(),
+            None => T::from(0),
+        }
+    }
+
+    fn set(&mut self, address: usize, value: T) {
+        let page = self
+            .pages
+            .entry(address / PAGE_SIZE)
+            .or_insert_with(|| vec![T::from(0); PAGE_SIZE]);
+        page[address % PAGE_SIZE] = value;
+    }
+
+    fn grow(&mut self, len: usize) {
+        if len > self.len {
+            self.len = len;
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            truncate_pages(&mut self.pages, len);
+        }
+    }
+}
+
+/// Remove the pages past `len`, and zero the cells past `len` in the page that contains it
+fn truncate_pages<T: Value>(pages: &mut HashMap<usize, Vec<T>>, len: usize) {
+    pages.retain(|&page_number, _| page_number * PAGE_SIZE < len);
+    if let Some(page) = pages.get_mut(&(len / PAGE_SIZE)) {
+        for cell in &mut page[len % PAGE_SIZE..] {
+            *cell = T::from(0);
+        }
+    }
+}
+
+/// Memory that shares a read-only base image, copying pages only when they are written to
+///
+/// Useful for brute-force searches that start thousands of processes from the same program:
+/// each process shares the base and only pays for the pages it modifies.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CopyOnWriteMemory<T> {
+    base: Arc<Vec<T>>,
+    /// How much of the base is still visible, since truncating hides the rest
+    base_len: usize,
+    pages: HashMap<usize, Vec<T>>,
+    len: usize,
+}
+
+impl<T> CopyOnWriteMemory<T> {
+    /// Create memory backed by the given shared base image
+    pub fn new(base: Arc<Vec<T>>) -> Self {
+        let len = base.len();
+        CopyOnWriteMemory {
+            base,
+            base_len: len,
+            pages: HashMap::new(),
+            len,
+        }
+    }
+
+    /// Get the number of pages that have been copied from the base image
+    pub fn copied_pages(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+impl<T> From<Arc<Vec<T>>> for CopyOnWriteMemory<T> {
+    fn from(base: Arc<Vec<T>>) -> Self {
+        CopyOnWriteMemory::new(base)
+    }
+}
+
+impl<T: Value> CopyOnWriteMemory<T> {
+    fn base_value(&self, address: usize) -> T {
+        match self.base.get(address) {
+            Some(value) if address < self.base_len => value.clone(),
+            _ => T::from(0),
+        }
+    }
+}
+
+impl<T: Value> MemoryBackend<T> for CopyOnWriteMemory<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, address: usize) -> T {
+        match self.pages.get(&(address / PAGE_SIZE)) {
+            Some(page) => page[address % PAGE_SIZE].clone(),
+            None => self.base_value(address),
+        }
+    }
+
+    fn set(&mut self, address: usize, value: T) {
+        let page_number = address / PAGE_SIZE;
+        if !self.pages.contains_key(&page_number) {
+            let start = page_number * PAGE_SIZE;
+            let page = (start..start + PAGE_SIZE)
+                .map(|address| self.base_value(address))
+                .collect();
+            self.pages.insert(page_number, page);
+        }
+
+        let page = self.pages.get_mut(&page_number).unwrap();
+        page[address % PAGE_SIZE] = value;
+    }
+
+    fn grow(&mut self, len: usize) {
+        if len > self.len {
+            self.len = len;
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.base_len = self.base_len.min(len);
+            truncate_pages(&mut self.pages, len);
+        }
+    }
+}
+
+/// A wrapper around another backend that counts every read and write
+///
+/// Handy for debugging and profiling: wrap the real backend, run the program, then look at the
+/// counts.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountingMemory<M> {
+    inner: M,
+    reads: Cell<usize>,
+    writes: usize,
+}
+
+impl<M> CountingMemory<M> {
+    /// Wrap the given backend
+    pub fn new(inner: M) -> Self {
+        CountingMemory {
+            inner,
+            reads: Cell::new(0),
+            writes: 0,
+        }
+    }
+
+    /// Get the wrapped backend
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Get the number of reads so far
+    pub fn reads(&self) -> usize {
+        self.reads.get()
+    }
+
+    /// Get the number of writes so far
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+}
+
+impl<T: Value, M: MemoryBackend<T>> MemoryBackend<T> for CountingMemory<M> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn get(&self, address: usize) -> T {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.get(address)
+    }
+
+    fn set(&mut self, address: usize, value: T) {
+        self.writes += 1;
+        self.inner.set(address, value)
+    }
+
+    fn grow(&mut self, len: usize) {
+        self.inner.grow(len)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dense_memory() {
+        let mut memory = DenseMemory::from(vec![1_i64, 2, 3]);
+        assert_eq!(memory.len(), 3);
+        assert_eq!(memory.get(1), 2);
+
+        memory.grow(5);
+        assert_eq!(memory.len(), 5);
+        assert_eq!(memory.get(4), 0);
+
+        memory.set(4, 10);
+        assert_eq!(memory.as_slice(), &[1, 2, 3, 0, 10]);
+    }
+
+    #[test]
+    fn test_sparse_memory() {
+        let mut memory = SparseMemory::from(vec![1_i64, 2, 3]);
+        assert_eq!(memory.len(), 3);
+        assert_eq!(memory.get(1), 2);
+        assert_eq!(memory.allocated_cells(), PAGE_SIZE);
+
+        memory.grow(1_000_000_001);
+        assert_eq!(memory.len(), 1_000_000_001);
+        assert_eq!(memory.get(1_000_000_000), 0);
+        assert_eq!(memory.allocated_cells(), PAGE_SIZE);
+
+        memory.set(1_000_000_000, 10);
+        assert_eq!(memory.get(1_000_000_000), 10);
+        assert_eq!(memory.get(999_999_999), 0);
+        assert_eq!(memory.allocated_cells(), 2 * PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_copy_on_write_memory() {
+        let base = Arc::new(vec![1_i64, 2, 3]);
+        let mut first = CopyOnWriteMemory::new(base.clone());
+        let second = CopyOnWriteMemory::new(base.clone());
+
+        first.set(1, 20);
+        assert_eq!(first.get(0), 1);
+        assert_eq!(first.get(1), 20);
+        assert_eq!(first.copied_pages(), 1);
+        assert_eq!(second.get(1), 2);
+        assert_eq!(second.copied_pages(), 0);
+        assert_eq!(base[1], 2);
+
+        first.grow(PAGE_SIZE * 3);
+        assert_eq!(first.get(PAGE_SIZE * 2), 0);
+        first.set(PAGE_SIZE * 2, 5);
+        assert_eq!(first.get(PAGE_SIZE * 2), 5);
+        assert_eq!(first.copied_pages(), 2);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut dense = DenseMemory::from(vec![1_i64, 2, 3]);
+        let mut sparse = SparseMemory::from(vec![1_i64, 2, 3]);
+        let mut copy_on_write = CopyOnWriteMemory::new(Arc::new(vec![1_i64, 2, 3]));
+        sparse.grow(PAGE_SIZE * 2);
+        sparse.set(PAGE_SIZE + 1, 5);
+        copy_on_write.set(2, 4);
+
+        let memories: [&mut dyn MemoryBackend<i64>; 3] =
+            [&mut dense, &mut sparse, &mut copy_on_write];
+        for memory in memories {
+            memory.truncate(1);
+            assert_eq!(memory.len(), 1);
+            memory.grow(PAGE_SIZE * 2);
+            assert_eq!(memory.get(0), 1);
+            assert_eq!(memory.get(1), 0);
+            assert_eq!(memory.get(2), 0);
+            assert_eq!(memory.get(PAGE_SIZE + 1), 0);
+        }
+        assert_eq!(sparse.allocated_cells(), PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_counting_memory() {
+        let mut memory = CountingMemory::new(DenseMemory::from(vec![1_i64, 2, 3]));
+        assert_eq!(memory.get(0), 1);
+        assert_eq!(memory.get(2), 3);
+        memory.set(1, 5);
+
+        assert_eq!(memory.reads(), 2);
+        assert_eq!(memory.writes(), 1);
+        assert_eq!(memory.inner().as_slice(), &[1, 5, 3]);
+    }
+}
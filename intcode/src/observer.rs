@@ -0,0 +1,120 @@
+//! Watch what a process does as it runs
+//!
+//! Visualizers, tracers, and debuggers all need to see inside the step loop. Instead of each of
+//! them adding its own hook, they implement [`Observer`] and are registered with
+//! `IntcodeProcess::add_observer`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Something that is told about every instruction, input, output, and memory write a process
+/// makes
+///
+/// Every method does nothing by default, so an observer only implements what it's interested
+/// in. To inspect an observer after giving it to a process, share it as an `Rc<RefCell<_>>`,
+/// which is an observer too.
+pub trait Observer<T> {
+    /// The instruction at `address` is about to execute
+    fn on_step(&mut self, _address: usize) {}
+
+    /// The input instruction consumed `value`
+    fn on_input(&mut self, _value: &T) {}
+
+    /// The output instruction produced `value`
+    fn on_output(&mut self, _value: &T) {}
+
+    /// An instruction is about to overwrite `old` with `new` at `address`
+    fn on_memory_write(&mut self, _address: usize, _old: &T, _new: &T) {}
+
+    /// The halt instruction at `address` executed
+    fn on_halt(&mut self, _address: usize) {}
+}
+
+impl<T, O: Observer<T>> Observer<T> for Rc<RefCell<O>> {
+    fn on_step(&mut self, address: usize) {
+        self.borrow_mut().on_step(address)
+    }
+
+    fn on_input(&mut self, value: &T) {
+        self.borrow_mut().on_input(value)
+    }
+
+    fn on_output(&mut self, value: &T) {
+        self.borrow_mut().on_output(value)
+    }
+
+    fn on_memory_write(&mut self, address: usize, old: &T, new: &T) {
+        self.borrow_mut().on_memory_write(address, old, new)
+    }
+
+    fn on_halt(&mut self, address: usize) {
+        self.borrow_mut().on_halt(address)
+    }
+}
+
+/// An observer that calls a closure with every output, see `IntcodeProcess::on_output`
+pub(crate) struct OutputCallback<F>(pub(crate) F);
+
+impl<T, F: FnMut(&T)> Observer<T> for OutputCallback<F> {
+    fn on_output(&mut self, value: &T) {
+        (self.0)(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    /// Log every event as a string
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl Observer<i64> for Log {
+        fn on_step(&mut self, address: usize) {
+            self.0.push(format!("step {}", address));
+        }
+
+        fn on_input(&mut self, value: &i64) {
+            self.0.push(format!("input {}", value));
+        }
+
+        fn on_output(&mut self, value: &i64) {
+            self.0.push(format!("output {}", value));
+        }
+
+        fn on_memory_write(&mut self, address: usize, old: &i64, new: &i64) {
+            self.0
+                .push(format!("write {}: {} -> {}", address, old, new));
+        }
+
+        fn on_halt(&mut self, address: usize) {
+            self.0.push(format!("halt {}", address));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        // Read a value, double it, and output it
+        let mut process = IntcodeProcess::from_vec(vec![3, 9, 102, 2, 9, 9, 4, 9, 99, 0]);
+        let log = Rc::new(RefCell::new(Log::default()));
+        process.add_observer(log.clone());
+        process.add_input(21);
+
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(
+            log.borrow().0,
+            vec![
+                "step 0",
+                "input 21",
+                "write 9: 0 -> 21",
+                "step 2",
+                "write 9: 21 -> 42",
+                "step 6",
+                "output 42",
+                "step 8",
+                "halt 8",
+            ]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Custom instructions for the opcodes that intcode doesn't use
+//!
+//! Opcodes 10 through 98 are unused by the intcode spec. A handler can be registered for any of
+//! them with `IntcodeProcess::register_opcode`, which makes it easy to experiment with new
+//! instructions without touching the decoder. One of them can also be made the trap instruction
+//! with `IntcodeProcess::set_trap`, which is the supported way for a program to call into the
+//! host.
+
+use crate::memory::MemoryBackend;
+use crate::{InputParameter, Instruction, IntcodeError, IntcodeProcess, OutputParameter, Value};
+
+/// A handler registered with `IntcodeProcess::register_opcode`
+pub(crate) type OpcodeHandler<T, M> =
+    Box<dyn FnMut(&mut CustomInstruction<'_, T, M>) -> Result<(), IntcodeError<T>>>;
+
+/// A registered custom opcode
+pub(crate) struct CustomOpcode<T, M> {
+    pub(crate) parameters: usize,
+    pub(crate) handler: OpcodeHandler<T, M>,
+}
+
+/// A custom instruction that is being executed, handed to the handler for its opcode
+pub struct CustomInstruction<'a, T, M> {
+    process: &'a mut IntcodeProcess<T, M>,
+    instruction: T,
+    address: usize,
+    pub(crate) jumped: bool,
+    pub(crate) suspended: bool,
+}
+
+impl<'a, T: Value, M: MemoryBackend<T>> CustomInstruction<'a, T, M> {
+    pub(crate) fn new(process: &'a mut IntcodeProcess<T, M>, instruction: T) -> Self {
+        let address = process.instruction_counter;
+        CustomInstruction {
+            process,
+            instruction,
+            address,
+            jumped: false,
+            suspended: false,
+        }
+    }
+
+    /// Get the full instruction, including the parameter modes
+    pub fn instruction(&self) -> T {
+        self.instruction.clone()
+    }
+
+    /// Get the address of the instruction
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Read the value of a parameter, starting from 0, according to its mode
+    pub fn read(&mut self, parameter: usize) -> Result<T, IntcodeError<T>> {
+        let mode = self.input_mode(parameter)?;
+        self.process.load_input(mode, self.address + parameter + 1)
+    }
+
+    /// Write a value to the address a parameter refers to, according to its mode
+    pub fn write(&mut self, parameter: usize, value: T) -> Result<(), IntcodeError<T>> {
+        let mode = self.output_mode(parameter)?;
+        self.process
+            .store_output(mode, self.address + parameter + 1, value)
+    }
+
+    /// Continue at the given address instead of the instruction after this one
+    pub fn jump(&mut self, address: usize) {
+        self.process.instruction_counter = address;
+        self.jumped = true;
+    }
+
+    /// Stop the process with `IntcodeError::Trapped` once this instruction finishes
+    pub fn suspend(&mut self) {
+        self.suspended = true;
+    }
+
+    /// Get the process that is executing the instruction
+    pub fn process(&mut self) -> &mut IntcodeProcess<T, M> {
+        self.process
+    }
+
+    fn input_mode(&self, parameter: usize) -> Result<InputParameter, IntcodeError<T>> {
+        let (modes, position) = self.modes(parameter)?;
+        Instruction::decode_input_mode(modes, position)
+            .map_err(|e| e.into_intcode_error(self.instruction.clone()))
+    }
+
+    fn output_mode(&self, parameter: usize) -> Result<OutputParameter, IntcodeError<T>> {
+        let (modes, position) = self.modes(parameter)?;
+        Instruction::decode_output_mode(modes, position)
+            .map_err(|e| e.into_intcode_error(self.instruction.clone()))
+    }
+
+    /// The instruction and the digit position of the parameter's mode
+    fn modes(&self, parameter: usize) -> Result<(i64, u32), IntcodeError<T>> {
+        let position = parameter.checked_add(2).filter(|&position| position <= 18);
+        match (self.instruction.to_i64(), position) {
+            (Some(modes), Some(position)) => Ok((modes, position as u32)),
+            _ => Err(IntcodeError::UnknownOpcode(self.instruction.clone())),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Look at the next instruction without executing it
+
+use crate::{InputParameter, Instruction, OutputParameter};
+use std::fmt;
+
+/// The next instruction of a process along with its resolved operands, created by
+/// `IntcodeProcess::peek_instruction`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PeekedInstruction<T> {
+    /// The address of the instruction
+    pub address: usize,
+    /// The value stored at the address, including the parameter modes
+    pub raw: T,
+    /// The decoded instruction
+    pub instruction: Instruction,
+    /// The operands, in the order they follow the opcode
+    pub operands: Vec<Operand<T>>,
+}
+
+impl<T: fmt::Display> fmt::Display for PeekedInstruction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.address, self.instruction.mnemonic())?;
+        for (i, operand) in self.operands.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, operand)?;
+        }
+        Ok(())
+    }
+}
+
+/// An operand of a peeked instruction
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Operand<T> {
+    /// An operand that is read, with the value it currently resolves to
+    Input {
+        /// The parameter as stored in memory
+        parameter: T,
+        /// The value the instruction would use
+        value: T,
+    },
+    /// An operand that is written to, with the address the instruction would write
+    Output {
+        /// The parameter as stored in memory
+        parameter: T,
+        /// The address the instruction would write to
+        address: usize,
+    },
+}
+
+impl<T: fmt::Display> fmt::Display for Operand<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Input { value, .. } => write!(f, "{}", value),
+            Operand::Output { address, .. } => write!(f, "[{}]", address),
+        }
+    }
+}
+
+/// How to resolve a parameter of an instruction
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Mode {
+    Input(InputParameter),
+    Output(OutputParameter),
+}
+
+impl Instruction {
+    /// The modes of the instruction's parameters, in order
+    pub(crate) fn modes(&self) -> Vec<Mode> {
+        use Mode::{Input, Output};
+        match *self {
+            Instruction::Add(in0, in1, out)
+            | Instruction::Mul(in0, in1, out)
+            | Instruction::LessThan(in0, in1, out)
+            | Instruction::Equals(in0, in1, out) => vec![Input(in0), Input(in1), Output(out)],
+            Instruction::JumpIfTrue(in0, in1) | Instruction::JumpIfFalse(in0, in1) => {
+                vec![Input(in0), Input(in1)]
+            }
+            Instruction::Input(out) => vec![Output(out)],
+            Instruction::Output(in0) | Instruction::RelativeMode(in0) => vec![Input(in0)],
+            Instruction::Halt => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_peek_instruction() {
+        let mut process = IntcodeProcess::from_vec(vec![109, 3, 22201, 0, -3, 4, 99]);
+        process.step().unwrap();
+
+        let peeked = process.peek_instruction().unwrap();
+        assert_eq!(
+            peeked,
+            PeekedInstruction {
+                address: 2,
+                raw: 22201,
+                instruction: Instruction::Add(
+                    InputParameter::Relative,
+                    InputParameter::Relative,
+                    OutputParameter::Relative
+                ),
+                operands: vec![
+                    Operand::Input {
+                        parameter: 0,
+                        value: 0
+                    },
+                    Operand::Input {
+                        parameter: -3,
+                        value: 109
+                    },
+                    Operand::Output {
+                        parameter: 4,
+                        address: 7
+                    },
+                ],
+            }
+        );
+        assert_eq!(peeked.to_string(), "2: add 0, 109, [7]");
+
+        // Peeking doesn't change anything
+        assert_eq!(process.instruction_counter(), 2);
+        assert_eq!(process.memory().len(), 7);
+
+        process.step().unwrap();
+        assert_eq!(process.peek_instruction().unwrap().to_string(), "6: halt");
+        assert_eq!(process.load(7), Ok(109));
+    }
+
+    #[test]
+    fn test_peek_instruction_errors() {
+        let process = IntcodeProcess::from_vec(vec![4, -1]);
+        assert_eq!(
+            process.peek_instruction(),
+            Err(IntcodeError::SegfaultRead(-1))
+        );
+
+        let process = IntcodeProcess::from_vec(vec![42]);
+        assert_eq!(
+            process.peek_instruction(),
+            Err(IntcodeError::UnknownOpcode(42))
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Run processes whose outputs are piped into each other's inputs
+//!
+//! A [`Pipeline`] owns a set of processes and the pipes between them. Every output a process
+//! produces is sent down each pipe that starts at it, to the input queue of the process at the
+//! other end; outputs of processes without pipes stay in their output list. The processes take
+//! turns until every one of them halts.
+//!
+//! When every process that hasn't halted is waiting for input and there is nothing queued for
+//! any of them, nothing can ever happen again, so running fails with a [`Deadlock`] instead of
+//! looping forever.
+//!
+//! ```
+//! use intcode::pipeline::Pipeline;
+//! use intcode::IntcodeProcess;
+//!
+//! // Double a number, then add one to it
+//! let double = IntcodeProcess::from_vec(vec![3, 9, 1002, 9, 2, 9, 4, 9, 99, 0]);
+//! let increment = IntcodeProcess::from_vec(vec![3, 9, 1001, 9, 1, 9, 4, 9, 99, 0]);
+//! let mut pipeline = Pipeline::new(vec![double, increment]);
+//! pipeline.connect(0, 1);
+//! pipeline.process_mut(0).unwrap().add_input(20);
+//! pipeline.run().unwrap();
+//! assert_eq!(pipeline.process(1).unwrap().outputs(), &[41]);
+//! ```
+
+use crate::memory::{DenseMemory, MemoryBackend};
+use crate::{IntcodeError, IntcodeProcess};
+use std::fmt;
+
+/// The most instructions a process executes in one turn, so a process that computes for a long
+/// time without any input or output can't starve the others
+const TIME_SLICE: usize = 10_000;
+
+/// A pipe from one process's output to another's input
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Pipe {
+    /// The index of the process whose outputs go into the pipe
+    pub from: usize,
+    /// The index of the process whose inputs come out of the pipe
+    pub to: usize,
+}
+
+/// A process that is waiting for input that will never come
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Waiting {
+    /// The index of the process
+    pub process: usize,
+    /// The pipes that lead to the process, by index, which are all empty
+    pub pipes: Vec<usize>,
+}
+
+/// Every process that hasn't halted is waiting for input, and none is on its way
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Deadlock {
+    /// The processes that are waiting, in order
+    pub waiting: Vec<Waiting>,
+}
+
+impl fmt::Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "deadlock:")?;
+        for (i, waiting) in self.waiting.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}process {} is waiting on ", separator, waiting.process)?;
+            match &waiting.pipes[..] {
+                [] => write!(f, "its input queue")?,
+                [pipe] => write!(f, "pipe {}", pipe)?,
+                pipes => write!(f, "pipes {:?}", pipes)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error from running a pipeline
+#[derive(Debug, Eq, PartialEq)]
+pub enum PipelineError {
+    /// A process stopped with an error other than halting or waiting for input
+    Intcode {
+        /// The index of the process
+        process: usize,
+        /// What went wrong
+        error: IntcodeError,
+    },
+    /// No process can make progress
+    Deadlock(Deadlock),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PipelineError::Intcode { process, error } => {
+                write!(f, "process {} failed: {:?}", process, error)
+            }
+            PipelineError::Deadlock(deadlock) => write!(f, "{}", deadlock),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+/// Processes and the pipes between them
+pub struct Pipeline<M = DenseMemory<i64>> {
+    processes: Vec<IntcodeProcess<i64, M>>,
+    halted: Vec<bool>,
+    pipes: Vec<Pipe>,
+}
+
+impl<M: MemoryBackend<i64>> Pipeline<M> {
+    /// Create a pipeline from the given processes, without any pipes
+    pub fn new(processes: Vec<IntcodeProcess<i64, M>>) -> Self {
+        Pipeline {
+            halted: vec![false; processes.len()],
+            processes,
+            pipes: Vec::new(),
+        }
+    }
+
+    /// Pipe the outputs of the process at index `from` into the inputs of the one at `to`, and
+    /// return the index of the new pipe
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index isn't a process in the pipeline.
+    pub fn connect(&mut self, from: usize, to: usize) -> usize {
+        assert!(
+            from < self.processes.len() && to < self.processes.len(),
+            "no process to connect"
+        );
+        self.pipes.push(Pipe { from, to });
+        self.pipes.len() - 1
+    }
+
+    /// Get the pipes, in the order they were connected
+    pub fn pipes(&self) -> &[Pipe] {
+        &self.pipes[..]
+    }
+
+    /// Get the process at the given index
+    pub fn process(&self, index: usize) -> Option<&IntcodeProcess<i64, M>> {
+        self.processes.get(index)
+    }
+
+    /// Get the process at the given index, for example to give it input
+    pub fn process_mut(&mut self, index: usize) -> Option<&mut IntcodeProcess<i64, M>> {
+        self.processes.get_mut(index)
+    }
+
+    /// Give the processes turns until every one of them has halted
+    ///
+    /// A turn lasts until the process halts, asks for input that isn't there, or has executed a
+    /// time slice of instructions.
+    pub fn run(&mut self) -> Result<(), PipelineError> {
+        loop {
+            let mut progressed = false;
+            for index in 0..self.processes.len() {
+                progressed |= self.turn(index)?;
+            }
+
+            if self.halted.iter().all(|&halted| halted) {
+                return Ok(());
+            }
+            if !progressed {
+                return Err(PipelineError::Deadlock(self.deadlock()));
+            }
+        }
+    }
+
+    /// Run the process at `index` for a turn, and return whether it executed anything
+    fn turn(&mut self, index: usize) -> Result<bool, PipelineError> {
+        if self.halted[index] {
+            return Ok(false);
+        }
+
+        let mut progressed = false;
+        self.processes[index].preempt_after(TIME_SLICE);
+        let result = loop {
+            match self.processes[index].step() {
+                Ok(output) => {
+                    progressed = true;
+                    if let Some(output) = output {
+                        self.send(index, output);
+                    }
+                }
+                Err(IntcodeError::Preempted) | Err(IntcodeError::NoInputAvailable) => {
+                    break Ok(progressed)
+                }
+                Err(IntcodeError::CatchFire) => {
+                    self.halted[index] = true;
+                    break Ok(true);
+                }
+                Err(error) => {
+                    break Err(PipelineError::Intcode {
+                        process: index,
+                        error,
+                    })
+                }
+            }
+        };
+        self.processes[index].cancel_preemption();
+        result
+    }
+
+    /// Send an output of the process at `from` down every pipe that starts there
+    fn send(&mut self, from: usize, value: i64) {
+        let mut piped = false;
+        for pipe in self.pipes.iter().filter(|pipe| pipe.from == from) {
+            self.processes[pipe.to].add_input(value);
+            piped = true;
+        }
+        if piped {
+            self.processes[from].drain_outputs().for_each(drop);
+        }
+    }
+
+    /// Describe which processes are stuck, and what they are waiting on
+    fn deadlock(&self) -> Deadlock {
+        let waiting = (0..self.processes.len())
+            .filter(|&index| !self.halted[index])
+            .map(|process| Waiting {
+                process,
+                pipes: (0..self.pipes.len())
+                    .filter(|&pipe| self.pipes[pipe].to == process)
+                    .collect(),
+            })
+            .collect();
+        Deadlock { waiting }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reads a number, and outputs it plus one, forever
+    const INCREMENT: &[i64] = &[3, 9, 1001, 9, 1, 9, 4, 9, 1105, 1, 0];
+
+    #[test]
+    fn test_pipeline() {
+        // Count to 4 by passing a number around a loop; the counter halts after that, but the
+        // other process waits for its next number forever
+        let counter = IntcodeProcess::from_vec(vec![
+            3, 13, 4, 13, 1007, 13, 4, 14, 1005, 14, 0, 99, 0, 0, 0,
+        ]);
+        let mut pipeline =
+            Pipeline::new(vec![counter, IntcodeProcess::from_vec(INCREMENT.to_vec())]);
+        pipeline.connect(0, 1);
+        pipeline.connect(1, 0);
+        pipeline.process_mut(0).unwrap().add_input(0);
+
+        assert_eq!(
+            pipeline.run(),
+            Err(PipelineError::Deadlock(Deadlock {
+                waiting: vec![Waiting {
+                    process: 1,
+                    pipes: vec![0],
+                }],
+            }))
+        );
+        assert!(pipeline.process(0).unwrap().outputs().is_empty());
+        assert!(pipeline.process(1).unwrap().outputs().is_empty());
+        assert_eq!(pipeline.process(0).unwrap().queued_inputs().count(), 1);
+    }
+
+    #[test]
+    fn test_deadlock() {
+        let processes = vec![
+            IntcodeProcess::from_vec(INCREMENT.to_vec()),
+            IntcodeProcess::from_vec(INCREMENT.to_vec()),
+            IntcodeProcess::from_vec(vec![3, 0, 99]),
+        ];
+        let mut pipeline = Pipeline::new(processes);
+        pipeline.connect(0, 1);
+        pipeline.connect(1, 0);
+        pipeline.connect(2, 1);
+
+        let error = pipeline.run().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "deadlock: process 0 is waiting on pipe 1, process 1 is waiting on pipes [0, 2], \
+             process 2 is waiting on its input queue"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Programs that are parsed once and shared between any number of processes
+
+use crate::memory::CopyOnWriteMemory;
+use crate::{IntcodeProcess, Value};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// An immutable intcode program
+///
+/// Cloning a program is cheap, since the code is shared. Processes created with
+/// `IntcodeProcess::new` share it too, copying only the pages of memory they write to, which
+/// makes brute-force searches that start thousands of processes much cheaper.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Program<T = i64> {
+    code: Arc<Vec<T>>,
+}
+
+impl<T> Program<T> {
+    /// Create a program from its code
+    pub fn new(code: Vec<T>) -> Self {
+        Program {
+            code: Arc::new(code),
+        }
+    }
+
+    /// Get the code as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.code[..]
+    }
+
+    /// The number of values in the program
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Whether the program is empty
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+}
+
+impl<T> From<Vec<T>> for Program<T> {
+    fn from(code: Vec<T>) -> Self {
+        Program::new(code)
+    }
+}
+
+impl<T> AsRef<[T]> for Program<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+/// An error from parsing a program that isn't a list of comma separated values
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseProgramError {
+    /// The position of the value that couldn't be parsed, counting from 0
+    pub index: usize,
+    /// The text that couldn't be parsed
+    pub value: String,
+}
+
+impl<T: FromStr> FromStr for Program<T> {
+    type Err = ParseProgramError;
+
+    /// Parse comma separated values, like the puzzle inputs, ignoring surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s
+            .trim()
+            .split(',')
+            .enumerate()
+            .map(|(index, value)| {
+                value.trim().parse().map_err(|_| ParseProgramError {
+                    index,
+                    value: value.to_string(),
+                })
+            })
+            .collect::<Result<Vec<T>, _>>()?;
+
+        Ok(Program::new(code))
+    }
+}
+
+impl<T: Value> IntcodeProcess<T, CopyOnWriteMemory<T>> {
+    /// Create a new process running the given program
+    ///
+    /// The process shares the program's code, and only copies the pages of memory it writes to.
+    pub fn new(program: &Program<T>) -> Self {
+        Self::with_backend(CopyOnWriteMemory::new(program.code.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntcodeError;
+
+    #[test]
+    fn test_parse() {
+        let program: Program = " 1,0, 0,3,99\n".parse().unwrap();
+        assert_eq!(program.as_slice(), &[1, 0, 0, 3, 99]);
+
+        let error = "1,0,x,3".parse::<Program>().unwrap_err();
+        assert_eq!(
+            error,
+            ParseProgramError {
+                index: 2,
+                value: "x".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_shared_program() {
+        let program = Program::new(vec![1, 0, 0, 3, 99]);
+
+        let mut first = IntcodeProcess::new(&program);
+        first.store(0, 2).unwrap();
+        assert_eq!(first.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(first.load(3), Ok(4));
+
+        let mut second = IntcodeProcess::new(&program);
+        assert_eq!(second.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(second.load(3), Ok(2));
+
+        assert_eq!(program.as_slice(), &[1, 0, 0, 3, 99]);
+    }
+}
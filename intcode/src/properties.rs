@@ -0,0 +1,166 @@
+//! Property tests over randomly generated programs
+//!
+//! The generated programs are always valid: every mode is one the instruction accepts, jumps
+//! only go forwards so every program halts, and every address is inside the program's memory.
+
+use crate::reference::{assert_agree, process_run, Outcome};
+use crate::{InputParameter, Instruction, IntcodeError, IntcodeProcess, OutputParameter};
+use proptest::prelude::*;
+
+/// The most a relative base adjustment can move the relative base
+const MAX_ADJUSTMENT: i64 = 4;
+/// The size of the data area that parameters refer to
+const DATA_LEN: i64 = 16;
+
+fn input_parameter() -> impl Strategy<Value = InputParameter> {
+    prop_oneof![
+        Just(InputParameter::Position),
+        Just(InputParameter::Immediate),
+        Just(InputParameter::Relative),
+    ]
+}
+
+fn output_parameter() -> impl Strategy<Value = OutputParameter> {
+    prop_oneof![
+        Just(OutputParameter::Position),
+        Just(OutputParameter::Relative),
+    ]
+}
+
+fn instruction() -> impl Strategy<Value = Instruction> {
+    let (i, o) = (input_parameter, output_parameter);
+    prop_oneof![
+        (i(), i(), o()).prop_map(|(a, b, c)| Instruction::Add(a, b, c)),
+        (i(), i(), o()).prop_map(|(a, b, c)| Instruction::Mul(a, b, c)),
+        o().prop_map(Instruction::Input),
+        i().prop_map(Instruction::Output),
+        (i(), i()).prop_map(|(a, b)| Instruction::JumpIfTrue(a, b)),
+        (i(), i()).prop_map(|(a, b)| Instruction::JumpIfFalse(a, b)),
+        (i(), i(), o()).prop_map(|(a, b, c)| Instruction::LessThan(a, b, c)),
+        (i(), i(), o()).prop_map(|(a, b, c)| Instruction::Equals(a, b, c)),
+        i().prop_map(Instruction::RelativeMode),
+        Just(Instruction::Halt),
+    ]
+}
+
+/// The modes of an instruction's parameters, as the digits that encode them
+fn modes(instruction: Instruction) -> Vec<i64> {
+    let encoded = instruction.encode();
+    (2..=instruction.len() as u32)
+        .map(|position| encoded / 10_i64.pow(position) % 10)
+        .collect()
+}
+
+/// Assemble generated instructions and raw parameter values into a valid program
+///
+/// The code is followed by padding, so that relative parameters stay in memory however the
+/// relative base moves, and then the data area.
+fn assemble(items: &[(Instruction, [i64; 3])]) -> Vec<i64> {
+    // Jump parameters and relative base adjustments must be immediate, for the program to be
+    // valid no matter what's in the data area
+    let items: Vec<(Instruction, [i64; 3])> = items
+        .iter()
+        .map(|&(instruction, values)| {
+            let instruction = match instruction {
+                Instruction::JumpIfTrue(a, _) => {
+                    Instruction::JumpIfTrue(a, InputParameter::Immediate)
+                }
+                Instruction::JumpIfFalse(a, _) => {
+                    Instruction::JumpIfFalse(a, InputParameter::Immediate)
+                }
+                Instruction::RelativeMode(_) => {
+                    Instruction::RelativeMode(InputParameter::Immediate)
+                }
+                instruction => instruction,
+            };
+            (instruction, values)
+        })
+        .chain(std::iter::once((Instruction::Halt, [0; 3])))
+        .collect();
+
+    let mut starts = vec![0];
+    for (instruction, _) in &items {
+        starts.push(starts.last().unwrap() + instruction.len() as i64);
+    }
+    let code_len = *starts.last().unwrap();
+    let padding = MAX_ADJUSTMENT * items.len() as i64;
+    let data = code_len + padding;
+
+    let mut program = Vec::new();
+    for (index, (instruction, values)) in items.iter().enumerate() {
+        program.push(instruction.encode());
+        for (position, mode) in modes(*instruction).into_iter().enumerate() {
+            let value = values[position];
+            let parameter = match (instruction, position, mode) {
+                (Instruction::JumpIfTrue(..), 1, _) | (Instruction::JumpIfFalse(..), 1, _) => {
+                    // Jump forwards to the start of a later instruction
+                    let later = &starts[index + 1..items.len()];
+                    later[value.rem_euclid(later.len() as i64) as usize]
+                }
+                (Instruction::RelativeMode(_), _, _) => {
+                    value.rem_euclid(2 * MAX_ADJUSTMENT + 1) - MAX_ADJUSTMENT
+                }
+                (_, _, 1) => value,
+                _ => data + value.rem_euclid(DATA_LEN),
+            };
+            program.push(parameter);
+        }
+    }
+    program.resize((data + DATA_LEN + padding) as usize, 0);
+    program
+}
+
+fn program() -> impl Strategy<Value = Vec<i64>> {
+    prop::collection::vec(
+        (instruction(), prop::array::uniform3(-1000..1000_i64)),
+        0..40,
+    )
+    .prop_map(|items| assemble(&items))
+}
+
+fn inputs() -> impl Strategy<Value = Vec<i64>> {
+    prop::collection::vec(-1000..1000_i64, 40)
+}
+
+proptest! {
+    #[test]
+    fn encode_decode_round_trip(instruction in instruction()) {
+        prop_assert_eq!(Instruction::decode(instruction.encode()), Ok(instruction));
+    }
+
+    #[test]
+    fn decode_encode_round_trip(value in 0..100_000_i64) {
+        if let Ok(instruction) = Instruction::decode(value) {
+            // Digits past the last parameter's mode are ignored, so only compare those that matter
+            let significant = 10_i64.pow(instruction.len() as u32 + 1);
+            prop_assert_eq!(instruction.encode(), value % significant);
+        }
+    }
+
+    #[test]
+    fn generated_programs_halt(program in program(), inputs in inputs()) {
+        let instructions = program.len();
+        let run = process_run(&program, &inputs, instructions);
+        prop_assert_eq!(run.outcome, Outcome::Halted);
+    }
+
+    #[test]
+    fn generated_programs_agree_with_reference(program in program(), inputs in inputs()) {
+        assert_agree(&program, &inputs, program.len());
+    }
+
+    #[test]
+    fn strict_and_resizing_memory_agree(program in program(), inputs in inputs()) {
+        let run = |strict| {
+            let mut process = IntcodeProcess::from_vec(program.clone());
+            process.set_strict_memory(strict);
+            process.add_inputs(&inputs);
+            let result = process.run();
+            (result, process.outputs().to_vec(), process.memory().to_vec())
+        };
+
+        let strict = run(true);
+        prop_assert_eq!(&strict.0, &Err(IntcodeError::CatchFire));
+        prop_assert_eq!(strict, run(false));
+    }
+}
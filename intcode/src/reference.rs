@@ -0,0 +1,304 @@
+//! A deliberately naive intcode interpreter, for differential testing of the real one
+//!
+//! The reference interpreter is written to be obviously correct rather than fast: it decodes
+//! every instruction from scratch with plain digit arithmetic, and shares no code with the
+//! process. The harness runs a program on both and compares everything observable, which gives
+//! confidence when optimizing the real VM.
+
+use crate::{IntcodeError, IntcodeProcess};
+use std::collections::VecDeque;
+
+/// How a run ended
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Outcome {
+    Halted,
+    NeedsInput,
+    Failed,
+    StepLimit,
+}
+
+/// Everything observable about a finished run
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Run {
+    pub(crate) outcome: Outcome,
+    pub(crate) outputs: Vec<i64>,
+    /// Memory with trailing zeros removed, since the interpreters may grow it differently
+    pub(crate) memory: Vec<i64>,
+    pub(crate) steps: usize,
+}
+
+fn trim_memory(mut memory: Vec<i64>) -> Vec<i64> {
+    while memory.last() == Some(&0) {
+        memory.pop();
+    }
+    memory
+}
+
+/// The state of the reference interpreter
+struct Machine {
+    memory: Vec<i64>,
+    pc: i64,
+    base: i64,
+}
+
+impl Machine {
+    /// Turn a value into an address, growing memory to include it
+    fn address(&mut self, address: i64) -> Option<usize> {
+        if address < 0 {
+            return None;
+        }
+        let address = address as usize;
+        if address >= self.memory.len() {
+            self.memory.resize(address + 1, 0);
+        }
+        Some(address)
+    }
+
+    fn mode(&mut self, n: u32) -> Option<i64> {
+        let address = self.address(self.pc)?;
+        let instruction = self.memory[address];
+        Some(instruction / 10_i64.pow(n + 1) % 10)
+    }
+
+    /// The address parameter `n` (counting from 1) refers to
+    fn target(&mut self, n: u32, allow_immediate: bool) -> Option<usize> {
+        let position = self.address(self.pc + n as i64)?;
+        match self.mode(n)? {
+            0 => self.address(self.memory[position]),
+            1 if allow_immediate => Some(position),
+            2 => {
+                let relative = self.memory[position].checked_add(self.base)?;
+                self.address(relative)
+            }
+            _ => None,
+        }
+    }
+
+    fn read(&mut self, n: u32) -> Option<i64> {
+        let address = self.target(n, true)?;
+        Some(self.memory[address])
+    }
+
+    fn write(&mut self, n: u32, value: i64) -> Option<()> {
+        let address = self.target(n, false)?;
+        self.memory[address] = value;
+        Some(())
+    }
+}
+
+/// Run a program on the reference interpreter
+pub(crate) fn reference_run(program: &[i64], inputs: &[i64], step_limit: usize) -> Run {
+    let mut machine = Machine {
+        memory: program.to_vec(),
+        pc: 0,
+        base: 0,
+    };
+    let mut inputs: VecDeque<i64> = inputs.iter().copied().collect();
+    let mut outputs = Vec::new();
+    let mut steps = 0;
+
+    let outcome = loop {
+        if steps == step_limit {
+            break Outcome::StepLimit;
+        }
+
+        let opcode = match machine.address(machine.pc) {
+            Some(address) => machine.memory[address] % 100,
+            None => break Outcome::Failed,
+        };
+        let m = &mut machine;
+        let mut needs_input = false;
+        let result = match opcode {
+            1 | 2 | 7 | 8 => (|| {
+                let a = m.read(1)?;
+                let b = m.read(2)?;
+                let value = match opcode {
+                    1 => a.wrapping_add(b),
+                    2 => a.wrapping_mul(b),
+                    7 => (a < b) as i64,
+                    _ => (a == b) as i64,
+                };
+                m.write(3, value)?;
+                m.pc += 4;
+                Some(())
+            })(),
+            3 => (|| {
+                // Like the process, check the mode before waiting for input, and the address after
+                if m.mode(1)? != 0 && m.mode(1)? != 2 {
+                    return None;
+                }
+                if inputs.is_empty() {
+                    needs_input = true;
+                    return None;
+                }
+                let value = inputs.pop_front()?;
+                m.write(1, value)?;
+                m.pc += 2;
+                Some(())
+            })(),
+            4 => (|| {
+                outputs.push(m.read(1)?);
+                m.pc += 2;
+                Some(())
+            })(),
+            5 | 6 => (|| {
+                let condition = m.read(1)?;
+                let destination = m.read(2)?;
+                if (condition != 0) == (opcode == 5) {
+                    if destination < 0 {
+                        return None;
+                    }
+                    m.pc = destination;
+                } else {
+                    m.pc += 3;
+                }
+                Some(())
+            })(),
+            9 => (|| {
+                m.base = m.base.checked_add(m.read(1)?)?;
+                m.pc += 2;
+                Some(())
+            })(),
+            99 => break Outcome::Halted,
+            _ => None,
+        };
+
+        if needs_input {
+            break Outcome::NeedsInput;
+        }
+        if result.is_none() {
+            break Outcome::Failed;
+        }
+        steps += 1;
+    };
+
+    Run {
+        outcome,
+        outputs,
+        memory: trim_memory(machine.memory),
+        steps,
+    }
+}
+
+/// Run a program on the real process, the same way as `reference_run`
+pub(crate) fn process_run(program: &[i64], inputs: &[i64], step_limit: usize) -> Run {
+    let mut process = IntcodeProcess::from_vec(program.to_vec());
+    process.add_inputs(inputs);
+
+    let mut steps = 0;
+    let outcome = loop {
+        if steps == step_limit {
+            break Outcome::StepLimit;
+        }
+        match process.step() {
+            Ok(_) => steps += 1,
+            Err(IntcodeError::CatchFire) => break Outcome::Halted,
+            Err(IntcodeError::NoInputAvailable) => break Outcome::NeedsInput,
+            Err(_) => break Outcome::Failed,
+        }
+    };
+
+    Run {
+        outcome,
+        outputs: process.outputs().to_vec(),
+        memory: trim_memory(process.memory().to_vec()),
+        steps,
+    }
+}
+
+/// Run a program on both interpreters and check that they agree
+pub(crate) fn assert_agree(program: &[i64], inputs: &[i64], step_limit: usize) -> Run {
+    let expected = reference_run(program, inputs, step_limit);
+    let actual = process_run(program, inputs, step_limit);
+    // Memory isn't comparable after a failure, since the interpreters may stop at different
+    // points within the failing instruction
+    if expected.outcome == Outcome::Failed && actual.outcome == Outcome::Failed {
+        assert_eq!(
+            (&expected.outputs, expected.steps),
+            (&actual.outputs, actual.steps),
+            "program {:?}",
+            program
+        );
+    } else {
+        assert_eq!(expected, actual, "program {:?}", program);
+    }
+    actual
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Program;
+
+    const STEP_LIMIT: usize = 10_000_000;
+
+    fn corpus(input: &str) -> Vec<i64> {
+        input.parse::<Program>().unwrap().as_slice().to_vec()
+    }
+
+    #[test]
+    fn test_corpus() {
+        let mut day_02 = corpus(include_str!("../../day-02/input.txt"));
+        day_02[1] = 12;
+        day_02[2] = 2;
+        assert_eq!(
+            assert_agree(&day_02, &[], STEP_LIMIT).outcome,
+            Outcome::Halted
+        );
+
+        let day_05 = corpus(include_str!("../../day-05/input.txt"));
+        for &input in &[1, 5] {
+            let run = assert_agree(&day_05, &[input], STEP_LIMIT);
+            assert_eq!(run.outcome, Outcome::Halted);
+        }
+
+        let day_07 = corpus(include_str!("../../day-07/input.txt"));
+        for phase in 0..10 {
+            assert_agree(&day_07, &[phase, 0], STEP_LIMIT);
+        }
+
+        let day_09 = corpus(include_str!("../../day-09/input.txt"));
+        for &input in &[1, 2] {
+            let run = assert_agree(&day_09, &[input], STEP_LIMIT);
+            assert_eq!(run.outcome, Outcome::Halted);
+        }
+    }
+
+    /// A small xorshift generator, so the generated programs are the same on every run
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> i64 {
+            (self.next() % n) as i64
+        }
+    }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = Rng(0x2019_1202);
+        let opcodes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+        for _ in 0..2000 {
+            let len = 4 + rng.below(40) as usize;
+            let program: Vec<i64> = (0..len)
+                .map(|_| match rng.below(3) {
+                    0 => {
+                        let opcode = opcodes[rng.below(opcodes.len() as u64) as usize];
+                        opcode + 100 * rng.below(3) + 1000 * rng.below(3) + 10000 * rng.below(3)
+                    }
+                    1 => rng.below(len as u64 + 4),
+                    _ => rng.below(21) - 10,
+                })
+                .collect();
+            let inputs: Vec<i64> = (0..rng.below(4)).map(|_| rng.below(100) - 50).collect();
+
+            assert_agree(&program, &inputs, 1000);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Run snippets of assembly against a persistent memory image, for experimenting
+//!
+//! Each snippet is assembled, written to a scratch area of memory, and run until it falls off
+//! its end. Memory, the relative base, and the inputs carry over from one snippet to the next,
+//! so a snippet can use the results of the ones before it. Lines starting with `:` are
+//! commands for inspecting the process; see [`HELP`]. The `intcode-repl` binary reads lines
+//! from stdin and evaluates them.
+//!
+//! ```
+//! use intcode::repl::Repl;
+//!
+//! let mut repl = Repl::new(Vec::new());
+//! repl.eval("add 20, 22, [0] | out [0]").unwrap();
+//! assert_eq!(repl.eval(":mem 0").unwrap(), "@0 = 42");
+//! ```
+
+use crate::asm::{assemble_at, disassemble_instruction};
+use crate::{IntcodeError, IntcodeProcess};
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+/// The address snippets are written to, unless changed with `:org`
+pub const DEFAULT_ORIGIN: usize = 1000;
+
+/// The most instructions a snippet may execute, in case it jumps into a loop
+const STEP_LIMIT: usize = 1_000_000;
+
+/// The commands the REPL understands
+pub const HELP: &str = "\
+Type instructions to run them, separated by |, like `add 1, 2, [0] | out [0]`.
+commands:
+  :mem <address> [n]   show n values of memory
+  :regs                show the relative base and queued inputs
+  :in <values...>      queue inputs
+  :org <address>       write snippets at a different address
+  :reset               start over with the original memory
+  :help                show this help";
+
+/// A process that snippets run against
+pub struct Repl {
+    initial: Vec<i64>,
+    process: IntcodeProcess,
+    origin: usize,
+}
+
+impl Repl {
+    /// Start a REPL whose memory starts as `memory`, which may be empty
+    pub fn new(memory: Vec<i64>) -> Self {
+        Repl {
+            process: IntcodeProcess::from_vec(memory.clone()),
+            initial: memory,
+            origin: DEFAULT_ORIGIN,
+        }
+    }
+
+    /// Get the process that snippets run against
+    pub fn process(&self) -> &IntcodeProcess {
+        &self.process
+    }
+
+    /// Evaluate a line, returning what to show the user
+    pub fn eval(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        match line.strip_prefix(':') {
+            Some(command) => self.command(command),
+            None if line.is_empty() => Ok(String::new()),
+            None => self.run_snippet(&line.replace('|', "\n")),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<String, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let number = |index: usize, default: Option<i64>| match words.get(index) {
+            Some(word) => word
+                .trim_start_matches('@')
+                .parse::<i64>()
+                .map_err(|_| format!("invalid number {:?}", word)),
+            None => default.ok_or_else(|| String::from("missing argument")),
+        };
+
+        let mut out = String::new();
+        match words.first().copied().unwrap_or("") {
+            "mem" => {
+                let start = number(1, None)?;
+                let count = number(2, Some(1))?;
+                for address in start..start + count {
+                    match self.process.load(address) {
+                        Ok(value) => writeln!(out, "@{} = {}", address, value).unwrap(),
+                        Err(_) => writeln!(out, "@{} is outside of memory", address).unwrap(),
+                    }
+                }
+            }
+            "regs" => {
+                writeln!(out, "rb = {}", self.process.relative_base()).unwrap();
+                writeln!(
+                    out,
+                    "{} queued inputs",
+                    self.process.queued_inputs().count()
+                )
+                .unwrap();
+            }
+            "in" => {
+                let values = (1..words.len())
+                    .map(|index| number(index, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.process.add_inputs(&values);
+            }
+            "org" => {
+                let origin = number(1, None)?;
+                self.origin = usize::try_from(origin).map_err(|_| "invalid address")?;
+            }
+            "reset" => *self = Repl::new(self.initial.clone()),
+            "help" => out.push_str(HELP),
+            other => return Err(format!("unknown command :{}; try :help", other)),
+        }
+        Ok(out.trim_end().to_string())
+    }
+
+    fn run_snippet(&mut self, source: &str) -> Result<String, String> {
+        let code = assemble_at(source, self.origin)
+            .map_err(|e| e.message)?
+            .program;
+        let end = self.origin + code.len();
+        for (offset, &value) in code.iter().enumerate() {
+            self.process
+                .store_with_resize((self.origin + offset) as i64, value)
+                .map_err(|e| format!("{:?}", e))?;
+        }
+        self.process.set_instruction_counter(self.origin);
+
+        let mut out = String::new();
+        let mut steps = 0;
+        let result = loop {
+            if self.process.instruction_counter() == end {
+                break Ok(());
+            }
+            if steps == STEP_LIMIT {
+                break Err(format!("stopped after {} steps", STEP_LIMIT));
+            }
+            let address = self.process.instruction_counter();
+            let memory = self.process.memory();
+            let text = disassemble_instruction(memory, address).map(|(text, _)| text);
+            match self.process.step() {
+                Ok(Some(output)) => writeln!(out, "out: {}", output).unwrap(),
+                Ok(None) => {}
+                Err(IntcodeError::CatchFire) => break Err(String::from("halted")),
+                Err(e) => {
+                    let text = text.unwrap_or_else(|| String::from("?"));
+                    break Err(format!("{:?} at {}: {}", e, address, text));
+                }
+            }
+            steps += 1;
+        };
+        self.process.drain_outputs();
+
+        match result {
+            Ok(()) => Ok(out.trim_end().to_string()),
+            Err(e) => Err(format!("{}{}", out, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_repl() {
+        let mut repl = Repl::new(vec![5, 7]);
+        assert_eq!(
+            repl.eval("mul [0], [1], [2] | out [2]"),
+            Ok("out: 35".into())
+        );
+        assert_eq!(repl.eval(":mem 0 3"), Ok("@0 = 5\n@1 = 7\n@2 = 35".into()));
+
+        // The relative base and inputs persist between snippets
+        assert_eq!(repl.eval("arb 2"), Ok(String::new()));
+        assert_eq!(repl.eval(":in 4"), Ok(String::new()));
+        assert_eq!(repl.eval("in [rb+1] | out [3]"), Ok("out: 4".into()));
+        assert_eq!(repl.eval(":regs"), Ok("rb = 2\n0 queued inputs".into()));
+
+        assert_eq!(
+            repl.eval("in [0]"),
+            Err("NoInputAvailable at 1000: in [0]".into())
+        );
+        assert_eq!(
+            repl.eval("jump 4"),
+            Err("unknown instruction \"jump\"".into())
+        );
+
+        // Labels count from where the snippet is written
+        assert_eq!(
+            repl.eval("jnz 1, skip | out 1 | skip: out 2"),
+            Ok("out: 2".into())
+        );
+
+        assert_eq!(repl.eval(":reset"), Ok(String::new()));
+        assert_eq!(repl.eval(":mem 2"), Ok("@2 is outside of memory".into()));
+    }
+}
@@ -0,0 +1,194 @@
+//! Record the input and output of a run and replay it against another process
+//!
+//! Recording a run of a real puzzle program and replaying it later verifies that changes to the
+//! VM don't change how programs behave.
+
+use crate::memory::MemoryBackend;
+use crate::{IntcodeError, IntcodeProcess, Value};
+
+/// A single input consumed or output produced by a process
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IoEvent<T> {
+    /// The input instruction consumed this value
+    Input(T),
+    /// The output instruction produced this value
+    Output(T),
+}
+
+/// What a process did when replaying a recording
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReplayStep<T> {
+    /// The process produced this output
+    Output(T),
+    /// The process asked for input
+    InputRequested,
+    /// The process halted
+    Halted,
+}
+
+/// An error that can occur while replaying a recording
+#[derive(Debug, Eq, PartialEq)]
+pub enum ReplayError<T> {
+    /// The process did something other than what was recorded at event <index>
+    Diverged {
+        /// The index of the event in the recording
+        index: usize,
+        /// The recorded event, or `None` if the recording was already finished
+        expected: Option<IoEvent<T>>,
+        /// What the process did instead
+        actual: ReplayStep<T>,
+    },
+    /// The process failed with an error other than halting or needing input
+    Intcode(IntcodeError<T>),
+}
+
+/// The sequence of inputs consumed and outputs produced by a process, in order
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recording<T> {
+    events: Vec<IoEvent<T>>,
+}
+
+impl<T> Recording<T> {
+    /// Create an empty recording
+    pub fn new() -> Self {
+        Recording { events: Vec::new() }
+    }
+
+    /// Add an event to the end of the recording
+    pub fn push(&mut self, event: IoEvent<T>) {
+        self.events.push(event);
+    }
+
+    /// Get the recorded events
+    pub fn events(&self) -> &[IoEvent<T>] {
+        &self.events[..]
+    }
+}
+
+impl<T> Default for Recording<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Value> Recording<T> {
+    /// Run a fresh process until it halts, feeding it the recorded inputs whenever it asks for
+    /// input and checking that it produces exactly the recorded outputs
+    pub fn replay<M: MemoryBackend<T>>(
+        &self,
+        process: &mut IntcodeProcess<T, M>,
+    ) -> Result<(), ReplayError<T>> {
+        let mut events = self.events.iter().enumerate();
+
+        loop {
+            let actual = match process.run_to_output() {
+                Ok(output) => ReplayStep::Output(output),
+                Err(IntcodeError::NoInputAvailable) => ReplayStep::InputRequested,
+                Err(IntcodeError::CatchFire) => ReplayStep::Halted,
+                Err(e) => return Err(ReplayError::Intcode(e)),
+            };
+
+            let (index, expected) = match events.next() {
+                Some((index, event)) => (index, Some(event)),
+                None => (self.events.len(), None),
+            };
+
+            match (&actual, expected) {
+                (ReplayStep::Halted, None) => return Ok(()),
+                (ReplayStep::Output(actual), Some(IoEvent::Output(expected)))
+                    if actual == expected => {}
+                (ReplayStep::InputRequested, Some(IoEvent::Input(input))) => {
+                    process.add_input(input.clone());
+                }
+                _ => {
+                    return Err(ReplayError::Diverged {
+                        index,
+                        expected: expected.cloned(),
+                        actual,
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Read numbers until a zero, printing each number doubled
+    const DOUBLER: [i64; 17] = [
+        3, 16, 1006, 16, 15, 1002, 16, 2, 16, 4, 16, 1105, 1, 0, 0, 99, 0,
+    ];
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut process = IntcodeProcess::from_vec(DOUBLER.to_vec());
+        process.start_recording();
+        process.add_input(5);
+        process.add_input(7);
+        process.add_input(0);
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+
+        let recording = process.take_recording().unwrap();
+        assert_eq!(
+            recording.events(),
+            &[
+                IoEvent::Input(5),
+                IoEvent::Output(10),
+                IoEvent::Input(7),
+                IoEvent::Output(14),
+                IoEvent::Input(0),
+            ]
+        );
+        assert_eq!(process.take_recording(), None);
+
+        let mut fresh = IntcodeProcess::from_vec(DOUBLER.to_vec());
+        assert_eq!(recording.replay(&mut fresh), Ok(()));
+        assert_eq!(fresh.outputs(), &[10, 14]);
+    }
+
+    #[test]
+    fn test_replay_diverged() {
+        let mut recording = Recording::new();
+        recording.push(IoEvent::Input(5));
+        recording.push(IoEvent::Output(11));
+
+        let mut process = IntcodeProcess::from_vec(DOUBLER.to_vec());
+        assert_eq!(
+            recording.replay(&mut process),
+            Err(ReplayError::Diverged {
+                index: 1,
+                expected: Some(IoEvent::Output(11)),
+                actual: ReplayStep::Output(10),
+            })
+        );
+
+        let mut recording = Recording::new();
+        recording.push(IoEvent::Input(0));
+        recording.push(IoEvent::Output(0));
+
+        let mut process = IntcodeProcess::from_vec(DOUBLER.to_vec());
+        assert_eq!(
+            recording.replay(&mut process),
+            Err(ReplayError::Diverged {
+                index: 1,
+                expected: Some(IoEvent::Output(0)),
+                actual: ReplayStep::Halted,
+            })
+        );
+
+        let recording = Recording::new();
+        let mut process = IntcodeProcess::from_vec(DOUBLER.to_vec());
+        assert_eq!(
+            recording.replay(&mut process),
+            Err(ReplayError::Diverged {
+                index: 0,
+                expected: None,
+                actual: ReplayStep::InputRequested,
+            })
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Bookkeeping for stepping a process backwards, see `IntcodeProcess::start_rewind`
+//!
+//! Every step logs a delta with just enough to undo it: the registers before the step, the old
+//! value of every cell it wrote, and the input it consumed or output it produced. Undoing steps
+//! one at a time works for short distances, and every so often a checkpoint with a copy of the
+//! memory backend is taken so that rewinding a long way doesn't have to undo every step in
+//! between. Copying the backend keeps checkpoints of sparse memory as small as the memory itself.
+
+use crate::memory::MemoryBackend;
+
+/// The changes one step made
+#[derive(Debug, Clone)]
+pub(crate) struct Delta<T> {
+    pub(crate) instruction_counter: usize,
+    pub(crate) relative_base: T,
+    pub(crate) memory_len: usize,
+    /// The old values of the cells that were written, in the order they were written
+    pub(crate) writes: Vec<(usize, T)>,
+    pub(crate) input: Option<T>,
+    pub(crate) output: bool,
+}
+
+/// The full state of memory and registers before a step
+#[derive(Clone)]
+pub(crate) struct Checkpoint<T, M> {
+    pub(crate) step: usize,
+    pub(crate) instruction_counter: usize,
+    pub(crate) relative_base: T,
+    pub(crate) memory: M,
+}
+
+/// Everything recorded since rewinding was started
+#[derive(Clone)]
+pub(crate) struct Rewind<T, M> {
+    checkpoint_interval: usize,
+    /// Copies the memory backend for a checkpoint
+    copy_memory: fn(&M) -> M,
+    pub(crate) deltas: Vec<Delta<T>>,
+    pub(crate) checkpoints: Vec<Checkpoint<T, M>>,
+    /// The delta of the step that is executing
+    current: Option<Delta<T>>,
+}
+
+impl<T: Clone, M: MemoryBackend<T>> Rewind<T, M> {
+    pub(crate) fn new(checkpoint_interval: usize, copy_memory: fn(&M) -> M) -> Self {
+        Rewind {
+            checkpoint_interval: checkpoint_interval.max(1),
+            copy_memory,
+            deltas: Vec::new(),
+            checkpoints: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// The number of steps that can be undone
+    pub(crate) fn steps(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub(crate) fn begin_step(&mut self, memory: &M, instruction_counter: usize, relative_base: T) {
+        let step = self.deltas.len();
+        if step.is_multiple_of(self.checkpoint_interval) {
+            self.checkpoints.push(Checkpoint {
+                step,
+                instruction_counter,
+                relative_base: relative_base.clone(),
+                memory: (self.copy_memory)(memory),
+            });
+        }
+
+        self.current = Some(Delta {
+            instruction_counter,
+            relative_base,
+            memory_len: memory.len(),
+            writes: Vec::new(),
+            input: None,
+            output: false,
+        });
+    }
+
+    /// Finish the step, keeping its delta unless it failed without changing anything
+    pub(crate) fn end_step(
+        &mut self,
+        failed: bool,
+        memory_len: usize,
+        instruction_counter: usize,
+        relative_base: &T,
+    ) where
+        T: PartialEq,
+    {
+        let delta = match self.current.take() {
+            Some(delta) => delta,
+            None => return,
+        };
+        let unchanged = delta.writes.is_empty()
+            && delta.input.is_none()
+            && !delta.output
+            && delta.memory_len == memory_len
+            && delta.instruction_counter == instruction_counter
+            && delta.relative_base == *relative_base;
+        if failed && unchanged {
+            if self.checkpoints.last().map(|c| c.step) == Some(self.deltas.len()) {
+                self.checkpoints.pop();
+            }
+        } else {
+            self.deltas.push(delta);
+        }
+    }
+
+    pub(crate) fn record_write(&mut self, address: usize, old: T) {
+        if let Some(delta) = self.current.as_mut() {
+            delta.writes.push((address, old));
+        }
+    }
+
+    pub(crate) fn record_input(&mut self, input: T) {
+        if let Some(delta) = self.current.as_mut() {
+            delta.input = Some(input);
+        }
+    }
+
+    pub(crate) fn record_output(&mut self) {
+        if let Some(delta) = self.current.as_mut() {
+            delta.output = true;
+        }
+    }
+
+    /// The earliest checkpoint at or after `step`, if it's worth restoring to get to `step`
+    pub(crate) fn checkpoint_after(&self, step: usize) -> Option<usize> {
+        self.checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.step >= step)
+            .filter(|&i| self.checkpoints[i].step < self.deltas.len())
+    }
+}
@@ -0,0 +1,495 @@
+//! Run a network of intcode machines that send packets to each other, like day 23
+//!
+//! Each machine is booted with its network address as its first input. It sends a packet by
+//! outputting the destination address followed by the packet's X and Y values, and receives
+//! packets as pairs of X and Y inputs. When a machine asks for input and no packet is waiting, it
+//! is given `-1`.
+//!
+//! The scheduler is a message bus: it routes every packet to the input queue of the machine it
+//! is addressed to. [`Interceptor`]s see every packet on its way, and can log it, change it, or
+//! drop it. A [`Nat`] can watch a special address and wake the network up whenever it goes idle,
+//! which is what day 23 part 2 needs.
+
+use crate::memory::{DenseMemory, MemoryBackend};
+use crate::peek::PeekedInstruction;
+use crate::{Instruction, IntcodeError, IntcodeProcess, Value};
+use std::convert::TryFrom;
+
+/// The value a machine receives when it asks for input and no packet is waiting
+const NO_PACKET: i64 = -1;
+
+/// The number of times in a row a machine must ask for input with nothing waiting before it
+/// counts as idle
+const IDLE_POLLS: usize = 2;
+
+/// The most instructions a machine executes in one turn, so a machine that computes for a long
+/// time without sending or receiving can't starve the others
+const TIME_SLICE: usize = 10_000;
+
+/// A packet sent from one machine to another
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Packet {
+    /// The address of the machine the packet is sent to
+    pub destination: i64,
+    /// The first value of the packet
+    pub x: i64,
+    /// The second value of the packet
+    pub y: i64,
+}
+
+/// Something that sees every packet a machine sends before it is routed
+///
+/// Any `FnMut(Packet) -> Option<Packet>` closure is an interceptor.
+pub trait Interceptor {
+    /// Handle a packet, and return the packet to route instead, or `None` to drop it
+    fn intercept(&mut self, packet: Packet) -> Option<Packet>;
+}
+
+impl<F: FnMut(Packet) -> Option<Packet>> Interceptor for F {
+    fn intercept(&mut self, packet: Packet) -> Option<Packet> {
+        self(packet)
+    }
+}
+
+/// A component that receives the packets sent to its address, and can wake up an idle network
+pub trait Nat {
+    /// Handle a packet that was sent to the NAT's address
+    fn receive(&mut self, packet: Packet);
+
+    /// Called when the whole network is idle; returns the packet to send to machine 0, if any
+    fn wake(&mut self) -> Option<Packet>;
+}
+
+/// The NAT from day 23: remembers the last packet it received and sends it to machine 0 whenever
+/// the network is idle
+#[derive(Debug, Default)]
+pub struct LastPacketNat {
+    last: Option<Packet>,
+}
+
+impl LastPacketNat {
+    /// Create a NAT that hasn't received any packets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the last packet the NAT received
+    pub fn last(&self) -> Option<Packet> {
+        self.last
+    }
+}
+
+impl Nat for LastPacketNat {
+    fn receive(&mut self, packet: Packet) {
+        self.last = Some(packet);
+    }
+
+    fn wake(&mut self) -> Option<Packet> {
+        self.last.map(|packet| Packet {
+            destination: 0,
+            ..packet
+        })
+    }
+}
+
+/// A machine in the network, along with what the scheduler knows about it
+struct Machine<M> {
+    process: IntcodeProcess<i64, M>,
+    /// Outputs of a packet that hasn't been completely sent yet
+    partial: Vec<i64>,
+    /// The number of times in a row the machine asked for input with nothing waiting
+    idle_polls: usize,
+    halted: bool,
+}
+
+/// Owns a network of machines and steps them fairly, routing packets between them
+///
+/// Every call to `tick` gives each machine one turn, in address order. A turn lasts until the
+/// machine sends a packet, asks for input, halts, or has executed a time slice of instructions.
+/// Packets the machines send go through the interceptors, in the order they were added. Then
+/// packets sent to an address inside the network are queued for that machine; packets sent to
+/// the NAT are handed to it; packets sent anywhere else are returned to the caller.
+pub struct Scheduler<M = DenseMemory<i64>> {
+    machines: Vec<Machine<M>>,
+    interceptors: Vec<Box<dyn Interceptor>>,
+    nat: Option<(i64, Box<dyn Nat>)>,
+    nat_deliveries: Vec<Packet>,
+}
+
+impl Scheduler {
+    /// Boot `count` machines running the same program, with addresses 0 through `count - 1`
+    pub fn boot(program: &[i64], count: usize) -> Self {
+        Scheduler::new(
+            (0..count)
+                .map(|_| IntcodeProcess::from_vec(program.to_vec()))
+                .collect(),
+        )
+    }
+}
+
+impl<M: MemoryBackend<i64>> Scheduler<M> {
+    /// Create a network from the given processes, giving each its index as its address
+    pub fn new(processes: Vec<IntcodeProcess<i64, M>>) -> Self {
+        let machines = processes
+            .into_iter()
+            .enumerate()
+            .map(|(address, mut process)| {
+                process.add_input(
+                    i64::from_address(address).expect("Machine addresses fit in an i64"),
+                );
+                process.set_default_input(Some(NO_PACKET));
+                Machine {
+                    process,
+                    partial: Vec::with_capacity(3),
+                    idle_polls: 0,
+                    halted: false,
+                }
+            })
+            .collect();
+
+        Scheduler {
+            machines,
+            interceptors: Vec::new(),
+            nat: None,
+            nat_deliveries: Vec::new(),
+        }
+    }
+
+    /// Add an interceptor, which sees packets after the interceptors that were added before it
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(Box::new(interceptor));
+    }
+
+    /// Install a NAT that receives the packets sent to `address`, replacing any previous one
+    pub fn set_nat(&mut self, address: i64, nat: impl Nat + 'static) {
+        self.nat = Some((address, Box::new(nat)));
+    }
+
+    /// Get every packet the NAT has sent to machine 0, in order
+    pub fn nat_deliveries(&self) -> &[Packet] {
+        &self.nat_deliveries[..]
+    }
+
+    /// Get the number of machines in the network
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Whether the network has no machines
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+
+    /// Get the process for the machine at the given address
+    pub fn process(&self, address: usize) -> Option<&IntcodeProcess<i64, M>> {
+        self.machines.get(address).map(|machine| &machine.process)
+    }
+
+    /// Queue a packet for the machine it is addressed to
+    ///
+    /// Returns the packet back if its destination isn't in the network.
+    pub fn send(&mut self, packet: Packet) -> Result<(), Packet> {
+        let machine = match usize::try_from(packet.destination) {
+            Ok(destination) => self.machines.get_mut(destination),
+            Err(_) => None,
+        };
+
+        match machine {
+            Some(machine) => {
+                machine.process.add_input(packet.x);
+                machine.process.add_input(packet.y);
+                machine.idle_polls = 0;
+                Ok(())
+            }
+            None => Err(packet),
+        }
+    }
+
+    /// Give every machine one turn, and return the packets sent outside of the network
+    ///
+    /// If the network is idle after the turns and a NAT is installed, the NAT is woken up.
+    pub fn tick(&mut self) -> Result<Vec<Packet>, IntcodeError> {
+        let mut outgoing = Vec::new();
+
+        for address in 0..self.machines.len() {
+            let packet = self.run_until_event(address)?;
+            let packet = packet.and_then(|packet| {
+                self.interceptors
+                    .iter_mut()
+                    .try_fold(packet, |packet, interceptor| interceptor.intercept(packet))
+            });
+            if let Some(packet) = packet {
+                match self.nat.as_mut() {
+                    Some((nat_address, nat)) if *nat_address == packet.destination => {
+                        nat.receive(packet)
+                    }
+                    _ => {
+                        if let Err(packet) = self.send(packet) {
+                            outgoing.push(packet);
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.is_idle() {
+            let packet = self.nat.as_mut().and_then(|(_, nat)| nat.wake());
+            if let Some(packet) = packet {
+                let packet = Packet {
+                    destination: 0,
+                    ..packet
+                };
+                self.nat_deliveries.push(packet);
+                let _ = self.send(packet);
+            }
+        }
+
+        Ok(outgoing)
+    }
+
+    /// Run until the NAT sends machine 0 the same Y value twice in a row, and return that packet
+    ///
+    /// This is the answer to day 23 part 2. Returns `None` if every machine halts first, or the
+    /// network goes idle while the NAT has nothing to send.
+    pub fn run_until_repeated_nat_y(&mut self) -> Result<Option<Packet>, IntcodeError> {
+        loop {
+            let delivered = self.nat_deliveries.len();
+            self.tick()?;
+
+            if self.nat_deliveries.len() > delivered {
+                if let [.., previous, last] = self.nat_deliveries[..] {
+                    if previous.y == last.y {
+                        return Ok(Some(last));
+                    }
+                }
+            } else if self.is_idle() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Whether every machine is waiting for a packet, and none are on their way
+    ///
+    /// A machine is waiting once it has asked for input repeatedly with nothing waiting. Halted
+    /// machines count as idle.
+    pub fn is_idle(&self) -> bool {
+        self.machines
+            .iter()
+            .all(|machine| machine.halted || machine.idle_polls >= IDLE_POLLS)
+    }
+
+    /// Run the machine at `address` until it sends a packet, asks for input, halts, or uses up
+    /// its time slice
+    fn run_until_event(&mut self, address: usize) -> Result<Option<Packet>, IntcodeError> {
+        let machine = &mut self.machines[address];
+        if machine.halted {
+            return Ok(None);
+        }
+        machine.process.preempt_after(TIME_SLICE);
+
+        loop {
+            // With nothing queued, an input instruction reads the default input
+            let polling = machine.process.queued_inputs().next().is_none()
+                && matches!(
+                    machine.process.peek_instruction(),
+                    Ok(PeekedInstruction {
+                        instruction: Instruction::Input(_),
+                        ..
+                    })
+                );
+            match machine.process.step() {
+                Ok(Some(output)) => {
+                    machine.idle_polls = 0;
+                    machine.partial.push(output);
+                    if machine.partial.len() == 3 {
+                        let packet = Packet {
+                            destination: machine.partial[0],
+                            x: machine.partial[1],
+                            y: machine.partial[2],
+                        };
+                        machine.partial.clear();
+                        return Ok(Some(packet));
+                    }
+                }
+                Ok(None) if polling => {
+                    machine.idle_polls += 1;
+                    return Ok(None);
+                }
+                Ok(None) => {}
+                Err(IntcodeError::Preempted) => {
+                    machine.idle_polls = 0;
+                    return Ok(None);
+                }
+                Err(IntcodeError::CatchFire) => {
+                    machine.halted = true;
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Sends (address + 1, address, 7) after booting, then forwards every packet it receives to
+    /// address 255
+    const FORWARDER: &[i64] = &[
+        3, 100, 1001, 100, 1, 103, 4, 103, 4, 100, 104, 7, 3, 101, 1008, 101, -1, 104, 1005, 104,
+        12, 3, 102, 104, 255, 4, 101, 4, 102, 1105, 1, 12, 99,
+    ];
+
+    fn forwarder() -> Vec<i64> {
+        let mut program = FORWARDER.to_vec();
+        program.resize(105, 0);
+        program
+    }
+
+    #[test]
+    fn test_scheduler() {
+        let mut scheduler = Scheduler::boot(&forwarder(), 2);
+        assert_eq!(scheduler.len(), 2);
+        assert!(!scheduler.is_idle());
+
+        let mut outgoing = Vec::new();
+        while !scheduler.is_idle() {
+            outgoing.extend(scheduler.tick().unwrap());
+        }
+
+        assert_eq!(
+            outgoing,
+            vec![
+                Packet {
+                    destination: 2,
+                    x: 1,
+                    y: 7
+                },
+                Packet {
+                    destination: 255,
+                    x: 0,
+                    y: 7
+                },
+            ]
+        );
+
+        // Idle machines read the default input, so nothing is left queued ahead of new packets
+        assert!(scheduler.machines.iter().all(|machine| machine
+            .process
+            .queued_inputs()
+            .next()
+            .is_none()));
+
+        let packet = Packet {
+            destination: 0,
+            x: 5,
+            y: 6,
+        };
+        assert_eq!(scheduler.send(packet), Ok(()));
+        assert!(!scheduler.is_idle());
+        assert_eq!(
+            scheduler.tick().unwrap(),
+            vec![Packet {
+                destination: 255,
+                x: 5,
+                y: 6
+            }]
+        );
+    }
+
+    #[test]
+    fn test_interceptors() {
+        let mut scheduler = Scheduler::boot(&forwarder(), 2);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let logged = log.clone();
+        scheduler.add_interceptor(move |packet| {
+            logged.borrow_mut().push(packet);
+            Some(packet)
+        });
+        scheduler.add_interceptor(|packet: Packet| match packet.destination {
+            2 => None,
+            _ => Some(packet),
+        });
+
+        let mut outgoing = Vec::new();
+        while !scheduler.is_idle() {
+            outgoing.extend(scheduler.tick().unwrap());
+        }
+        assert_eq!(
+            outgoing,
+            vec![Packet {
+                destination: 255,
+                x: 0,
+                y: 7
+            }]
+        );
+        assert_eq!(
+            log.borrow()
+                .iter()
+                .map(|packet| packet.destination)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 255]
+        );
+    }
+
+    #[test]
+    fn test_time_slice() {
+        // Machine 0 loops forever without sending or receiving, but machine 1 still gets a turn
+        let spinner = IntcodeProcess::from_vec(vec![3, 100, 1105, 1, 2]);
+        let mut scheduler = Scheduler::new(vec![spinner, IntcodeProcess::from_vec(forwarder())]);
+        assert_eq!(
+            scheduler.tick().unwrap(),
+            vec![Packet {
+                destination: 2,
+                x: 1,
+                y: 7
+            }]
+        );
+        assert!(!scheduler.is_idle());
+    }
+
+    #[test]
+    fn test_nat() {
+        // Machine 1 forwards everything to the NAT at 255, which keeps sending it to machine 0,
+        // which forwards it again
+        let mut scheduler = Scheduler::boot(&forwarder(), 2);
+        scheduler.set_nat(255, LastPacketNat::new());
+
+        let mut outgoing = Vec::new();
+        let repeated = loop {
+            if let Some(packet) = scheduler.nat_deliveries().get(1) {
+                break *packet;
+            }
+            outgoing.extend(scheduler.tick().unwrap());
+        };
+
+        assert_eq!(
+            outgoing,
+            vec![Packet {
+                destination: 2,
+                x: 1,
+                y: 7
+            }]
+        );
+        assert_eq!(
+            repeated,
+            Packet {
+                destination: 0,
+                x: 0,
+                y: 7
+            }
+        );
+
+        let mut scheduler = Scheduler::boot(&forwarder(), 2);
+        scheduler.set_nat(255, LastPacketNat::new());
+        assert_eq!(scheduler.run_until_repeated_nat_y(), Ok(Some(repeated)));
+        assert_eq!(scheduler.nat_deliveries().len(), 2);
+
+        let mut scheduler = Scheduler::boot(&forwarder(), 1);
+        scheduler.set_nat(255, LastPacketNat::new());
+        assert_eq!(scheduler.run_until_repeated_nat_y(), Ok(None));
+    }
+}
@@ -0,0 +1,122 @@
+//! Save the state of a process to disk and load it back later
+//!
+//! A snapshot file is the magic bytes `ICVM`, a little-endian `u32` format version, and then the
+//! bincode-encoded state of the process.
+
+use crate::{IntcodeProcess, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"ICVM";
+const VERSION: u32 = 1;
+
+/// An error that can occur while saving or loading a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot failed
+    Io(io::Error),
+    /// The state of the process couldn't be encoded or decoded
+    Encoding(bincode::Error),
+    /// The data doesn't start with the snapshot magic bytes
+    NotASnapshot,
+    /// The snapshot was written with a format version this library doesn't understand
+    UnsupportedVersion(u32),
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(error: bincode::Error) -> Self {
+        SnapshotError::Encoding(error)
+    }
+}
+
+impl<T, M> IntcodeProcess<T, M>
+where
+    T: Value + Serialize + DeserializeOwned,
+    M: Serialize + DeserializeOwned,
+{
+    /// Write a snapshot of the process to the given writer
+    pub fn save_snapshot(&self, mut writer: impl Write) -> Result<(), SnapshotError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a snapshot of a process from the given reader
+    ///
+    /// Callbacks and input providers are not part of the snapshot and need to be registered
+    /// again.
+    pub fn load_snapshot(mut reader: impl Read) -> Result<Self, SnapshotError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    /// Save a snapshot of the process to the file at the given path
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        self.save_snapshot(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load a snapshot of a process from the file at the given path
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::load_snapshot(BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntcodeError;
+
+    #[test]
+    fn test_save_and_load() {
+        let input = vec![3, 11, 4, 11, 3, 11, 4, 11, 99, 0, 0, 0];
+        let mut process = IntcodeProcess::from_vec(input);
+        process.add_input(5);
+        assert_eq!(process.run(), Err(IntcodeError::NoInputAvailable));
+
+        let path = std::env::temp_dir().join(format!("intcode-snapshot-{}", std::process::id()));
+        process.save_to(&path).unwrap();
+        let mut restored: IntcodeProcess = IntcodeProcess::load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.instruction_counter(), 4);
+        assert_eq!(restored.outputs(), &[5]);
+        restored.add_input(6);
+        assert_eq!(restored.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(restored.outputs(), &[5, 6]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let result = IntcodeProcess::<i64>::load_snapshot(&b"JUNKJUNK"[..]);
+        assert!(matches!(result, Err(SnapshotError::NotASnapshot)));
+
+        let result = IntcodeProcess::<i64>::load_snapshot(&b"ICVM\x02\x00\x00\x00"[..]);
+        assert!(matches!(result, Err(SnapshotError::UnsupportedVersion(2))));
+
+        let result = IntcodeProcess::<i64>::load_snapshot(&b"ICVM\x01\x00\x00\x00"[..]);
+        assert!(matches!(result, Err(SnapshotError::Encoding(_))));
+    }
+}
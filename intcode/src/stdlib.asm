@@ -0,0 +1,102 @@
+; The intcode standard routine library, see src/stdlib.rs for the calling convention
+
+.global divmod, div, mod, print_num, memcpy, stack
+
+; Divide [rb+1] by [rb+2], leaving the quotient in [rb+1] and the remainder in [rb+2]
+divmod:
+    add 0, 0, [rb+8]
+    jnz 1, divmod_start
+
+; Divide [rb+1] by [rb+2], leaving the quotient in [rb+1]
+div:
+    add 1, 0, [rb+8]
+    jnz 1, divmod_start
+
+; Divide [rb+1] by [rb+2], leaving the remainder in [rb+1]
+mod:
+    add 2, 0, [rb+8]
+
+; Long division: subtract the largest doubling of the divisor that fits, until none do
+divmod_start:
+    add 0, 0, [rb+3]                ; quotient = 0
+divmod_outer:
+    lt [rb+1], [rb+2], [rb+6]
+    jnz [rb+6], divmod_done
+    add [rb+2], 0, [rb+4]           ; chunk = divisor
+    add 1, 0, [rb+5]                ; count = 1
+divmod_double:
+    add [rb+4], [rb+4], [rb+7]
+    lt [rb+1], [rb+7], [rb+6]
+    jnz [rb+6], divmod_subtract
+    add [rb+7], 0, [rb+4]           ; chunk *= 2
+    add [rb+5], [rb+5], [rb+5]      ; count *= 2
+    jnz 1, divmod_double
+divmod_subtract:
+    mul [rb+4], -1, [rb+7]
+    add [rb+1], [rb+7], [rb+1]      ; dividend -= chunk
+    add [rb+3], [rb+5], [rb+3]      ; quotient += count
+    jnz 1, divmod_outer
+divmod_done:
+    eq [rb+8], 2, [rb+6]
+    jnz [rb+6], divmod_return
+    add [rb+1], 0, [rb+2]           ; remainder
+    add [rb+3], 0, [rb+1]           ; quotient
+divmod_return:
+    ret
+
+; Output [rb+1] as decimal ASCII digits
+print_num:
+    lt [rb+1], 0, [rb+4]
+    jz [rb+4], print_num_positive
+    out 45                          ; -
+    mul [rb+1], -1, [rb+1]
+print_num_positive:
+    add 1, 0, [rb+2]                ; place = 1
+print_num_grow:
+    mul [rb+2], 10, [rb+5]
+    lt [rb+1], [rb+5], [rb+4]
+    jnz [rb+4], print_num_digit
+    add [rb+5], 0, [rb+2]           ; place *= 10 while it fits
+    jnz 1, print_num_grow
+print_num_digit:
+    add 48, 0, [rb+3]               ; digit = '0'
+print_num_count:
+    lt [rb+1], [rb+2], [rb+4]
+    jnz [rb+4], print_num_emit
+    mul [rb+2], -1, [rb+6]
+    add [rb+1], [rb+6], [rb+1]      ; number -= place
+    add [rb+3], 1, [rb+3]           ; digit += 1
+    jnz 1, print_num_count
+print_num_emit:
+    out [rb+3]
+    eq [rb+2], 1, [rb+4]
+    jnz [rb+4], print_num_done
+    add 1, 0, [rb+5]
+print_num_shrink:
+    mul [rb+5], 10, [rb+6]
+    eq [rb+6], [rb+2], [rb+4]
+    jnz [rb+4], print_num_next
+    add [rb+6], 0, [rb+5]
+    jnz 1, print_num_shrink
+print_num_next:
+    add [rb+5], 0, [rb+2]           ; place /= 10
+    jnz 1, print_num_digit
+print_num_done:
+    ret
+
+; Copy [rb+3] values from address [rb+1] to address [rb+2], lowest address first
+memcpy:
+    jz [rb+3], memcpy_done
+    add [rb+1], 0, [memcpy_move+1]  ; patch the addresses into the move
+    add [rb+2], 0, [memcpy_move+3]
+memcpy_move:
+    add [0], 0, [0]
+    add [rb+1], 1, [rb+1]
+    add [rb+2], 1, [rb+2]
+    add [rb+3], -1, [rb+3]
+    jnz 1, memcpy
+memcpy_done:
+    ret
+
+; The stack starts after everything else
+stack:
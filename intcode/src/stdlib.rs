@@ -0,0 +1,121 @@
+//! A small library of routines for hand-written intcode assembly
+//!
+//! The routines follow the calling convention described in [`crate::asm`]: arguments and results
+//! go in `[rb+1]`, `[rb+2]`, and so on, and a routine may overwrite anything up to `[rb+8]`.
+//!
+//! The library is its own module, linked after the program, and a program starts with
+//! `arb stack` to put the stack after everything else. The routines are:
+//!
+//! - `divmod`, `div`, and `mod`: divide `[rb+1]` by `[rb+2]`; `divmod` leaves the quotient in
+//!   `[rb+1]` and the remainder in `[rb+2]`, `div` and `mod` leave just one of them in `[rb+1]`.
+//!   Both values must not be negative, and the divisor must not be zero.
+//! - `print_num`: output `[rb+1]` as decimal ASCII digits, for values smaller than 10^18
+//! - `memcpy`: copy `[rb+3]` values from address `[rb+1]` to address `[rb+2]`
+//!
+//! ```
+//! use intcode::stdlib::assemble_with_stdlib;
+//!
+//! let source = "
+//!     arb stack
+//!     add 1234, 0, [rb+1]
+//!     call print_num
+//!     halt
+//! ";
+//! let program = assemble_with_stdlib(source).unwrap();
+//! let outputs = intcode::run(&program, &[]).unwrap();
+//! assert_eq!(outputs, vec![49, 50, 51, 52]);
+//! ```
+
+use crate::asm::{assemble_object, Object};
+use crate::link::{link, LinkError};
+
+/// The library's source
+pub const SOURCE: &str = include_str!("stdlib.asm");
+
+/// Assemble the library, for linking with a program
+pub fn object() -> Object {
+    assemble_object(SOURCE).expect("the library assembles")
+}
+
+/// Assemble a program and link it with the library
+pub fn assemble_with_stdlib(source: &str) -> Result<Vec<i64>, LinkError> {
+    let program =
+        assemble_object(source).map_err(|error| LinkError::Assemble { module: 0, error })?;
+    link(&[program, object()])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run;
+
+    /// Run a program that uses the library and return its outputs
+    fn outputs(source: &str, inputs: &[i64]) -> Vec<i64> {
+        run(&assemble_with_stdlib(source).unwrap(), inputs).unwrap()
+    }
+
+    #[test]
+    fn test_division() {
+        let source = "
+            arb stack
+            in [rb+1]
+            in [rb+2]
+            call divmod
+            out [rb+1]
+            out [rb+2]
+            in [rb+1]
+            in [rb+2]
+            call div
+            out [rb+1]
+            in [rb+1]
+            in [rb+2]
+            call mod
+            out [rb+1]
+            halt
+        ";
+        for &(a, b) in &[
+            (17, 5),
+            (0, 3),
+            (3, 7),
+            (1_000_000_007, 1),
+            (987_654_321, 1000),
+        ] {
+            assert_eq!(
+                outputs(source, &[a, b, a, b, a, b]),
+                vec![a / b, a % b, a / b, a % b]
+            );
+        }
+    }
+
+    #[test]
+    fn test_print_num() {
+        let source = "
+            arb stack
+            in [rb+1]
+            call print_num
+            halt
+        ";
+        for &n in &[0, 7, 10, -120, 1234567, 999_999_999_999_999_999] {
+            let printed: Vec<i64> = n.to_string().bytes().map(i64::from).collect();
+            assert_eq!(outputs(source, &[n]), printed);
+        }
+    }
+
+    #[test]
+    fn test_memcpy() {
+        let source = "
+            arb stack
+            add from, 0, [rb+1]
+            add to, 0, [rb+2]
+            add 3, 0, [rb+3]
+            call memcpy
+            out [to]
+            out [to+1]
+            out [to+2]
+            halt
+        from: .data 4, 5, 6
+        to:   .data 0, 0, 0
+        ";
+        assert_eq!(outputs(source, &[]), vec![4, 5, 6]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Connect a process's ASCII input and output to `std::io` readers and writers
+//!
+//! A [`ReadInput`] feeds every byte of a reader (a file, stdin, a TCP stream) to the input
+//! instruction, and [`write_output`] makes a callback for `IntcodeProcess::on_output` that writes
+//! every output to a writer.
+//!
+//! ```no_run
+//! use intcode::stream::{write_output, ReadInput};
+//! use intcode::IntcodeProcess;
+//! use std::io;
+//!
+//! let mut process = IntcodeProcess::from_vec(vec![3, 0, 4, 0, 99]);
+//! process.set_input_provider(ReadInput::new(io::stdin()));
+//! process.on_output(write_output(io::stdout()));
+//! process.run().unwrap_err();
+//! ```
+
+use crate::input::InputProvider;
+use crate::Value;
+use std::io::{self, Read, Write};
+
+/// An input provider that provides the bytes of a reader, one per input
+///
+/// When the reader reaches its end there is no more input, so the input instruction fails with
+/// `IntcodeError::NoInputAvailable`. The reader is read one byte at a time, so wrap unbuffered
+/// readers like files in a `BufReader`.
+///
+/// # Panics
+///
+/// The provider panics if reading fails, like the write side does.
+#[derive(Debug)]
+pub struct ReadInput<R> {
+    reader: R,
+}
+
+impl<R: Read> ReadInput<R> {
+    /// Provide input from the given reader
+    pub fn new(reader: R) -> Self {
+        ReadInput { reader }
+    }
+}
+
+impl<T: Value, R: Read> InputProvider<T> for ReadInput<R> {
+    fn next_input(&mut self) -> Option<T> {
+        let mut byte = [0];
+        loop {
+            match self.reader.read(&mut byte) {
+                Ok(0) => return None,
+                Ok(_) => return Some(T::from(byte[0])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => panic!("failed to read intcode input: {}", e),
+            }
+        }
+    }
+}
+
+/// Make an output callback that writes every output to the given writer
+///
+/// ASCII outputs are written as characters. Outputs outside of the ASCII range are written as
+/// numbers on their own line, since they're usually a puzzle's answer.
+///
+/// # Panics
+///
+/// The callback panics if writing fails, like `print!` does.
+pub fn write_output<T: Value>(mut writer: impl Write + 'static) -> impl FnMut(&T) {
+    move |value| {
+        write_ascii(&mut writer, value).expect("failed to write intcode output");
+    }
+}
+
+/// Write an output as an ASCII character, or as a number on its own line if it isn't ASCII
+pub(crate) fn write_ascii<T: Value>(mut writer: impl Write, value: &T) -> io::Result<()> {
+    match value.to_i64() {
+        Some(c) if (0..128).contains(&c) => write!(writer, "{}", c as u8 as char),
+        _ => writeln!(writer, "{}", value),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A writer that can be inspected after it's given away
+    #[derive(Clone, Default)]
+    struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        // Echo input until it runs out, then output 1000
+        let program = vec![3, 100, 4, 100, 1105, 1, 0];
+        let mut process = IntcodeProcess::from_vec(program);
+        let output = SharedWriter::default();
+        process.set_input_provider(ReadInput::new(&b"hi\n"[..]));
+        process.on_output(write_output(output.clone()));
+
+        assert_eq!(process.run(), Err(IntcodeError::NoInputAvailable));
+        process.store(1, 1000).unwrap();
+        process.store(0, 104).unwrap();
+        assert_eq!(process.step(), Ok(Some(1000)));
+
+        assert_eq!(&output.0.borrow()[..], b"hi\n1000\n");
+    }
+}
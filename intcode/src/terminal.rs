@@ -0,0 +1,115 @@
+//! Run ASCII intcode programs interactively by wiring their input and output to a terminal
+
+use crate::memory::MemoryBackend;
+use crate::stream::write_ascii;
+use crate::{IntcodeError, IntcodeProcess, Value};
+use std::io::{self, BufRead, Write};
+
+/// An error that can occur while running a process interactively
+#[derive(Debug)]
+pub enum TerminalError<T = i64> {
+    /// The process stopped with an error other than halting
+    Intcode(IntcodeError<T>),
+    /// Reading input, or writing output or the transcript, failed
+    Io(io::Error),
+    /// The process asked for input, but the input was exhausted
+    EndOfInput,
+}
+
+impl<T> From<io::Error> for TerminalError<T> {
+    fn from(error: io::Error) -> Self {
+        TerminalError::Io(error)
+    }
+}
+
+/// Run the process until it halts, printing its ASCII output and feeding it lines of input
+///
+/// Whenever the process asks for input and none is queued, a line is read from `input` and
+/// handed to the process (including the trailing newline). Every line read is also written to
+/// `transcript`, if given, so a session can be replayed later. Outputs outside of the ASCII
+/// range are written as numbers on their own line.
+pub fn run_interactive<T: Value, M: MemoryBackend<T>>(
+    process: &mut IntcodeProcess<T, M>,
+    mut input: impl BufRead,
+    mut output: impl Write,
+    mut transcript: Option<&mut dyn Write>,
+) -> Result<(), TerminalError<T>> {
+    loop {
+        match process.run_to_output() {
+            Ok(value) => write_ascii(&mut output, &value)?,
+            Err(IntcodeError::CatchFire) => break,
+            Err(IntcodeError::NoInputAvailable) => {
+                output.flush()?;
+
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    return Err(TerminalError::EndOfInput);
+                }
+                if !line.ends_with('\n') {
+                    line.push('\n');
+                }
+
+                if let Some(transcript) = transcript.as_mut() {
+                    transcript.write_all(line.as_bytes())?;
+                }
+                process.add_ascii_input(&line);
+            }
+            Err(e) => return Err(TerminalError::Intcode(e)),
+        }
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_interactive() {
+        // Print "Hi\n", then echo back two characters of input
+        let program = vec![
+            104, 72, 104, 105, 104, 10, 3, 15, 4, 15, 3, 15, 4, 15, 99, 0,
+        ];
+        let mut process = IntcodeProcess::from_vec(program);
+
+        let mut output = Vec::new();
+        let mut transcript = Vec::new();
+        let result = run_interactive(
+            &mut process,
+            &b"x\ny\n"[..],
+            &mut output,
+            Some(&mut transcript),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(String::from_utf8(output).unwrap(), "Hi\nx\n");
+        assert_eq!(String::from_utf8(transcript).unwrap(), "x\n");
+    }
+
+    #[test]
+    fn test_run_interactive_end_of_input() {
+        let program = vec![3, 3, 99, 0];
+        let mut process = IntcodeProcess::from_vec(program);
+
+        let result = run_interactive(&mut process, &b""[..], io::sink(), None);
+
+        match result {
+            Err(TerminalError::EndOfInput) => {}
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_interactive_large_output() {
+        let program = vec![104, 1000, 99];
+        let mut process = IntcodeProcess::from_vec(program);
+
+        let mut output = Vec::new();
+        let result = run_interactive(&mut process, &b""[..], &mut output, None);
+
+        assert!(result.is_ok());
+        assert_eq!(String::from_utf8(output).unwrap(), "1000\n");
+    }
+}
@@ -0,0 +1,79 @@
+//! Slow a process down to a steady pace, for watching it run
+//!
+//! Visualizations of programs like day 13's arcade cabinet are over before they start at full
+//! speed. `IntcodeProcess::set_steps_per_second` paces every step, however the process is run,
+//! so the observers and output callbacks that draw the screen are called at a watchable rate.
+//! A [`Throttle`] can also pace anything else.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Paces a loop to a number of iterations per second
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl Throttle {
+    /// Create a throttle that allows `per_second` iterations every second
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_second` is 0.
+    pub fn new(per_second: u32) -> Self {
+        assert!(per_second > 0, "a throttle needs a positive rate");
+        Throttle {
+            interval: Duration::from_secs(1) / per_second,
+            next: None,
+        }
+    }
+
+    /// Wait until the next iteration is allowed
+    ///
+    /// A loop that falls behind, for example while it waits for input, doesn't rush to catch
+    /// up; it carries on at the same pace from where it is.
+    pub fn wait(&mut self) {
+        let now = Instant::now();
+        let next = match self.next {
+            Some(next) if next > now => {
+                thread::sleep(next - now);
+                next
+            }
+            _ => now,
+        };
+        self.next = Some(next + self.interval);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntcodeProcess;
+
+    #[test]
+    fn test_throttle() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(200);
+        for _ in 0..5 {
+            throttle.wait();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        // Loop forever, at 1000 steps per second
+        let mut process = IntcodeProcess::from_vec(vec![1105, 1, 0]);
+        process.set_steps_per_second(Some(1000));
+        let start = Instant::now();
+        for _ in 0..51 {
+            process.step().unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        process.set_steps_per_second(None);
+        let start = Instant::now();
+        for _ in 0..1000 {
+            process.step().unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+}
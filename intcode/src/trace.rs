@@ -0,0 +1,138 @@
+//! Trace every memory access a process makes
+//!
+//! A trace shows where a program keeps its state. For example, tracing a game and looking at
+//! which addresses are written when the score changes finds where the score lives.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Whether a memory access was a read or a write
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AccessKind {
+    /// The process read the value at the address
+    Read,
+    /// The process wrote the value to the address
+    Write,
+}
+
+/// A single memory access made by the process
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryAccess<T> {
+    /// The number of the step that made the access, counting from 0 when tracing started
+    pub step: usize,
+    /// The address of the instruction that made the access
+    pub instruction: usize,
+    /// The address that was accessed
+    pub address: usize,
+    /// Whether the access was a read or a write
+    pub kind: AccessKind,
+    /// The value that was read or written
+    pub value: T,
+}
+
+/// Every memory access made by a process while tracing, in order
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryTrace<T> {
+    accesses: Vec<MemoryAccess<T>>,
+    /// The number of steps started since tracing started
+    steps: usize,
+}
+
+impl<T> MemoryTrace<T> {
+    /// Create an empty trace
+    pub fn new() -> Self {
+        MemoryTrace {
+            accesses: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    /// Get the recorded accesses
+    pub fn accesses(&self) -> &[MemoryAccess<T>] {
+        &self.accesses[..]
+    }
+
+    /// Get the number of steps started since tracing started
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub(crate) fn begin_step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub(crate) fn push(&mut self, instruction: usize, address: usize, kind: AccessKind, value: T) {
+        self.accesses.push(MemoryAccess {
+            step: self.steps.saturating_sub(1),
+            instruction,
+            address,
+            kind,
+            value,
+        });
+    }
+}
+
+impl<T> Default for MemoryTrace<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Display> MemoryTrace<T> {
+    /// Write the trace as CSV, with a header row
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "step,instruction,address,kind,value")?;
+        for access in &self.accesses {
+            let kind = match access.kind {
+                AccessKind::Read => "read",
+                AccessKind::Write => "write",
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                access.step, access.instruction, access.address, kind, access.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_trace() {
+        let mut process = IntcodeProcess::from_vec(vec![1001, 5, 2, 5, 99, 40]);
+        process.start_tracing();
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+
+        let trace = process.take_trace().unwrap();
+        assert_eq!(
+            trace.accesses()[2],
+            MemoryAccess {
+                step: 0,
+                instruction: 0,
+                address: 5,
+                kind: AccessKind::Read,
+                value: 40,
+            }
+        );
+
+        let mut csv = Vec::new();
+        trace.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "step,instruction,address,kind,value\n\
+             0,0,0,read,1001\n\
+             0,0,1,read,5\n\
+             0,0,5,read,40\n\
+             0,0,2,read,2\n\
+             0,0,3,read,5\n\
+             0,0,5,write,42\n\
+             1,4,4,read,99\n"
+        );
+        assert!(process.take_trace().is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Log every input and output of a run, along with the step it happened at
+//!
+//! A [`Transcript`] is an observer that writes a line to a writer, like a file, for every input
+//! a process consumes and every output it produces. Steps are counted from 1, for the first
+//! instruction the process executes after the transcript is added. In ASCII mode, values that
+//! are ASCII characters are followed by the character:
+//!
+//! ```text
+//! 1 out 72 'H'
+//! 3 out 10 '\n'
+//! 4 in 121 'y'
+//! ```
+
+use crate::observer::Observer;
+use crate::Value;
+use std::io::{self, Write};
+
+/// An observer that writes a line for every input and output
+///
+/// Writing can fail, but observers can't stop the process, so the transcript stops writing
+/// after the first error and keeps it, to be checked with `error` or `into_inner`.
+#[derive(Debug)]
+pub struct Transcript<W> {
+    writer: W,
+    ascii: bool,
+    step: u64,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Transcript<W> {
+    /// Write a transcript to the given writer, decoding ASCII characters if `ascii` is true
+    pub fn new(writer: W, ascii: bool) -> Self {
+        Transcript {
+            writer,
+            ascii,
+            step: 0,
+            error: None,
+        }
+    }
+
+    /// Get the error that stopped the transcript, if writing failed
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Get the writer back, or the error that stopped the transcript
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+
+    fn write_event<T: Value>(&mut self, direction: &str, value: &T) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let character = match value.to_i64() {
+            Some(value @ 0..=127) if self.ascii => Some(value as u8 as char),
+            _ => None,
+        };
+        let result = match character {
+            Some(character) => writeln!(
+                self.writer,
+                "{} {} {} {:?}",
+                self.step, direction, value, character
+            ),
+            None => writeln!(self.writer, "{} {} {}", self.step, direction, value),
+        };
+        self.error = result.err();
+    }
+}
+
+impl<T: Value, W: Write> Observer<T> for Transcript<W> {
+    fn on_step(&mut self, _address: usize) {
+        self.step += 1;
+    }
+
+    fn on_input(&mut self, value: &T) {
+        self.write_event("in", value);
+    }
+
+    fn on_output(&mut self, value: &T) {
+        self.write_event("out", value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn transcribe(ascii: bool) -> String {
+        // Print "H\n", read a character, and output it plus 1000
+        let program = vec![
+            104, 72, 1101, 0, 0, 0, 104, 10, 3, 17, 101, 1000, 17, 17, 4, 17, 99, 0,
+        ];
+        let mut process = IntcodeProcess::from_vec(program);
+        let transcript = Rc::new(RefCell::new(Transcript::new(Vec::new(), ascii)));
+        process.add_observer(transcript.clone());
+        process.add_input(121);
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        drop(process);
+
+        let transcript = Rc::try_unwrap(transcript).unwrap().into_inner();
+        String::from_utf8(transcript.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_transcript() {
+        assert_eq!(
+            transcribe(true),
+            "1 out 72 'H'\n3 out 10 '\\n'\n4 in 121 'y'\n6 out 1121\n"
+        );
+        assert_eq!(
+            transcribe(false),
+            "1 out 72\n3 out 10\n4 in 121\n6 out 1121\n"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Translate an intcode program into a standalone Rust function
+//!
+//! The generated function keeps the program counter in a variable and dispatches on it with a
+//! `match`, with one arm for every address that holds a valid instruction. Parameters are baked
+//! into the generated code, so this is only correct for programs that never write to their own
+//! instructions or parameters. Memory outside of the instructions can be used freely.
+//!
+//! The generated function looks like:
+//!
+//! ```text
+//! pub fn run(
+//!     memory: &mut Vec<i64>,
+//!     input: impl FnMut() -> Option<i64>,
+//!     output: impl FnMut(i64),
+//! ) -> Result<(), String>
+//! ```
+//!
+//! It returns `Ok(())` when the program halts, and an error if it segfaults, runs out of input,
+//! or jumps to an address that didn't hold an instruction when it was translated.
+
+use crate::{InputParameter, Instruction, OutputParameter};
+use std::fmt::Write;
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod samples;
+
+/// Translate the program into the source code of a Rust function with the given name
+pub fn transpile(program: &[i64], name: &str) -> String {
+    let mut arms = String::new();
+    for (address, &value) in program.iter().enumerate() {
+        let instruction = match Instruction::decode(value) {
+            Ok(instruction) => instruction,
+            Err(_) => continue,
+        };
+        let length = instruction.len();
+        if address + length > program.len() {
+            continue;
+        }
+
+        let parameters = &program[address + 1..address + length];
+        let body = translate(&instruction, address, parameters);
+        writeln!(arms, "            {} => {{", address).unwrap();
+        for line in body {
+            writeln!(arms, "                {}", line).unwrap();
+        }
+        writeln!(arms, "            }}").unwrap();
+    }
+
+    // Only declare the relative base when something uses it, so the generated code has no
+    // unused variable
+    let relative_base = match arms.contains("relative_base") {
+        true => "    let mut relative_base: i64 = 0;\n",
+        false => "",
+    };
+
+    format!(
+        r#"/// Generated from an intcode program by `intcode::transpile`
+#[allow(unused_mut, unused_parens, unused_variables, unreachable_code, clippy::all)]
+pub fn {name}(
+    memory: &mut Vec<i64>,
+    mut input: impl FnMut() -> Option<i64>,
+    mut output: impl FnMut(i64),
+) -> Result<(), String> {{
+    fn to_address(address: i64) -> Result<usize, String> {{
+        if address < 0 {{
+            return Err(format!("segfault at {{}}", address));
+        }}
+        Ok(address as usize)
+    }}
+
+    fn load(memory: &mut Vec<i64>, address: i64) -> Result<i64, String> {{
+        let address = to_address(address)?;
+        if address >= memory.len() {{
+            memory.resize(address + 1, 0);
+        }}
+        Ok(memory[address])
+    }}
+
+    fn store(memory: &mut Vec<i64>, address: i64, value: i64) -> Result<(), String> {{
+        let address = to_address(address)?;
+        if address >= memory.len() {{
+            memory.resize(address + 1, 0);
+        }}
+        memory[address] = value;
+        Ok(())
+    }}
+
+{relative_base}    let mut pc: i64 = 0;
+    loop {{
+        match pc {{
+{arms}            _ => return Err(format!("no instruction at {{}}", pc)),
+        }}
+    }}
+}}
+"#,
+        name = name,
+        relative_base = relative_base,
+        arms = arms,
+    )
+}
+
+/// Generate the statements that execute a single instruction
+fn translate(instruction: &Instruction, address: usize, parameters: &[i64]) -> Vec<String> {
+    let next = address + instruction.len();
+    let read = |index: usize, mode: &InputParameter| read(mode, parameters[index]);
+    let write = |index: usize, mode: &OutputParameter| write(mode, parameters[index]);
+
+    match instruction {
+        Instruction::Add(in0, in1, out) => vec![
+            format!("let value = {} + {};", read(0, in0), read(1, in1)),
+            format!("store(memory, {}, value)?;", write(2, out)),
+            format!("pc = {};", next),
+        ],
+        Instruction::Mul(in0, in1, out) => vec![
+            format!("let value = {} * {};", read(0, in0), read(1, in1)),
+            format!("store(memory, {}, value)?;", write(2, out)),
+            format!("pc = {};", next),
+        ],
+        Instruction::Input(out) => vec![
+            format!(
+                "let value = input().ok_or_else(|| String::from(\"no input available at {}\"))?;",
+                address
+            ),
+            format!("store(memory, {}, value)?;", write(0, out)),
+            format!("pc = {};", next),
+        ],
+        Instruction::Output(in0) => vec![
+            format!("output({});", read(0, in0)),
+            format!("pc = {};", next),
+        ],
+        Instruction::JumpIfTrue(in0, in1) => vec![format!(
+            "pc = if {} != 0 {{ {} }} else {{ {} }};",
+            read(0, in0),
+            read(1, in1),
+            next
+        )],
+        Instruction::JumpIfFalse(in0, in1) => vec![format!(
+            "pc = if {} == 0 {{ {} }} else {{ {} }};",
+            read(0, in0),
+            read(1, in1),
+            next
+        )],
+        Instruction::LessThan(in0, in1, out) => vec![
+            format!("let value = ({} < {}) as i64;", read(0, in0), read(1, in1)),
+            format!("store(memory, {}, value)?;", write(2, out)),
+            format!("pc = {};", next),
+        ],
+        Instruction::Equals(in0, in1, out) => vec![
+            format!("let value = ({} == {}) as i64;", read(0, in0), read(1, in1)),
+            format!("store(memory, {}, value)?;", write(2, out)),
+            format!("pc = {};", next),
+        ],
+        Instruction::RelativeMode(in0) => vec![
+            format!("relative_base += {};", read(0, in0)),
+            format!("pc = {};", next),
+        ],
+        Instruction::Halt => vec![String::from("return Ok(());")],
+    }
+}
+
+/// Generate an expression for the value of an input parameter
+fn read(mode: &InputParameter, parameter: i64) -> String {
+    match mode {
+        InputParameter::Position => format!("load(memory, {})?", parameter),
+        InputParameter::Immediate => format!("({})", parameter),
+        InputParameter::Relative => format!("load(memory, relative_base + ({}))?", parameter),
+    }
+}
+
+/// Generate an expression for the address an output parameter refers to
+fn write(mode: &OutputParameter, parameter: i64) -> String {
+    match mode {
+        OutputParameter::Position => format!("{}", parameter),
+        OutputParameter::Relative => format!("relative_base + ({})", parameter),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{IntcodeError, IntcodeProcess};
+
+    #[test]
+    fn test_transpile() {
+        let source = transpile(&[3, 9, 1001, 9, 5, 9, 4, 9, 99, 0], "add_five");
+
+        assert!(source.contains("pub fn add_five("));
+        assert!(source.contains(
+            "            0 => {\n                let value = input().ok_or_else(|| String::from(\"no input available at 0\"))?;\n                store(memory, 9, value)?;\n                pc = 2;\n            }\n"
+        ));
+        assert!(source.contains(
+            "            2 => {\n                let value = load(memory, 9)? + (5);\n                store(memory, 9, value)?;\n                pc = 6;\n            }\n"
+        ));
+        assert!(
+            source.contains("            8 => {\n                return Ok(());\n            }\n")
+        );
+        assert!(!source.contains("            9 => {"));
+    }
+
+    /// Run a program with the interpreter and with its transpiled function, and compare them
+    fn assert_same(program: &[i64], inputs: &[i64], transpiled: Transpiled) {
+        let mut memory = program.to_vec();
+        let mut queue = inputs.iter().copied();
+        let mut outputs = Vec::new();
+        transpiled(&mut memory, &mut || queue.next(), &mut |value| {
+            outputs.push(value)
+        })
+        .unwrap();
+
+        let mut process = IntcodeProcess::from_vec(program.to_vec());
+        process.add_inputs(inputs);
+        assert_eq!(process.run(), Err(IntcodeError::CatchFire));
+        assert_eq!(outputs, process.outputs());
+        assert_eq!(&memory[..], process.memory());
+    }
+
+    type Transpiled = fn(
+        &mut Vec<i64>,
+        &mut dyn FnMut() -> Option<i64>,
+        &mut dyn FnMut(i64),
+    ) -> Result<(), String>;
+
+    #[test]
+    fn test_samples() {
+        // The checked in samples are what the transpiler generates now
+        let sample_source = include_str!("transpile/samples.rs");
+        let compare = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        let quine = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        assert!(sample_source.contains(&transpile(&compare, "compare_to_eight")));
+        assert!(sample_source.contains(&transpile(&quine, "quine")));
+
+        // The relative base is only declared when it's used
+        assert!(!transpile(&compare, "compare_to_eight").contains("relative_base"));
+
+        for input in 6..=10 {
+            assert_same(&compare, &[input], |m, i, o| {
+                samples::compare_to_eight(m, i, o)
+            });
+        }
+        assert_same(&quine, &[], |m, i, o| samples::quine(m, i, o));
+    }
+}
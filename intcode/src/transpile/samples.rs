@@ -0,0 +1,213 @@
+//! Transpiled sample programs, checked against the interpreter by `transpile::test`
+//!
+//! Regenerate with `cargo run --bin intcode-transpile -- <program> --name <name>` if the
+//! transpiler's output changes.
+
+/// Generated from an intcode program by `intcode::transpile`
+#[allow(unused_mut, unused_parens, unused_variables, unreachable_code, clippy::all)]
+pub fn compare_to_eight(
+    memory: &mut Vec<i64>,
+    mut input: impl FnMut() -> Option<i64>,
+    mut output: impl FnMut(i64),
+) -> Result<(), String> {
+    fn to_address(address: i64) -> Result<usize, String> {
+        if address < 0 {
+            return Err(format!("segfault at {}", address));
+        }
+        Ok(address as usize)
+    }
+
+    fn load(memory: &mut Vec<i64>, address: i64) -> Result<i64, String> {
+        let address = to_address(address)?;
+        if address >= memory.len() {
+            memory.resize(address + 1, 0);
+        }
+        Ok(memory[address])
+    }
+
+    fn store(memory: &mut Vec<i64>, address: i64, value: i64) -> Result<(), String> {
+        let address = to_address(address)?;
+        if address >= memory.len() {
+            memory.resize(address + 1, 0);
+        }
+        memory[address] = value;
+        Ok(())
+    }
+
+    let mut pc: i64 = 0;
+    loop {
+        match pc {
+            0 => {
+                let value = input().ok_or_else(|| String::from("no input available at 0"))?;
+                store(memory, 21, value)?;
+                pc = 2;
+            }
+            2 => {
+                let value = (load(memory, 21)? == (8)) as i64;
+                store(memory, 20, value)?;
+                pc = 6;
+            }
+            4 => {
+                let value = (load(memory, 20)? == load(memory, 1005)?) as i64;
+                store(memory, 20, value)?;
+                pc = 8;
+            }
+            6 => {
+                pc = if load(memory, 20)? != 0 { (22) } else { 9 };
+            }
+            9 => {
+                let value = ((8) < load(memory, 21)?) as i64;
+                store(memory, 20, value)?;
+                pc = 13;
+            }
+            10 => {
+                let value = (load(memory, 21)? == load(memory, 20)?) as i64;
+                store(memory, 1006, value)?;
+                pc = 14;
+            }
+            13 => {
+                pc = if load(memory, 20)? == 0 { (31) } else { 16 };
+            }
+            16 => {
+                pc = if (0) == 0 { (36) } else { 19 };
+            }
+            22 => {
+                let value = load(memory, 21)? * (125);
+                store(memory, 20, value)?;
+                pc = 26;
+            }
+            26 => {
+                output(load(memory, 20)?);
+                pc = 28;
+            }
+            28 => {
+                pc = if (1) != 0 { (46) } else { 31 };
+            }
+            29 => {
+                let value = load(memory, 46)? + load(memory, 104)?;
+                store(memory, 999, value)?;
+                pc = 33;
+            }
+            31 => {
+                output((999));
+                pc = 33;
+            }
+            32 => {
+                return Ok(());
+            }
+            33 => {
+                pc = if (1) != 0 { (46) } else { 36 };
+            }
+            34 => {
+                let value = load(memory, 46)? + load(memory, 1101)?;
+                store(memory, 1000, value)?;
+                pc = 38;
+            }
+            36 => {
+                let value = (1000) + (1);
+                store(memory, 20, value)?;
+                pc = 40;
+            }
+            38 => {
+                let value = load(memory, 20)? + load(memory, 4)?;
+                store(memory, 20, value)?;
+                pc = 42;
+            }
+            40 => {
+                output(load(memory, 20)?);
+                pc = 42;
+            }
+            42 => {
+                pc = if (1) != 0 { (46) } else { 45 };
+            }
+            43 => {
+                let value = load(memory, 46)? + load(memory, 98)?;
+                store(memory, 99, value)?;
+                pc = 47;
+            }
+            46 => {
+                return Ok(());
+            }
+            _ => return Err(format!("no instruction at {}", pc)),
+        }
+    }
+}
+
+/// Generated from an intcode program by `intcode::transpile`
+#[allow(unused_mut, unused_parens, unused_variables, unreachable_code, clippy::all)]
+pub fn quine(
+    memory: &mut Vec<i64>,
+    mut input: impl FnMut() -> Option<i64>,
+    mut output: impl FnMut(i64),
+) -> Result<(), String> {
+    fn to_address(address: i64) -> Result<usize, String> {
+        if address < 0 {
+            return Err(format!("segfault at {}", address));
+        }
+        Ok(address as usize)
+    }
+
+    fn load(memory: &mut Vec<i64>, address: i64) -> Result<i64, String> {
+        let address = to_address(address)?;
+        if address >= memory.len() {
+            memory.resize(address + 1, 0);
+        }
+        Ok(memory[address])
+    }
+
+    fn store(memory: &mut Vec<i64>, address: i64, value: i64) -> Result<(), String> {
+        let address = to_address(address)?;
+        if address >= memory.len() {
+            memory.resize(address + 1, 0);
+        }
+        memory[address] = value;
+        Ok(())
+    }
+
+    let mut relative_base: i64 = 0;
+    let mut pc: i64 = 0;
+    loop {
+        match pc {
+            0 => {
+                relative_base += (1);
+                pc = 2;
+            }
+            1 => {
+                let value = load(memory, 204)? + load(memory, -1)?;
+                store(memory, 1001, value)?;
+                pc = 5;
+            }
+            2 => {
+                output(load(memory, relative_base + (-1))?);
+                pc = 4;
+            }
+            4 => {
+                let value = load(memory, 100)? + (1);
+                store(memory, 100, value)?;
+                pc = 8;
+            }
+            6 => {
+                let value = load(memory, 100)? + load(memory, 1008)?;
+                store(memory, 100, value)?;
+                pc = 10;
+            }
+            8 => {
+                let value = (load(memory, 100)? == (16)) as i64;
+                store(memory, 101, value)?;
+                pc = 12;
+            }
+            11 => {
+                let value = (1006) + load(memory, 101)?;
+                store(memory, 0, value)?;
+                pc = 15;
+            }
+            12 => {
+                pc = if load(memory, 101)? == 0 { (0) } else { 15 };
+            }
+            15 => {
+                return Ok(());
+            }
+            _ => return Err(format!("no instruction at {}", pc)),
+        }
+    }
+}
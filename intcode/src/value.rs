@@ -0,0 +1,121 @@
+//! The types of values that can be stored in the memory cells of an intcode process
+
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Mul};
+
+/// A type that can be used as the value of a memory cell
+///
+/// Implemented for the primitive signed integers, and for `num_bigint::BigInt` when the `bigint`
+/// feature is enabled, for programs whose values don't fit into 64 bits.
+pub trait Value:
+    Clone + Debug + Display + Eq + Ord + From<u8> + Add<Output = Self> + Mul<Output = Self>
+{
+    /// Convert the value to an `i64`, if it fits
+    fn to_i64(&self) -> Option<i64>;
+
+    /// Convert the value to an index into the memory space, if it is a valid address
+    fn to_address(&self) -> Option<usize>;
+
+    /// Convert an index into the memory space to a value, if it fits
+    fn from_address(address: usize) -> Option<Self>;
+
+    /// Convert a count, like the number of steps a process has run, to a value, saturating if
+    /// it doesn't fit
+    fn saturating_from_count(count: usize) -> Self;
+
+    /// Add two values, returning `None` if the result doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// Multiply two values, returning `None` if the result doesn't fit
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_value_for_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl Value for $ty {
+                fn to_i64(&self) -> Option<i64> {
+                    i64::try_from(*self).ok()
+                }
+
+                fn to_address(&self) -> Option<usize> {
+                    usize::try_from(*self).ok()
+                }
+
+                fn from_address(address: usize) -> Option<Self> {
+                    <$ty>::try_from(address).ok()
+                }
+
+                fn saturating_from_count(count: usize) -> Self {
+                    <$ty>::try_from(count).unwrap_or(<$ty>::MAX)
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *other)
+                }
+            }
+        )*
+    };
+}
+
+impl_value_for_primitive!(i32, i64, i128, isize);
+
+#[cfg(feature = "bigint")]
+impl Value for num_bigint::BigInt {
+    fn to_i64(&self) -> Option<i64> {
+        num_traits::ToPrimitive::to_i64(self)
+    }
+
+    fn to_address(&self) -> Option<usize> {
+        num_traits::ToPrimitive::to_usize(self)
+    }
+
+    fn from_address(address: usize) -> Option<Self> {
+        Some(num_bigint::BigInt::from(address))
+    }
+
+    fn saturating_from_count(count: usize) -> Self {
+        num_bigint::BigInt::from(count)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_address() {
+        assert_eq!(5_i64.to_address(), Some(5));
+        assert_eq!((-1_i64).to_address(), None);
+        assert_eq!(i128::MAX.to_address(), None);
+        assert_eq!(i64::from_address(5), Some(5));
+        assert_eq!(i32::from_address(usize::MAX), None);
+        assert_eq!(i32::saturating_from_count(usize::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(Value::checked_add(&1_i64, &2), Some(3));
+        assert_eq!(Value::checked_add(&i64::MAX, &1), None);
+        assert_eq!(Value::checked_mul(&i32::MAX, &2), None);
+    }
+
+    #[test]
+    fn test_to_i64() {
+        assert_eq!(5_i32.to_i64(), Some(5));
+        assert_eq!(i128::MAX.to_i64(), None);
+    }
+}
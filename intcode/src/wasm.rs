@@ -0,0 +1,133 @@
+//! JavaScript bindings for running intcode in a browser through `wasm-bindgen`
+
+use crate::{IntcodeError, IntcodeProcess};
+use wasm_bindgen::prelude::*;
+
+/// What the process did after it was stepped or run
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Status {
+    /// The process executed an instruction and can keep going
+    Running,
+    /// The process produced an output
+    Output,
+    /// The process needs more input before it can continue
+    NeedsInput,
+    /// The process halted
+    Halted,
+}
+
+/// An intcode process that can be driven from JavaScript
+#[wasm_bindgen]
+pub struct Process {
+    inner: IntcodeProcess,
+}
+
+#[wasm_bindgen]
+impl Process {
+    /// Create a new process with the given program
+    #[wasm_bindgen(constructor)]
+    pub fn new(program: &[i64]) -> Process {
+        Process {
+            inner: IntcodeProcess::from_vec(program.to_vec()),
+        }
+    }
+
+    /// Create a new process from comma-separated program text
+    #[wasm_bindgen(js_name = fromText)]
+    pub fn from_text(text: &str) -> Result<Process, JsError> {
+        let program = text
+            .trim()
+            .split(',')
+            .map(|s| s.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Process::new(&program))
+    }
+
+    /// Execute the next instruction
+    pub fn step(&mut self) -> Result<Status, JsError> {
+        status(self.inner.step())
+    }
+
+    /// Execute instructions until the process halts or needs input
+    pub fn run(&mut self) -> Result<Status, JsError> {
+        loop {
+            match status(self.inner.step())? {
+                Status::Running | Status::Output => {}
+                stopped => return Ok(stopped),
+            }
+        }
+    }
+
+    /// Add a value to the input queue
+    #[wasm_bindgen(js_name = addInput)]
+    pub fn add_input(&mut self, value: i64) {
+        self.inner.add_input(value);
+    }
+
+    /// Add a string to the input queue, one character at a time
+    #[wasm_bindgen(js_name = addAsciiInput)]
+    pub fn add_ascii_input(&mut self, input: &str) {
+        self.inner.add_ascii_input(input);
+    }
+
+    /// Get the outputs that haven't been drained
+    pub fn outputs(&self) -> Vec<i64> {
+        self.inner.outputs().to_vec()
+    }
+
+    /// Remove and return the outputs produced since the last drain
+    #[wasm_bindgen(js_name = drainOutputs)]
+    pub fn drain_outputs(&mut self) -> Vec<i64> {
+        self.inner.drain_outputs().collect()
+    }
+
+    /// Get a copy of the memory
+    pub fn memory(&self) -> Vec<i64> {
+        self.inner.memory().to_vec()
+    }
+
+    /// Get the current instruction counter
+    #[wasm_bindgen(js_name = instructionCounter)]
+    pub fn instruction_counter(&self) -> usize {
+        self.inner.instruction_counter()
+    }
+
+    /// Get the current relative base
+    #[wasm_bindgen(js_name = relativeBase)]
+    pub fn relative_base(&self) -> i64 {
+        self.inner.relative_base()
+    }
+}
+
+fn status(result: Result<Option<i64>, IntcodeError>) -> Result<Status, JsError> {
+    match result {
+        Ok(Some(_)) => Ok(Status::Output),
+        Ok(None) => Ok(Status::Running),
+        Err(IntcodeError::NoInputAvailable) => Ok(Status::NeedsInput),
+        Err(IntcodeError::CatchFire) => Ok(Status::Halted),
+        Err(e) => Err(JsError::new(&format!("{:?}", e))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_process() {
+        let mut process = Process::from_text("3,9,4,9,104,7,99,0,0,0").unwrap_or_else(|_| panic!());
+
+        assert_eq!(
+            process.run().unwrap_or_else(|_| panic!()),
+            Status::NeedsInput
+        );
+        process.add_input(5);
+        assert_eq!(process.step().unwrap_or_else(|_| panic!()), Status::Running);
+        assert_eq!(process.step().unwrap_or_else(|_| panic!()), Status::Output);
+        assert_eq!(process.instruction_counter(), 4);
+        assert_eq!(process.run().unwrap_or_else(|_| panic!()), Status::Halted);
+        assert_eq!(process.drain_outputs(), vec![5, 7]);
+        assert!(process.outputs().is_empty());
+    }
+}